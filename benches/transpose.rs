@@ -0,0 +1,121 @@
+//! Throughput of the square transposes and of `matrix::transpose_blocked`.
+//!
+//! The square transposes compare the portable `const fn` kernels with the `*_in_place`
+//! variants, which use SSE2/AVX2 on `x86_64`. The blocked transpose is compared with a naive
+//! per-bit loop.
+//!
+//! Run with `cargo bench --bench transpose`.
+#![allow(clippy::print_stdout)]
+
+use std::time::{Duration, Instant};
+
+use bittersweet::matrix::{
+    row_words, transpose128x128, transpose128x128_in_place, transpose64x64,
+    transpose64x64_in_place, transpose_blocked,
+};
+
+fn naive_transpose(input: &[u64], rows: usize, cols: usize, output: &mut [u64]) {
+    let in_stride = row_words(cols);
+    let out_stride = row_words(rows);
+    for word in output.iter_mut() {
+        *word = 0;
+    }
+    for r in 0..rows {
+        for c in 0..cols {
+            if input[r * in_stride + c / 64] & (1 << (63 - c % 64)) != 0 {
+                output[c * out_stride + r / 64] |= 1 << (63 - r % 64);
+            }
+        }
+    }
+}
+
+fn pseudo_random_matrix(rows: usize, cols: usize) -> Vec<u64> {
+    let mut state = 0x9e3779b97f4a7c15_u64;
+    (0..rows * row_words(cols))
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        })
+        .collect()
+}
+
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    // Warm up once, then repeat until the measurement is long enough to be stable.
+    f();
+    let mut iterations = 0_u32;
+    let start = Instant::now();
+    while iterations < 3 || start.elapsed() < Duration::from_millis(500) {
+        f();
+        iterations += 1;
+    }
+    start.elapsed() / iterations
+}
+
+fn report(name: &str, rows: usize, cols: usize, elapsed: Duration) {
+    let bits = (rows * cols) as f64;
+    let gbits_per_sec = bits / elapsed.as_secs_f64() / 1e9;
+    println!(
+        "{:>8} {:>9}x{:<4} {:>12.3?} {:>8.3} Gbit/s",
+        name, rows, cols, elapsed, gbits_per_sec
+    );
+}
+
+fn bench_square() {
+    let input = pseudo_random_matrix(128, 128);
+    let mut a64 = [0_u64; 64];
+    a64.copy_from_slice(&input[..64]);
+    let mut a128 = [0_u128; 128];
+    for (row, words) in a128.iter_mut().zip(input.chunks(2)) {
+        *row = (words[0] as u128) << 64 | words[1] as u128;
+    }
+
+    let mut checksum = 0_u64;
+    let portable = measure(|| {
+        a64 = transpose64x64(a64);
+        checksum ^= a64[0];
+    });
+    report("portable", 64, 64, portable);
+    let in_place = measure(|| {
+        transpose64x64_in_place(&mut a64);
+        checksum ^= a64[0];
+    });
+    report("in-place", 64, 64, in_place);
+    let portable = measure(|| {
+        a128 = transpose128x128(a128);
+        checksum ^= a128[0] as u64;
+    });
+    report("portable", 128, 128, portable);
+    let in_place = measure(|| {
+        transpose128x128_in_place(&mut a128);
+        checksum ^= a128[0] as u64;
+    });
+    report("in-place", 128, 128, in_place);
+    println!("{:>8} {:016x}", "checksum", checksum);
+}
+
+fn main() {
+    bench_square();
+
+    let shapes = [(1 << 20, 64), (64, 1 << 20), (4096, 4096), (100_003, 129)];
+    for &(rows, cols) in shapes.iter() {
+        let input = pseudo_random_matrix(rows, cols);
+        let mut output = vec![0_u64; cols * row_words(rows)];
+
+        // Fold every result into a checksum that is printed, so no run can be optimized away.
+        let mut checksum = 0_u64;
+        let blocked = measure(|| {
+            transpose_blocked(&input, rows, cols, &mut output);
+            checksum ^= output.iter().fold(0, |acc, word| acc ^ word);
+        });
+        report("blocked", rows, cols, blocked);
+
+        let naive = measure(|| {
+            naive_transpose(&input, rows, cols, &mut output);
+            checksum ^= output.iter().fold(0, |acc, word| acc ^ word);
+        });
+        report("naive", rows, cols, naive);
+        println!("{:>8} {:016x}", "checksum", checksum);
+    }
+}
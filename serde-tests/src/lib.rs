@@ -0,0 +1,2 @@
+//! Round-trip tests for the `serde` feature of `bittersweet` through `serde_json`,
+//! `bincode` and `serde_test`. The tests live in `tests/`.
@@ -0,0 +1,205 @@
+use bittersweet::bitboard::{Bitboard16x16, Bitboard8x8};
+use bittersweet::bitline::Bitline;
+use bittersweet::compressed::CompressedBitmap;
+use serde::{Deserialize, Serialize};
+use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Token};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Masks {
+    #[serde(with = "bittersweet::serde::bits")]
+    a: u8,
+    #[serde(with = "bittersweet::serde::bits")]
+    b: u16,
+    #[serde(with = "bittersweet::serde::bit_string")]
+    c: u32,
+    #[serde(with = "bittersweet::serde::bit_bytes")]
+    d: u64,
+    #[serde(with = "bittersweet::serde::bits")]
+    e: u128,
+    plain: u16,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Matrices {
+    #[serde(with = "bittersweet::serde::bit_rows")]
+    small: [u8; 8],
+    #[serde(with = "bittersweet::serde::bit_rows")]
+    large: [u64; 64],
+}
+
+fn masks() -> Masks {
+    Masks {
+        a: 0b1000_0001,
+        b: 0x0f0f,
+        c: 0x8000_0001,
+        d: 0x0123_4567_89ab_cdef,
+        e: u128::MAX - 1,
+        plain: 7,
+    }
+}
+
+#[test]
+fn bitlines_round_trip_through_json() {
+    let json = serde_json::to_string(&masks()).unwrap();
+    assert_eq!(
+        json,
+        format!(
+            r#"{{"a":"10000001","b":"0000111100001111","c":"{}","d":[1,35,69,103,137,171,205,239],"e":"{}","plain":7}}"#,
+            0x8000_0001_u32.bit_repr(),
+            (u128::MAX - 1).bit_repr()
+        )
+    );
+    assert_eq!(serde_json::from_str::<Masks>(&json).unwrap(), masks());
+}
+
+#[test]
+fn bitlines_round_trip_through_bincode() {
+    let bytes = bincode::serialize(&masks()).unwrap();
+    // Byte strings carry a u64 length prefix in bincode; `c` is always a string.
+    assert_eq!(
+        bytes.len(),
+        (8 + 1) + (8 + 2) + (8 + 32) + (8 + 8) + (8 + 16) + 2
+    );
+    assert_eq!(&bytes[..9], &[1, 0, 0, 0, 0, 0, 0, 0, 0b1000_0001]);
+    assert_eq!(bincode::deserialize::<Masks>(&bytes).unwrap(), masks());
+}
+
+#[test]
+fn bitline_errors() {
+    let invalid = r#"{"a":"1000000","b":"0000111100001111","c":"0","d":[0],"e":"0","plain":0}"#;
+    let error = serde_json::from_str::<Masks>(invalid).unwrap_err();
+    assert!(error.to_string().contains("invalid length 7"), "{}", error);
+    let invalid = r#"{"a":"1000000x","b":"","c":"","d":[],"e":"","plain":0}"#;
+    let error = serde_json::from_str::<Masks>(invalid).unwrap_err();
+    assert!(error.to_string().contains("invalid value"), "{}", error);
+    let invalid = r#"{"a":"10000001","b":"0000111100001111","c":"00000000000000000000000000000000","d":[1,2,3,4,5,6,7,8,9],"e":"","plain":0}"#;
+    let error = serde_json::from_str::<Masks>(invalid).unwrap_err();
+    assert!(error.to_string().contains("invalid length 9"), "{}", error);
+}
+
+#[test]
+fn matrices_round_trip() {
+    let mut large = [0_u64; 64];
+    for (i, row) in large.iter_mut().enumerate() {
+        *row = 1 << i;
+    }
+    let matrices = Matrices {
+        small: [1, 2, 4, 8, 16, 32, 64, 128],
+        large,
+    };
+    let json = serde_json::to_string(&matrices).unwrap();
+    assert!(json.starts_with(r#"{"small":["00000001","00000010","#));
+    assert_eq!(serde_json::from_str::<Matrices>(&json).unwrap(), matrices);
+    let bytes = bincode::serialize(&matrices).unwrap();
+    assert_eq!(bytes.len(), 8 + 8 + 8 + 64 * 8);
+    assert_eq!(bincode::deserialize::<Matrices>(&bytes).unwrap(), matrices);
+}
+
+#[test]
+fn bitboards_tokens() {
+    let board = Bitboard8x8::from_rows([0x80, 0, 0, 0, 0, 0, 0, 0x01]);
+    let mut tokens = vec![Token::Seq { len: Some(8) }, Token::Str("10000000")];
+    tokens.extend([Token::Str("00000000"); 6].iter().copied());
+    tokens.extend([Token::Str("00000001"), Token::SeqEnd].iter().copied());
+    assert_tokens(&board.readable(), &tokens);
+    assert_tokens(
+        &board.compact(),
+        &[Token::Bytes(&[0x80, 0, 0, 0, 0, 0, 0, 1])],
+    );
+    assert_de_tokens_error::<serde_test::Compact<Bitboard8x8>>(
+        &[Token::Bytes(&[0; 7])],
+        "invalid length 7, expected 8 row strings or 8 bytes",
+    );
+    assert_de_tokens_error::<serde_test::Readable<Bitboard8x8>>(
+        &[
+            Token::Seq { len: Some(1) },
+            Token::Str("00000000"),
+            Token::SeqEnd,
+        ],
+        "invalid length 1, expected 8 row strings or 8 bytes",
+    );
+
+    let mut rows = [0_u16; 16];
+    rows[3] = 0xabcd;
+    let board = Bitboard16x16::from_rows(rows);
+    let json = serde_json::to_string(&board).unwrap();
+    assert_eq!(serde_json::from_str::<Bitboard16x16>(&json).unwrap(), board);
+    let bytes = bincode::serialize(&board).unwrap();
+    assert_eq!(&bytes[8 + 6..8 + 8], &[0xab, 0xcd]);
+    assert_eq!(
+        bincode::deserialize::<Bitboard16x16>(&bytes).unwrap(),
+        board
+    );
+}
+
+#[test]
+fn compressed_bitmap_round_trip() {
+    let bitmap: CompressedBitmap = (0..5).chain(1 << 20..(1 << 20) + 70_000).collect();
+    let json = serde_json::to_string(&bitmap).unwrap();
+    assert!(json.starts_with("[0,1,2,3,4,1048576,"));
+    assert_eq!(
+        serde_json::from_str::<CompressedBitmap>(&json).unwrap(),
+        bitmap
+    );
+    let bytes = bincode::serialize(&bitmap).unwrap();
+    assert_eq!(&bytes[8..], &bitmap.serialize_roaring()[..]);
+    assert_eq!(
+        bincode::deserialize::<CompressedBitmap>(&bytes).unwrap(),
+        bitmap
+    );
+    let small: CompressedBitmap = [3, 1].iter().copied().collect();
+    assert_tokens(
+        &small.readable(),
+        &[
+            Token::Seq { len: Some(2) },
+            Token::U32(1),
+            Token::U32(3),
+            Token::SeqEnd,
+        ],
+    );
+    assert_de_tokens_error::<serde_test::Compact<CompressedBitmap>>(
+        &[Token::Bytes(&[0; 4])],
+        "invalid serialization cookie",
+    );
+}
+
+#[test]
+fn bits_field_in_json_and_bincode() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Config {
+        #[serde(with = "bittersweet::serde::bits")]
+        mask: u8,
+        count: u8,
+    }
+
+    let config = Config {
+        mask: 0b00101100,
+        count: 3,
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(json, r#"{"mask":"00101100","count":3}"#);
+    assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Mask(#[serde(with = "bittersweet::serde::bits")] u16);
+
+    assert_eq!(
+        serde_json::to_string(&Mask(0x8001)).unwrap(),
+        r#""1000000000000001""#
+    );
+    assert_eq!(
+        bincode::serialize(&Mask(0x8001)).unwrap(),
+        [2, 0, 0, 0, 0, 0, 0, 0, 0x80, 0x01]
+    );
+}
+
+#[test]
+fn bit_rows_field_in_json() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Tile(#[serde(with = "bittersweet::serde::bit_rows")] [u8; 4]);
+
+    let tile = Tile([0b10000000, 0b01000000, 0b00100000, 0b00010000]);
+    let json = serde_json::to_string(&tile).unwrap();
+    assert_eq!(json, r#"["10000000","01000000","00100000","00010000"]"#);
+    assert_eq!(serde_json::from_str::<Tile>(&json).unwrap(), tile);
+}
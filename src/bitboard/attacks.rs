@@ -0,0 +1,360 @@
+use crate::bitboard::Bitboard8x8;
+use crate::bitline::Bitline;
+
+/// The direction in which a pawn advances, which decides the squares it attacks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PawnDirection {
+    /// The pawn advances towards row 0 and attacks the two squares diagonally north of it.
+    North,
+    /// The pawn advances towards row 7 and attacks the two squares diagonally south of it.
+    South,
+}
+
+/// The lines through each square, excluding the square itself: its row, its column, its
+/// diagonal (parallel to row 0 column 0 to row 7 column 7) and its anti-diagonal.
+pub(crate) const LINES: [[u64; 4]; 64] = lines();
+
+/// The knight, king, north pawn and south pawn attacks from each square.
+const LEAPERS: [[u64; 64]; 4] = leapers();
+
+/// Return the squares attacked along one line by a slider on `slider`, stopping at the first
+/// occupied square in each direction (which is included).
+///
+/// This is hyperbola quintessence: subtracting twice the slider from the line's occupancy
+/// flips the squares up to the first blocker on the more significant side, and the same
+/// subtraction on the bit-reversed board handles the less significant side.
+#[inline]
+fn line_attacks(occupied: u64, slider: u64, line: u64) -> u64 {
+    let occupied = occupied & line;
+    let forward = occupied.wrapping_sub(slider.wrapping_mul(2));
+    let reverse = occupied
+        .bin_to_bit_reversal_permutation()
+        .wrapping_sub(slider.bin_to_bit_reversal_permutation().wrapping_mul(2))
+        .bin_to_bit_reversal_permutation();
+    (forward ^ reverse) & line
+}
+
+#[inline]
+fn square_bit(square: usize) -> u64 {
+    assert!(square < 64, "square index out of range");
+    1 << (63 - square)
+}
+
+/// Return the squares a rook on `square` attacks, given the occupied squares.
+///
+/// Squares are numbered row by row, `square = row * 8 + col`, so square 0 is the MSB of the
+/// packed board as in [`Bitboard8x8::get`]. Each ray stops at the first occupied square,
+/// which is included; whether it holds a friendly piece is left to the caller. Occupancy of
+/// `square` itself is ignored.
+///
+/// # Examples
+/// ```
+/// use bittersweet::bitboard::{rook_attacks, Bitboard8x8};
+/// // A rook on row 0, column 0 blocked by a piece on row 0, column 2.
+/// let occupied = Bitboard8x8::EMPTY.with(0, 2);
+/// let attacks = rook_attacks(0, occupied);
+/// assert_eq!(attacks.row(0), 0b01100000);
+/// assert_eq!(attacks.column(0), 0b01111111);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `square` is not less than 64.
+#[inline]
+pub fn rook_attacks(square: usize, occupied: Bitboard8x8) -> Bitboard8x8 {
+    let slider = square_bit(square);
+    let [row, column, _, _] = LINES[square];
+    Bitboard8x8(line_attacks(occupied.0, slider, row) | line_attacks(occupied.0, slider, column))
+}
+
+/// Return the squares a bishop on `square` attacks, given the occupied squares.
+///
+/// See [`rook_attacks`] for the square numbering and blocker handling.
+///
+/// # Examples
+/// ```
+/// use bittersweet::bitboard::{bishop_attacks, Bitboard8x8};
+/// let occupied = Bitboard8x8::EMPTY.with(5, 5);
+/// let attacks = bishop_attacks(3 * 8 + 3, occupied);
+/// assert!(attacks.get(5, 5));
+/// assert!(!attacks.get(6, 6));
+/// assert!(attacks.get(0, 0) && attacks.get(0, 6) && attacks.get(6, 0));
+/// assert_eq!(attacks.count(), 11);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `square` is not less than 64.
+#[inline]
+pub fn bishop_attacks(square: usize, occupied: Bitboard8x8) -> Bitboard8x8 {
+    let slider = square_bit(square);
+    let [_, _, diagonal, anti_diagonal] = LINES[square];
+    Bitboard8x8(
+        line_attacks(occupied.0, slider, diagonal)
+            | line_attacks(occupied.0, slider, anti_diagonal),
+    )
+}
+
+/// Return the squares a queen on `square` attacks, given the occupied squares.
+///
+/// This is the union of [`rook_attacks`] and [`bishop_attacks`].
+///
+/// # Panics
+///
+/// Panics if `square` is not less than 64.
+#[inline]
+pub fn queen_attacks(square: usize, occupied: Bitboard8x8) -> Bitboard8x8 {
+    rook_attacks(square, occupied) | bishop_attacks(square, occupied)
+}
+
+/// Return the squares a knight on `square` attacks.
+///
+/// # Examples
+/// ```
+/// use bittersweet::bitboard::knight_attacks;
+/// assert_eq!(knight_attacks(0).count(), 2);
+/// assert_eq!(knight_attacks(3 * 8 + 3).count(), 8);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `square` is not less than 64.
+#[inline]
+pub fn knight_attacks(square: usize) -> Bitboard8x8 {
+    assert!(square < 64, "square index out of range");
+    Bitboard8x8(LEAPERS[0][square])
+}
+
+/// Return the squares a king on `square` attacks.
+///
+/// # Examples
+/// ```
+/// use bittersweet::bitboard::king_attacks;
+/// assert_eq!(king_attacks(0).count(), 3);
+/// assert_eq!(king_attacks(3 * 8 + 3).count(), 8);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `square` is not less than 64.
+#[inline]
+pub fn king_attacks(square: usize) -> Bitboard8x8 {
+    assert!(square < 64, "square index out of range");
+    Bitboard8x8(LEAPERS[1][square])
+}
+
+/// Return the squares a pawn on `square` advancing in `direction` attacks.
+///
+/// # Examples
+/// ```
+/// use bittersweet::bitboard::{pawn_attacks, Bitboard8x8, PawnDirection};
+/// let square = 6 * 8 + 4;
+/// assert_eq!(
+///     pawn_attacks(square, PawnDirection::North),
+///     Bitboard8x8::EMPTY.with(5, 3).with(5, 5)
+/// );
+/// assert!(pawn_attacks(square, PawnDirection::South).get(7, 3));
+/// assert!(pawn_attacks(7, PawnDirection::North).is_empty());
+/// ```
+///
+/// # Panics
+///
+/// Panics if `square` is not less than 64.
+#[inline]
+pub fn pawn_attacks(square: usize, direction: PawnDirection) -> Bitboard8x8 {
+    assert!(square < 64, "square index out of range");
+    let table = match direction {
+        PawnDirection::North => 2,
+        PawnDirection::South => 3,
+    };
+    Bitboard8x8(LEAPERS[table][square])
+}
+
+const fn lines() -> [[u64; 4]; 64] {
+    let mut lines = [[0; 4]; 64];
+    let mut square = 0;
+    while square < 64 {
+        let (row, col) = ((square / 8) as isize, (square % 8) as isize);
+        let mut other = 0;
+        while other < 64 {
+            let (r, c) = ((other / 8) as isize, (other % 8) as isize);
+            if other != square {
+                let bit = 1 << (63 - other);
+                if r == row {
+                    lines[square][0] |= bit;
+                }
+                if c == col {
+                    lines[square][1] |= bit;
+                }
+                if r - c == row - col {
+                    lines[square][2] |= bit;
+                }
+                if r + c == row + col {
+                    lines[square][3] |= bit;
+                }
+            }
+            other += 1;
+        }
+        square += 1;
+    }
+    lines
+}
+
+const fn leapers() -> [[u64; 64]; 4] {
+    let mut tables = [[0; 64]; 4];
+    let mut square = 0;
+    while square < 64 {
+        let b = Bitboard8x8(1 << (63 - square));
+        let (n, s, e, w) = (b.shift_n(), b.shift_s(), b.shift_e(), b.shift_w());
+        tables[0][square] = n.shift_ne().0
+            | n.shift_nw().0
+            | s.shift_se().0
+            | s.shift_sw().0
+            | e.shift_ne().0
+            | e.shift_se().0
+            | w.shift_nw().0
+            | w.shift_sw().0;
+        let row = b.0 | e.0 | w.0;
+        tables[1][square] = (row | row << 8 | row >> 8) & !b.0;
+        tables[2][square] = b.shift_ne().0 | b.shift_nw().0;
+        tables[3][square] = b.shift_se().0 | b.shift_sw().0;
+        square += 1;
+    }
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::xorshift64;
+
+    /// Walk each direction square by square until the edge or the first occupied square.
+    fn naive_slider(square: usize, occupied: u64, directions: &[(isize, isize)]) -> u64 {
+        let mut attacks = 0;
+        for &(dr, dc) in directions {
+            let (mut r, mut c) = ((square / 8) as isize + dr, (square % 8) as isize + dc);
+            while (0..8).contains(&r) && (0..8).contains(&c) {
+                let bit = 1 << (63 - (r * 8 + c));
+                attacks |= bit;
+                if occupied & bit != 0 {
+                    break;
+                }
+                r += dr;
+                c += dc;
+            }
+        }
+        attacks
+    }
+
+    fn naive_leaper(square: usize, offsets: &[(isize, isize)]) -> u64 {
+        offsets
+            .iter()
+            .map(|&(dr, dc)| ((square / 8) as isize + dr, (square % 8) as isize + dc))
+            .filter(|(r, c)| (0..8).contains(r) && (0..8).contains(c))
+            .fold(0, |attacks, (r, c)| attacks | 1 << (63 - (r * 8 + c)))
+    }
+
+    const ROOK: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+    const BISHOP: [(isize, isize); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+    #[test]
+    fn test_sliders_match_naive() {
+        let mut state = 0x9e3779b97f4a7c15;
+        for i in 0..256 {
+            // Vary the density from sparse to dense boards.
+            let occupied = match i % 3 {
+                0 => xorshift64(&mut state) & xorshift64(&mut state),
+                1 => xorshift64(&mut state),
+                _ => xorshift64(&mut state) | xorshift64(&mut state),
+            };
+            for square in 0..64 {
+                let board = Bitboard8x8(occupied);
+                let rook = naive_slider(square, occupied, &ROOK);
+                let bishop = naive_slider(square, occupied, &BISHOP);
+                assert_eq!(rook_attacks(square, board).0, rook);
+                assert_eq!(bishop_attacks(square, board).0, bishop);
+                assert_eq!(queen_attacks(square, board).0, rook | bishop);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sliders_on_empty_board() {
+        for square in 0..64 {
+            assert_eq!(rook_attacks(square, Bitboard8x8::EMPTY).count(), 14);
+            let (r, c) = (square / 8, square % 8);
+            let rook = Bitboard8x8::row_mask(r) ^ Bitboard8x8::column_mask(c);
+            assert_eq!(rook_attacks(square, Bitboard8x8::FULL), {
+                let mut adjacent = Bitboard8x8::EMPTY;
+                for &(dr, dc) in ROOK.iter() {
+                    let (nr, nc) = (r as isize + dr, c as isize + dc);
+                    if (0..8).contains(&nr) && (0..8).contains(&nc) {
+                        adjacent = adjacent.with(nr as usize, nc as usize);
+                    }
+                }
+                adjacent
+            });
+            assert_eq!(rook_attacks(square, Bitboard8x8::EMPTY), rook);
+        }
+        assert_eq!(bishop_attacks(0, Bitboard8x8::EMPTY).count(), 7);
+        assert_eq!(bishop_attacks(27, Bitboard8x8::EMPTY).count(), 13);
+    }
+
+    #[test]
+    fn test_slider_ignores_own_square() {
+        let occupied = Bitboard8x8::EMPTY.with(4, 4);
+        let square = 4 * 8 + 4;
+        assert_eq!(
+            rook_attacks(square, occupied),
+            rook_attacks(square, Bitboard8x8::EMPTY)
+        );
+    }
+
+    #[test]
+    fn test_leapers_match_naive() {
+        let knight = [
+            (-2, -1),
+            (-2, 1),
+            (-1, -2),
+            (-1, 2),
+            (1, -2),
+            (1, 2),
+            (2, -1),
+            (2, 1),
+        ];
+        let king = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ];
+        for square in 0..64 {
+            assert_eq!(knight_attacks(square).0, naive_leaper(square, &knight));
+            assert_eq!(king_attacks(square).0, naive_leaper(square, &king));
+            assert_eq!(
+                pawn_attacks(square, PawnDirection::North).0,
+                naive_leaper(square, &[(-1, -1), (-1, 1)])
+            );
+            assert_eq!(
+                pawn_attacks(square, PawnDirection::South).0,
+                naive_leaper(square, &[(1, -1), (1, 1)])
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "square index out of range")]
+    fn test_rook_attacks_out_of_range() {
+        rook_attacks(64, Bitboard8x8::EMPTY);
+    }
+
+    #[test]
+    #[should_panic(expected = "square index out of range")]
+    fn test_knight_attacks_out_of_range() {
+        knight_attacks(64);
+    }
+}
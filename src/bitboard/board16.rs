@@ -0,0 +1,389 @@
+use crate::matrix::transpose16x16;
+
+/// Build a board by applying an expression to each row of one board, or to each pair of rows
+/// of two boards. Function pointers cannot be called in a `const fn` on the minimum supported
+/// Rust version, so the loop is expanded in place instead.
+macro_rules! map_rows {
+    ($a:expr, |$x:ident| $e:expr) => {{
+        let a = $a.0;
+        let mut rows = [0_u16; 16];
+        let mut i = 0;
+        while i < 16 {
+            let $x = a[i];
+            rows[i] = $e;
+            i += 1;
+        }
+        Bitboard16x16(rows)
+    }};
+    ($a:expr, $b:expr, |$x:ident, $y:ident| $e:expr) => {{
+        let (a, b) = ($a.0, $b.0);
+        let mut rows = [0_u16; 16];
+        let mut i = 0;
+        while i < 16 {
+            let ($x, $y) = (a[i], b[i]);
+            rows[i] = $e;
+            i += 1;
+        }
+        Bitboard16x16(rows)
+    }};
+}
+
+/// A 16×16 board stored as one `u16` per row.
+///
+/// Element `i` is row `i` and, within a row, the MSB is column 0; see the
+/// [module-level documentation](super) for the full convention. The wrapped array is public,
+/// so it can be passed straight to the 16×16 functions in [`crate::matrix`].
+///
+/// # Examples
+/// ```
+/// use bittersweet::bitboard::Bitboard16x16;
+/// let board = Bitboard16x16::EMPTY.with(0, 15).with(8, 8);
+/// assert_eq!(board.shift_sw(), Bitboard16x16::EMPTY.with(1, 14).with(9, 7));
+/// assert_eq!(board.column(8), 0x0080);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard16x16(pub [u16; 16]);
+
+impl Bitboard16x16 {
+    /// The number of rows and of columns.
+    pub const SIZE: usize = 16;
+    /// The board with no square set.
+    pub const EMPTY: Self = Bitboard16x16([0; 16]);
+    /// The board with every square set.
+    pub const FULL: Self = Bitboard16x16([u16::MAX; 16]);
+
+    /// Create a board whose row `i` is `rows[i]`.
+    #[inline]
+    pub const fn from_rows(rows: [u16; 16]) -> Self {
+        Bitboard16x16(rows)
+    }
+
+    /// Return the rows of the board, top row first.
+    #[inline]
+    pub const fn to_rows(self) -> [u16; 16] {
+        self.0
+    }
+
+    /// Return the mask of every square in row `row`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is not less than 16.
+    #[inline]
+    pub fn row_mask(row: usize) -> Self {
+        assert!(row < Self::SIZE, "row index out of range");
+        let mut rows = [0; 16];
+        rows[row] = u16::MAX;
+        Bitboard16x16(rows)
+    }
+
+    /// Return the mask of every square in column `col`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `col` is not less than 16.
+    #[inline]
+    pub fn column_mask(col: usize) -> Self {
+        assert!(col < Self::SIZE, "column index out of range");
+        Bitboard16x16([0x8000 >> col; 16])
+    }
+
+    /// Return row `row` as a `u16` whose MSB is column 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is not less than 16.
+    #[inline]
+    pub fn row(self, row: usize) -> u16 {
+        assert!(row < Self::SIZE, "row index out of range");
+        self.0[row]
+    }
+
+    /// Return column `col` as a `u16` whose MSB is row 0.
+    ///
+    /// The column is read from the transposed board, see
+    /// [`transpose16x16`](crate::matrix::transpose16x16).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `col` is not less than 16.
+    #[inline]
+    pub fn column(self, col: usize) -> u16 {
+        assert!(col < Self::SIZE, "column index out of range");
+        transpose16x16(self.0)[col]
+    }
+
+    /// Return whether the square at `row`, `col` is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is not less than 16.
+    #[inline]
+    pub fn get(self, row: usize, col: usize) -> bool {
+        self.0[row] & Self::square(col) != 0
+    }
+
+    /// Return a copy of the board with the square at `row`, `col` set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is not less than 16.
+    #[inline]
+    pub fn with(mut self, row: usize, col: usize) -> Self {
+        self.set(row, col, true);
+        self
+    }
+
+    /// Return a copy of the board with the square at `row`, `col` cleared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is not less than 16.
+    #[inline]
+    pub fn without(mut self, row: usize, col: usize) -> Self {
+        self.set(row, col, false);
+        self
+    }
+
+    /// Set the square at `row`, `col` to `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is not less than 16.
+    #[inline]
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        let bit = Self::square(col);
+        let row = &mut self.0[row];
+        if value {
+            *row |= bit;
+        } else {
+            *row &= !bit;
+        }
+    }
+
+    #[inline]
+    fn square(col: usize) -> u16 {
+        assert!(col < Self::SIZE, "square index out of range");
+        0x8000 >> col
+    }
+
+    /// Return the number of set squares.
+    #[inline]
+    pub const fn count(self) -> u32 {
+        let mut count = 0;
+        let mut i = 0;
+        while i < 16 {
+            count += self.0[i].count_ones();
+            i += 1;
+        }
+        count
+    }
+
+    /// Return whether no square is set.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        let mut i = 0;
+        while i < 16 {
+            if self.0[i] != 0 {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// Return whether every square is set.
+    #[inline]
+    pub const fn is_full(self) -> bool {
+        self.complement().is_empty()
+    }
+
+    /// Return the squares set in both boards.
+    #[inline]
+    pub const fn intersection(self, other: Self) -> Self {
+        map_rows!(self, other, |a, b| a & b)
+    }
+
+    /// Return the squares set in either board.
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        map_rows!(self, other, |a, b| a | b)
+    }
+
+    /// Return the squares set in exactly one of the boards.
+    #[inline]
+    pub const fn symmetric_difference(self, other: Self) -> Self {
+        map_rows!(self, other, |a, b| a ^ b)
+    }
+
+    /// Return the squares set in `self` but not in `other`.
+    #[inline]
+    pub const fn difference(self, other: Self) -> Self {
+        map_rows!(self, other, |a, b| a & !b)
+    }
+
+    /// Return the squares not set in the board.
+    #[inline]
+    pub const fn complement(self) -> Self {
+        map_rows!(self, |a| !a)
+    }
+
+    /// Move every square one row north (towards row 0).
+    #[inline]
+    pub const fn shift_n(self) -> Self {
+        let mut rows = [0; 16];
+        let mut i = 0;
+        while i < 15 {
+            rows[i] = self.0[i + 1];
+            i += 1;
+        }
+        Bitboard16x16(rows)
+    }
+
+    /// Move every square one row south (towards row 15).
+    #[inline]
+    pub const fn shift_s(self) -> Self {
+        let mut rows = [0; 16];
+        let mut i = 1;
+        while i < 16 {
+            rows[i] = self.0[i - 1];
+            i += 1;
+        }
+        Bitboard16x16(rows)
+    }
+
+    /// Move every square one column east (towards column 15).
+    #[inline]
+    pub const fn shift_e(self) -> Self {
+        map_rows!(self, |a| a >> 1)
+    }
+
+    /// Move every square one column west (towards column 0).
+    #[inline]
+    pub const fn shift_w(self) -> Self {
+        map_rows!(self, |a| a << 1)
+    }
+
+    /// Move every square one row north and one column east.
+    #[inline]
+    pub const fn shift_ne(self) -> Self {
+        self.shift_n().shift_e()
+    }
+
+    /// Move every square one row north and one column west.
+    #[inline]
+    pub const fn shift_nw(self) -> Self {
+        self.shift_n().shift_w()
+    }
+
+    /// Move every square one row south and one column east.
+    #[inline]
+    pub const fn shift_se(self) -> Self {
+        self.shift_s().shift_e()
+    }
+
+    /// Move every square one row south and one column west.
+    #[inline]
+    pub const fn shift_sw(self) -> Self {
+        self.shift_s().shift_w()
+    }
+}
+
+impl_board_ops!(Bitboard16x16);
+
+impl From<[u16; 16]> for Bitboard16x16 {
+    #[inline]
+    fn from(rows: [u16; 16]) -> Self {
+        Bitboard16x16(rows)
+    }
+}
+
+impl From<Bitboard16x16> for [u16; 16] {
+    #[inline]
+    fn from(board: Bitboard16x16) -> Self {
+        board.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pseudo_random_boards;
+
+    /// Move every square by `dr` rows and `dc` columns, one square at a time.
+    fn naive_shift(board: Bitboard16x16, dr: isize, dc: isize) -> Bitboard16x16 {
+        let mut shifted = Bitboard16x16::EMPTY;
+        for row in 0..16 {
+            for col in 0..16 {
+                let (r, c) = (row as isize + dr, col as isize + dc);
+                if board.get(row, col) && (0..16).contains(&r) && (0..16).contains(&c) {
+                    shifted = shifted.with(r as usize, c as usize);
+                }
+            }
+        }
+        shifted
+    }
+
+    #[test]
+    fn test_shifts_match_naive() {
+        for board in pseudo_random_boards(32, Bitboard16x16::from_rows)
+            .chain([Bitboard16x16::FULL].iter().copied())
+        {
+            assert_eq!(board.shift_n(), naive_shift(board, -1, 0));
+            assert_eq!(board.shift_s(), naive_shift(board, 1, 0));
+            assert_eq!(board.shift_e(), naive_shift(board, 0, 1));
+            assert_eq!(board.shift_w(), naive_shift(board, 0, -1));
+            assert_eq!(board.shift_ne(), naive_shift(board, -1, 1));
+            assert_eq!(board.shift_nw(), naive_shift(board, -1, -1));
+            assert_eq!(board.shift_se(), naive_shift(board, 1, 1));
+            assert_eq!(board.shift_sw(), naive_shift(board, 1, -1));
+        }
+    }
+
+    #[test]
+    fn test_masks_row_and_column() {
+        for i in 0..16 {
+            assert_eq!(Bitboard16x16::row_mask(i).count(), 16);
+            assert_eq!(Bitboard16x16::column_mask(i).column(i), u16::MAX);
+        }
+        assert_eq!(
+            Bitboard16x16::FULL.shift_e(),
+            !Bitboard16x16::column_mask(0)
+        );
+        for board in pseudo_random_boards(32, Bitboard16x16::from_rows) {
+            for i in 0..16 {
+                for j in 0..16 {
+                    let bit = 0x8000 >> j;
+                    assert_eq!(board.row(i) & bit != 0, board.get(i, j));
+                    assert_eq!(board.column(i) & bit != 0, board.get(j, i));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = Bitboard16x16::row_mask(3);
+        let b = Bitboard16x16::column_mask(5);
+        assert_eq!((a & b), Bitboard16x16::EMPTY.with(3, 5));
+        assert_eq!((a | b).count(), 31);
+        assert_eq!((a ^ b).count(), 30);
+        assert_eq!(a.difference(b).count(), 15);
+        assert!((!Bitboard16x16::EMPTY).is_full());
+    }
+
+    #[test]
+    #[should_panic(expected = "square index out of range")]
+    fn test_with_out_of_range() {
+        Bitboard16x16::EMPTY.with(0, 16);
+    }
+
+    #[test]
+    fn test_display() {
+        let rendered = format!("{}", Bitboard16x16::EMPTY.with(0, 1).with(15, 15));
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 16);
+        assert_eq!(lines[0], ".1..............");
+        assert_eq!(lines[15], "...............1");
+    }
+}
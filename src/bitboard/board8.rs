@@ -0,0 +1,423 @@
+use crate::matrix::transpose8x8_u64;
+
+/// Every square of column 0, the west edge.
+const COLUMN_0: u64 = 0x8080_8080_8080_8080;
+/// Every square of column 7, the east edge.
+const COLUMN_7: u64 = 0x0101_0101_0101_0101;
+
+/// An 8×8 board packed into a `u64`, one byte per row.
+///
+/// The most-significant byte is row 0 and, within a byte, the MSB is column 0; see the
+/// [module-level documentation](super) for the full convention. The wrapped `u64` is public,
+/// so existing code using bare `u64` boards can convert freely.
+///
+/// # Examples
+/// ```
+/// use bittersweet::bitboard::Bitboard8x8;
+/// let board = Bitboard8x8::from_rows([
+///     0b00000000, //
+///     0b00011000, //
+///     0b00011000, //
+///     0b00000000, //
+///     0b00000000, //
+///     0b00000000, //
+///     0b00000000, //
+///     0b00000000, //
+/// ]);
+/// assert_eq!(board.shift_e().row(1), 0b00001100);
+/// assert_eq!(board.shift_n().row(0), 0b00011000);
+/// assert_eq!(board.column(3), 0b01100000);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard8x8(pub u64);
+
+impl Bitboard8x8 {
+    /// The number of rows and of columns.
+    pub const SIZE: usize = 8;
+    /// The board with no square set.
+    pub const EMPTY: Self = Bitboard8x8(0);
+    /// The board with every square set.
+    pub const FULL: Self = Bitboard8x8(u64::MAX);
+
+    /// Create a board from its packed `u64` form.
+    #[inline]
+    pub const fn new(bits: u64) -> Self {
+        Bitboard8x8(bits)
+    }
+
+    /// Return the packed `u64` form of the board.
+    #[inline]
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    /// Create a board whose row `i` is `rows[i]`.
+    #[inline]
+    pub const fn from_rows(rows: [u8; 8]) -> Self {
+        Bitboard8x8(u64::from_be_bytes(rows))
+    }
+
+    /// Return the rows of the board, top row first.
+    #[inline]
+    pub const fn to_rows(self) -> [u8; 8] {
+        self.0.to_be_bytes()
+    }
+
+    /// Return the mask of every square in row `row`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is not less than 8.
+    #[inline]
+    pub fn row_mask(row: usize) -> Self {
+        assert!(row < Self::SIZE, "row index out of range");
+        Bitboard8x8(0xff00_0000_0000_0000 >> (row * 8))
+    }
+
+    /// Return the mask of every square in column `col`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `col` is not less than 8.
+    #[inline]
+    pub fn column_mask(col: usize) -> Self {
+        assert!(col < Self::SIZE, "column index out of range");
+        Bitboard8x8(COLUMN_0 >> col)
+    }
+
+    /// Return row `row` as a byte whose MSB is column 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is not less than 8.
+    #[inline]
+    pub fn row(self, row: usize) -> u8 {
+        assert!(row < Self::SIZE, "row index out of range");
+        self.to_rows()[row]
+    }
+
+    /// Return column `col` as a byte whose MSB is row 0.
+    ///
+    /// The column is read from the transposed board, see
+    /// [`transpose8x8_u64`](crate::matrix::transpose8x8_u64).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `col` is not less than 8.
+    #[inline]
+    pub fn column(self, col: usize) -> u8 {
+        assert!(col < Self::SIZE, "column index out of range");
+        transpose8x8_u64(self.0).to_be_bytes()[col]
+    }
+
+    /// Return whether the square at `row`, `col` is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is not less than 8.
+    #[inline]
+    pub fn get(self, row: usize, col: usize) -> bool {
+        self.0 & Self::square(row, col) != 0
+    }
+
+    /// Return a copy of the board with the square at `row`, `col` set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is not less than 8.
+    #[inline]
+    pub fn with(self, row: usize, col: usize) -> Self {
+        Bitboard8x8(self.0 | Self::square(row, col))
+    }
+
+    /// Return a copy of the board with the square at `row`, `col` cleared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is not less than 8.
+    #[inline]
+    pub fn without(self, row: usize, col: usize) -> Self {
+        Bitboard8x8(self.0 & !Self::square(row, col))
+    }
+
+    /// Set the square at `row`, `col` to `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is not less than 8.
+    #[inline]
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        *self = if value {
+            self.with(row, col)
+        } else {
+            self.without(row, col)
+        };
+    }
+
+    #[inline]
+    fn square(row: usize, col: usize) -> u64 {
+        assert!(
+            row < Self::SIZE && col < Self::SIZE,
+            "square index out of range"
+        );
+        1 << (63 - (row * 8 + col))
+    }
+
+    /// Return the number of set squares.
+    #[inline]
+    pub const fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Return whether no square is set.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Return whether every square is set.
+    #[inline]
+    pub const fn is_full(self) -> bool {
+        self.0 == u64::MAX
+    }
+
+    /// Return the squares set in both boards.
+    #[inline]
+    pub const fn intersection(self, other: Self) -> Self {
+        Bitboard8x8(self.0 & other.0)
+    }
+
+    /// Return the squares set in either board.
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Bitboard8x8(self.0 | other.0)
+    }
+
+    /// Return the squares set in exactly one of the boards.
+    #[inline]
+    pub const fn symmetric_difference(self, other: Self) -> Self {
+        Bitboard8x8(self.0 ^ other.0)
+    }
+
+    /// Return the squares set in `self` but not in `other`.
+    #[inline]
+    pub const fn difference(self, other: Self) -> Self {
+        Bitboard8x8(self.0 & !other.0)
+    }
+
+    /// Return the squares not set in the board.
+    #[inline]
+    pub const fn complement(self) -> Self {
+        Bitboard8x8(!self.0)
+    }
+
+    /// Move every square one row north (towards row 0).
+    #[inline]
+    pub const fn shift_n(self) -> Self {
+        Bitboard8x8(self.0 << 8)
+    }
+
+    /// Move every square one row south (towards row 7).
+    #[inline]
+    pub const fn shift_s(self) -> Self {
+        Bitboard8x8(self.0 >> 8)
+    }
+
+    /// Move every square one column east (towards column 7).
+    #[inline]
+    pub const fn shift_e(self) -> Self {
+        Bitboard8x8((self.0 >> 1) & !COLUMN_0)
+    }
+
+    /// Move every square one column west (towards column 0).
+    #[inline]
+    pub const fn shift_w(self) -> Self {
+        Bitboard8x8((self.0 << 1) & !COLUMN_7)
+    }
+
+    /// Move every square one row north and one column east.
+    #[inline]
+    pub const fn shift_ne(self) -> Self {
+        Bitboard8x8((self.0 << 7) & !COLUMN_0)
+    }
+
+    /// Move every square one row north and one column west.
+    #[inline]
+    pub const fn shift_nw(self) -> Self {
+        Bitboard8x8((self.0 << 9) & !COLUMN_7)
+    }
+
+    /// Move every square one row south and one column east.
+    #[inline]
+    pub const fn shift_se(self) -> Self {
+        Bitboard8x8((self.0 >> 9) & !COLUMN_0)
+    }
+
+    /// Move every square one row south and one column west.
+    #[inline]
+    pub const fn shift_sw(self) -> Self {
+        Bitboard8x8((self.0 >> 7) & !COLUMN_7)
+    }
+}
+
+impl_board_ops!(Bitboard8x8);
+
+impl From<u64> for Bitboard8x8 {
+    #[inline]
+    fn from(bits: u64) -> Self {
+        Bitboard8x8(bits)
+    }
+}
+
+impl From<Bitboard8x8> for u64 {
+    #[inline]
+    fn from(board: Bitboard8x8) -> Self {
+        board.0
+    }
+}
+
+impl From<[u8; 8]> for Bitboard8x8 {
+    #[inline]
+    fn from(rows: [u8; 8]) -> Self {
+        Bitboard8x8::from_rows(rows)
+    }
+}
+
+impl From<Bitboard8x8> for [u8; 8] {
+    #[inline]
+    fn from(board: Bitboard8x8) -> Self {
+        board.to_rows()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pseudo_random_boards;
+
+    /// Move every square by `dr` rows and `dc` columns, one square at a time.
+    fn naive_shift(board: Bitboard8x8, dr: isize, dc: isize) -> Bitboard8x8 {
+        let mut shifted = Bitboard8x8::EMPTY;
+        for row in 0..8 {
+            for col in 0..8 {
+                let (r, c) = (row as isize + dr, col as isize + dc);
+                if board.get(row, col) && (0..8).contains(&r) && (0..8).contains(&c) {
+                    shifted = shifted.with(r as usize, c as usize);
+                }
+            }
+        }
+        shifted
+    }
+
+    #[test]
+    fn test_shifts_match_naive() {
+        for board in pseudo_random_boards(64, Bitboard8x8::from_rows)
+            .chain([Bitboard8x8::FULL].iter().copied())
+        {
+            assert_eq!(board.shift_n(), naive_shift(board, -1, 0));
+            assert_eq!(board.shift_s(), naive_shift(board, 1, 0));
+            assert_eq!(board.shift_e(), naive_shift(board, 0, 1));
+            assert_eq!(board.shift_w(), naive_shift(board, 0, -1));
+            assert_eq!(board.shift_ne(), naive_shift(board, -1, 1));
+            assert_eq!(board.shift_nw(), naive_shift(board, -1, -1));
+            assert_eq!(board.shift_se(), naive_shift(board, 1, 1));
+            assert_eq!(board.shift_sw(), naive_shift(board, 1, -1));
+        }
+    }
+
+    #[test]
+    fn test_shifts_drop_edges() {
+        let full = Bitboard8x8::FULL;
+        assert_eq!(full.shift_n(), !Bitboard8x8::row_mask(7));
+        assert_eq!(full.shift_s(), !Bitboard8x8::row_mask(0));
+        assert_eq!(full.shift_e(), !Bitboard8x8::column_mask(0));
+        assert_eq!(full.shift_w(), !Bitboard8x8::column_mask(7));
+        assert_eq!(full.shift_ne().count(), 49);
+        assert!(Bitboard8x8::row_mask(0).shift_n().is_empty());
+        assert!(Bitboard8x8::column_mask(7).shift_e().is_empty());
+    }
+
+    #[test]
+    fn test_masks() {
+        let mut rows = Bitboard8x8::EMPTY;
+        let mut columns = Bitboard8x8::EMPTY;
+        for i in 0..8 {
+            assert_eq!(Bitboard8x8::row_mask(i).count(), 8);
+            assert_eq!(Bitboard8x8::column_mask(i).count(), 8);
+            assert_eq!(Bitboard8x8::row_mask(i).row(i), 0xff);
+            assert_eq!(Bitboard8x8::column_mask(i).column(i), 0xff);
+            rows |= Bitboard8x8::row_mask(i);
+            columns ^= Bitboard8x8::column_mask(i);
+        }
+        assert!(rows.is_full());
+        assert!(columns.is_full());
+    }
+
+    #[test]
+    fn test_row_and_column() {
+        for board in pseudo_random_boards(64, Bitboard8x8::from_rows) {
+            for i in 0..8 {
+                for j in 0..8 {
+                    let bit = 0x80 >> j;
+                    assert_eq!(board.row(i) & bit != 0, board.get(i, j));
+                    assert_eq!(board.column(i) & bit != 0, board.get(j, i));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_set() {
+        let mut board = Bitboard8x8::EMPTY;
+        board.set(2, 5, true);
+        assert!(board.get(2, 5));
+        assert_eq!(board.bits(), 1 << (63 - 21));
+        assert_eq!(board, Bitboard8x8::EMPTY.with(2, 5));
+        board.set(2, 5, false);
+        assert!(board.is_empty());
+        assert_eq!(Bitboard8x8::FULL.without(0, 0).count(), 63);
+    }
+
+    #[test]
+    #[should_panic(expected = "square index out of range")]
+    fn test_get_out_of_range() {
+        Bitboard8x8::EMPTY.get(0, 8);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let rows = [1, 2, 3, 4, 5, 6, 7, 8];
+        let board = Bitboard8x8::from(rows);
+        assert_eq!(board.bits(), 0x0102030405060708);
+        assert_eq!(<[u8; 8]>::from(board), rows);
+        assert_eq!(u64::from(board), 0x0102030405060708);
+        assert_eq!(Bitboard8x8::from(0x0102030405060708), board);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = Bitboard8x8(0b1100);
+        let b = Bitboard8x8(0b1010);
+        assert_eq!(a & b, Bitboard8x8(0b1000));
+        assert_eq!(a | b, Bitboard8x8(0b1110));
+        assert_eq!(a ^ b, Bitboard8x8(0b0110));
+        assert_eq!(a.difference(b), Bitboard8x8(0b0100));
+        assert_eq!(!Bitboard8x8::EMPTY, Bitboard8x8::FULL);
+    }
+
+    #[test]
+    fn test_display() {
+        let board = Bitboard8x8::EMPTY.with(0, 0).with(7, 7).with(3, 4);
+        assert_eq!(
+            format!("{}", board),
+            "1.......\n\
+             ........\n\
+             ........\n\
+             ....1...\n\
+             ........\n\
+             ........\n\
+             ........\n\
+             .......1"
+        );
+    }
+}
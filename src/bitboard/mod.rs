@@ -0,0 +1,127 @@
+//! Fixed-size two-dimensional bit boards with directional shifts.
+//!
+//! # Board convention
+//!
+//! Boards follow the row-per-element layout of [`crate::matrix`]:
+//!
+//! - [`Bitboard8x8`] wraps a `u64` in the packed form of
+//!   [`transpose8x8_u64`](crate::matrix::transpose8x8_u64): the most-significant byte is
+//!   row 0 and the least-significant byte is row 7.
+//! - [`Bitboard16x16`] wraps a `[u16; 16]` whose element `i` is row `i`.
+//!
+//! Within a row, the MSB is column 0. Row 0 is the top (north) edge and column 0 is the left
+//! (west) edge, so `shift_n` moves every square one row up and `shift_e` one column right.
+//! Squares shifted past an edge are dropped; they never wrap into the neighbouring row.
+//!
+//! # Example
+//!
+//! ```
+//! use bittersweet::bitboard::Bitboard8x8;
+//! let board = Bitboard8x8::EMPTY.with(0, 7);
+//! // The top-right corner has nowhere to go to the north-east.
+//! assert!(board.shift_ne().is_empty());
+//! assert_eq!(board.shift_sw(), Bitboard8x8::EMPTY.with(1, 6));
+//! ```
+//!
+//! # Attacks
+//!
+//! For chess-like games on [`Bitboard8x8`], [`rook_attacks`], [`bishop_attacks`] and
+//! [`queen_attacks`] compute sliding attacks with hyperbola quintessence, and
+//! [`knight_attacks`], [`king_attacks`] and [`pawn_attacks`] look up precomputed masks.
+//! Squares are numbered `row * 8 + col`, so square 0 is the MSB. With the `alloc` feature,
+//! [`MagicAttacks`] trades about 840 KiB of tables for faster sliding attacks.
+//!
+//! See also: [`crate::matrix`] for transposition and symmetries of the same layouts.
+
+/// Implement the bitwise operators, their assigning forms and `Display` for a board type in
+/// terms of its inherent set operations and `get`.
+///
+/// Defined before the submodules so that it is in scope inside them.
+macro_rules! impl_board_ops {
+    ($Board:ty) => {
+        impl core::ops::BitAnd for $Board {
+            type Output = Self;
+            #[inline]
+            fn bitand(self, rhs: Self) -> Self {
+                self.intersection(rhs)
+            }
+        }
+
+        impl core::ops::BitOr for $Board {
+            type Output = Self;
+            #[inline]
+            fn bitor(self, rhs: Self) -> Self {
+                self.union(rhs)
+            }
+        }
+
+        impl core::ops::BitXor for $Board {
+            type Output = Self;
+            #[inline]
+            fn bitxor(self, rhs: Self) -> Self {
+                self.symmetric_difference(rhs)
+            }
+        }
+
+        impl core::ops::Not for $Board {
+            type Output = Self;
+            #[inline]
+            fn not(self) -> Self {
+                self.complement()
+            }
+        }
+
+        impl core::ops::BitAndAssign for $Board {
+            #[inline]
+            fn bitand_assign(&mut self, rhs: Self) {
+                *self = *self & rhs;
+            }
+        }
+
+        impl core::ops::BitOrAssign for $Board {
+            #[inline]
+            fn bitor_assign(&mut self, rhs: Self) {
+                *self = *self | rhs;
+            }
+        }
+
+        impl core::ops::BitXorAssign for $Board {
+            #[inline]
+            fn bitxor_assign(&mut self, rhs: Self) {
+                *self = *self ^ rhs;
+            }
+        }
+
+        impl core::fmt::Display for $Board {
+            /// Render the board as one line per row, top row first, with `1` for a set square
+            /// and `.` for an empty one.
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                use core::fmt::Write;
+                for row in 0..Self::SIZE {
+                    if row != 0 {
+                        f.write_char('\n')?;
+                    }
+                    for col in 0..Self::SIZE {
+                        f.write_char(if self.get(row, col) { '1' } else { '.' })?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+mod attacks;
+mod board16;
+mod board8;
+#[cfg(feature = "alloc")]
+mod magic;
+// re-export
+pub use attacks::{
+    bishop_attacks, king_attacks, knight_attacks, pawn_attacks, queen_attacks, rook_attacks,
+    PawnDirection,
+};
+pub use board16::Bitboard16x16;
+pub use board8::Bitboard8x8;
+#[cfg(feature = "alloc")]
+pub use magic::MagicAttacks;
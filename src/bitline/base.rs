@@ -5,8 +5,25 @@ extern crate std;
 #[cfg(feature = "std")]
 use std::prelude::v1::*;
 
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 use core::option::Option;
 
+use crate::encoding::DecodeError;
+
+/// How the cells past either end of a bitline are treated by neighborhood rules such as
+/// [`Bitline::elementary_ca_step`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Boundary {
+    /// Cells past either end are always unset.
+    Zero,
+    /// The bitline is a ring: the neighbor past the most significant bit is the least
+    /// significant bit and vice versa.
+    Wrap,
+}
+
 /// Bit-manipulation predicates and operations over a fixed-width bit sequence.
 ///
 /// Position arguments use MSB-first indexing: index `0` is the most significant
@@ -280,6 +297,9 @@ pub trait Bitline {
     fn filled_first_bit_to_last_bit(&self) -> Self;
 
     /// Return the bytes size of the bitline.
+    ///
+    /// This is the slice length taken by [`to_be_bit_bytes`](Bitline::to_be_bit_bytes) and
+    /// [`from_be_bit_bytes`](Bitline::from_be_bit_bytes).
     /// # Examples
     /// ```
     /// use bittersweet::bitline::{Bitline, Bitline8, Bitline16, Bitline32, Bitline64};
@@ -796,4 +816,234 @@ pub trait Bitline {
     /// assert_eq!(bitline.select(0, true), Some(3));  // first 1
     /// ```
     fn select(&self, nth: usize, bit: bool) -> Option<usize>;
+
+    /// Return the next generation of the elementary cellular automaton with the given rule.
+    ///
+    /// `rule` is the Wolfram code: the new state of a cell is bit `4 * left + 2 * center +
+    /// right` of `rule`, where `left` is the neighbor at the previous (more significant)
+    /// position and `right` the neighbor at the next one. All cells are updated at once with
+    /// bitwise operations. `boundary` decides whether the neighbors past either end are
+    /// unset ([`Boundary::Zero`]) or taken from the other end ([`Boundary::Wrap`], through
+    /// [`left_rotate`](Bitline::left_rotate) and [`right_rotate`](Bitline::right_rotate)).
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline8, Boundary};
+    /// // Rule 90: each cell becomes the XOR of its two neighbors.
+    /// let bitline = 0b00010000 as Bitline8;
+    /// assert_eq!(bitline.elementary_ca_step(90, Boundary::Zero), 0b00101000);
+    /// // Rule 30 at the edge: wrapping brings the left neighbor in from the other end.
+    /// let bitline = 0b00000001 as Bitline8;
+    /// assert_eq!(bitline.elementary_ca_step(30, Boundary::Zero), 0b00000011);
+    /// assert_eq!(bitline.elementary_ca_step(30, Boundary::Wrap), 0b10000011);
+    /// ```
+    fn elementary_ca_step(&self, rule: u8, boundary: Boundary) -> Self;
+
+    /// Return the run-length encoding of the bitline.
+    ///
+    /// The result lists the lengths of the maximal runs of equal bits, MSB-first, alternating
+    /// between runs of zeros and runs of ones and starting with zeros. The first run is 0
+    /// when the most significant bit is set; every other run is positive, and the runs sum
+    /// to `Self::length()`. Run boundaries are found with
+    /// [`first_bits`](Bitline::first_bits) and [`last_bits`](Bitline::last_bits).
+    ///
+    /// See [`crate::rle`] for bit sequences of any length and a byte serialization of runs.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline8};
+    /// assert_eq!(0b00111001_u8.to_rle(), vec![2, 3, 2, 1]);
+    /// assert_eq!(0b11000000_u8.to_rle(), vec![0, 2, 6]);
+    /// assert_eq!(0b00000000_u8.to_rle(), vec![8]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn to_rle(&self) -> Vec<usize>;
+
+    /// Return the bitline described by alternating run lengths starting with zeros.
+    ///
+    /// This is the inverse of [`to_rle`](Bitline::to_rle). Zero-length runs are allowed
+    /// anywhere and simply contribute no bits.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline8};
+    /// assert_eq!(Bitline8::from_rle(&[2, 3, 2, 1]), 0b00111001);
+    /// assert_eq!(Bitline8::from_rle(&[0, 8]), 0b11111111);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runs do not sum to the bitline length.
+    fn from_rle(runs: &[usize]) -> Self;
+
+    /// Return the bitline described by alternating run lengths starting with zeros, or
+    /// `None` if the runs do not sum to the bitline length.
+    ///
+    /// This is the non-panicking variant of [`from_rle`](Bitline::from_rle).
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline8};
+    /// assert_eq!(Bitline8::try_from_rle(&[4, 4]), Some(0b00001111));
+    /// assert_eq!(Bitline8::try_from_rle(&[4, 5]), None);
+    /// assert_eq!(Bitline8::try_from_rle(&[usize::MAX, 9]), None);
+    /// ```
+    fn try_from_rle(runs: &[usize]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let total = runs
+            .iter()
+            .try_fold(0_usize, |total, run| total.checked_add(*run));
+        if total == Some(Self::length()) {
+            Some(Self::from_rle(runs))
+        } else {
+            None
+        }
+    }
+
+    /// Write the bitline into `bytes`, position 0 at the most significant bit of byte 0.
+    ///
+    /// This matches the order of [`bit_repr`](Bitline::bit_repr): the bytes are the
+    /// big-endian representation of the integer.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline16};
+    /// let mut bytes = [0; 2];
+    /// 0b10000000_00000011_u16.to_be_bit_bytes(&mut bytes);
+    /// assert_eq!(bytes, [0b10000000, 0b00000011]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes.len()` is not [`Self::bytes_length()`](Bitline::bytes_length).
+    fn to_be_bit_bytes(&self, bytes: &mut [u8]);
+
+    /// Return the bitline stored in `bytes`, position 0 at the most significant bit of byte 0.
+    ///
+    /// This is the inverse of [`to_be_bit_bytes`](Bitline::to_be_bit_bytes).
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline16};
+    /// assert_eq!(Bitline16::from_be_bit_bytes(&[0x80, 0x03]), 0b10000000_00000011);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes.len()` is not [`Self::bytes_length()`](Bitline::bytes_length).
+    fn from_be_bit_bytes(bytes: &[u8]) -> Self;
+
+    /// Return the bitline stored in `bytes`, or `None` if `bytes.len()` is not
+    /// [`Self::bytes_length()`](Bitline::bytes_length).
+    ///
+    /// This is the non-panicking variant of [`from_be_bit_bytes`](Bitline::from_be_bit_bytes).
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline16};
+    /// assert_eq!(Bitline16::try_from_be_bit_bytes(&[0x80, 0x03]), Some(0x8003));
+    /// assert_eq!(Bitline16::try_from_be_bit_bytes(&[0x80]), None);
+    /// ```
+    fn try_from_be_bit_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if bytes.len() == Self::bytes_length() {
+            Some(Self::from_be_bit_bytes(bytes))
+        } else {
+            None
+        }
+    }
+
+    /// Return the bytes of [`to_be_bit_bytes`](Bitline::to_be_bit_bytes) as lowercase
+    /// hexadecimal digits.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline32};
+    /// assert_eq!(0x0123abcd_u32.to_hex(), "0123abcd");
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn to_hex(&self) -> String;
+
+    /// Write the hexadecimal digits of [`to_hex`](Bitline::to_hex) into `out` and return
+    /// them as a string, without allocating.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline32};
+    /// use bittersweet::encoding::hex_length;
+    /// let mut out = [0; hex_length(4)];
+    /// assert_eq!(0x0123abcd_u32.encode_hex_into(&mut out), "0123abcd");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out.len()` is not
+    /// [`hex_length(Self::bytes_length())`](crate::encoding::hex_length).
+    fn encode_hex_into<'a>(&self, out: &'a mut [u8]) -> &'a str;
+
+    /// Return the bitline encoded by hexadecimal digits of either case.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline32};
+    /// use bittersweet::encoding::DecodeError;
+    /// assert_eq!(Bitline32::from_hex("0123ABCD"), Ok(0x0123abcd));
+    /// assert_eq!(Bitline32::from_hex("123abcd"), Err(DecodeError::InvalidLength));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails as [`decode_hex`](crate::encoding::decode_hex) does for
+    /// [`Self::bytes_length()`](Bitline::bytes_length) bytes.
+    fn from_hex(text: &str) -> Result<Self, DecodeError>
+    where
+        Self: Sized;
+
+    /// Return the bytes of [`to_be_bit_bytes`](Bitline::to_be_bit_bytes) as padded
+    /// standard base64.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline32};
+    /// assert_eq!(0x62697473_u32.to_base64(), "Yml0cw==");
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn to_base64(&self) -> String;
+
+    /// Write the base64 text of [`to_base64`](Bitline::to_base64) into `out` and return it
+    /// as a string, without allocating.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline32};
+    /// use bittersweet::encoding::base64_length;
+    /// let mut out = [0; base64_length(4)];
+    /// assert_eq!(0x62697473_u32.encode_base64_into(&mut out), "Yml0cw==");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out.len()` is not
+    /// [`base64_length(Self::bytes_length())`](crate::encoding::base64_length).
+    fn encode_base64_into<'a>(&self, out: &'a mut [u8]) -> &'a str;
+
+    /// Return the bitline encoded by padded standard base64.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::{Bitline, Bitline32};
+    /// assert_eq!(Bitline32::from_base64("Yml0cw=="), Ok(0x62697473));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails as [`decode_base64`](crate::encoding::decode_base64) does for
+    /// [`Self::bytes_length()`](Bitline::bytes_length) bytes.
+    fn from_base64(text: &str) -> Result<Self, DecodeError>
+    where
+        Self: Sized;
 }
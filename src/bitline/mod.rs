@@ -8,6 +8,8 @@
 
 mod base;
 mod uints;
+mod word;
 // re-export
-pub use base::Bitline;
+pub use base::{Bitline, Boundary};
 pub use uints::{Bitline128, Bitline16, Bitline32, Bitline64, Bitline8};
+pub use word::BitlineWord;
@@ -5,7 +5,13 @@ extern crate std;
 #[cfg(feature = "std")]
 use std::prelude::v1::*;
 
-use crate::bitline::base::Bitline;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::bitline::base::{Bitline, Boundary};
+use crate::encoding::{self, DecodeError};
 use core::cmp;
 use core::option::{
     Option,
@@ -221,14 +227,7 @@ macro_rules! impl_Bitline {
 
             #[inline]
             fn bin_to_bit_reversal_permutation(&self) -> Self {
-                let bits = Self::length();
-                let mut reversed_index = 0;
-                for i in 0..bits {
-                    if (*self & (1 << i)) != 0 {
-                        reversed_index |= 1 << (bits - 1 - i);
-                    }
-                }
-                reversed_index
+                self.reverse_bits()
             }
             #[inline]
             fn bit_reversal_permutation_to_bin(&self) -> Self {
@@ -340,6 +339,105 @@ macro_rules! impl_Bitline {
                     self.select_0(nth)
                 }
             }
+            #[inline]
+            fn elementary_ca_step(&self, rule: u8, boundary: Boundary) -> Self {
+                // Align each cell's left and right neighbors with the cell itself.
+                let (left, right) = match boundary {
+                    Boundary::Zero => (self >> 1, self << 1),
+                    Boundary::Wrap => (self.right_rotate(1), self.left_rotate(1)),
+                };
+                let mut next = 0;
+                for pattern in 0..8 {
+                    if (rule >> pattern) & 1 == 0 {
+                        continue;
+                    }
+                    let l = if pattern & 0b100 != 0 { left } else { !left };
+                    let c = if pattern & 0b010 != 0 { *self } else { !*self };
+                    let r = if pattern & 0b001 != 0 { right } else { !right };
+                    next |= l & c & r;
+                }
+                next
+            }
+            #[cfg(feature = "alloc")]
+            fn to_rle(&self) -> Vec<usize> {
+                let mut runs = Vec::new();
+                let mut starts = self.first_bits();
+                let mut ends = self.last_bits();
+                let mut position = 0;
+                // Each run of ones has one bit in `starts` and one in `ends`, in order.
+                while let (Some(start), Some(end)) = (starts.first_index(), ends.first_index()) {
+                    runs.push(start - position);
+                    runs.push(end + 1 - start);
+                    position = end + 1;
+                    starts = starts.remove(starts.first_bit());
+                    ends = ends.remove(ends.first_bit());
+                }
+                if position < Self::length() {
+                    runs.push(Self::length() - position);
+                }
+                runs
+            }
+            fn from_rle(runs: &[usize]) -> Self {
+                let total = runs
+                    .iter()
+                    .try_fold(0_usize, |total, run| total.checked_add(*run));
+                assert!(
+                    total == Some(Self::length()),
+                    "run lengths do not sum to the bitline length"
+                );
+                let mut bitline = Self::as_empty();
+                let mut position = 0;
+                // Odd-numbered runs are the runs of ones.
+                for (i, &run) in runs.iter().enumerate() {
+                    if i % 2 == 1 {
+                        bitline |= Self::by_range(position, position + run);
+                    }
+                    position += run;
+                }
+                bitline
+            }
+            fn to_be_bit_bytes(&self, bytes: &mut [u8]) {
+                assert!(
+                    bytes.len() == Self::bytes_length(),
+                    "byte count does not match the bitline length"
+                );
+                bytes.copy_from_slice(&self.to_be_bytes());
+            }
+            fn from_be_bit_bytes(bytes: &[u8]) -> Self {
+                assert!(
+                    bytes.len() == Self::bytes_length(),
+                    "byte count does not match the bitline length"
+                );
+                let mut be_bytes = [0; $Size / 8];
+                be_bytes.copy_from_slice(bytes);
+                Self::from_be_bytes(be_bytes)
+            }
+            #[cfg(feature = "alloc")]
+            fn to_hex(&self) -> String {
+                let mut out = [0; encoding::hex_length($Size / 8)];
+                String::from(self.encode_hex_into(&mut out))
+            }
+            fn encode_hex_into<'a>(&self, out: &'a mut [u8]) -> &'a str {
+                encoding::encode_hex(&self.to_be_bytes(), out)
+            }
+            fn from_hex(text: &str) -> Result<Self, DecodeError> {
+                let mut be_bytes = [0; $Size / 8];
+                encoding::decode_hex(text, &mut be_bytes)?;
+                Ok(Self::from_be_bytes(be_bytes))
+            }
+            #[cfg(feature = "alloc")]
+            fn to_base64(&self) -> String {
+                let mut out = [0; encoding::base64_length($Size / 8)];
+                String::from(self.encode_base64_into(&mut out))
+            }
+            fn encode_base64_into<'a>(&self, out: &'a mut [u8]) -> &'a str {
+                encoding::encode_base64(&self.to_be_bytes(), out)
+            }
+            fn from_base64(text: &str) -> Result<Self, DecodeError> {
+                let mut be_bytes = [0; $Size / 8];
+                encoding::decode_base64(text, &mut be_bytes)?;
+                Ok(Self::from_be_bytes(be_bytes))
+            }
         }
     };
 }
@@ -355,6 +453,7 @@ mod tests {
     use std::collections::HashMap;
 
     use super::*;
+    use crate::test_util::xorshift64;
 
     #[test]
     fn test_as_empty() {
@@ -999,6 +1098,173 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_elementary_ca_step() {
+        // Compare every rule and every 8-bit state with a per-cell evaluation.
+        for rule in 0..=255_u8 {
+            for bitline in 0..=255_u8 {
+                for &boundary in [Boundary::Zero, Boundary::Wrap].iter() {
+                    let mut expected = 0_u8;
+                    for i in 0..8 {
+                        let cell = |j: isize| -> u8 {
+                            let j = match boundary {
+                                Boundary::Zero if !(0..8).contains(&j) => return 0,
+                                _ => j.rem_euclid(8) as u32,
+                            };
+                            (bitline >> (7 - j)) & 1
+                        };
+                        let i = i as isize;
+                        let pattern = cell(i - 1) << 2 | cell(i) << 1 | cell(i + 1);
+                        expected |= ((rule >> pattern) & 1) << (7 - i);
+                    }
+                    assert_eq!(bitline.elementary_ca_step(rule, boundary), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_elementary_ca_step_wide() {
+        // Rule 90 from a single cell draws the Sierpinski triangle: the cells at generation
+        // `t` are those `t - 2k` away whose binomial coefficient C(t, k) is odd.
+        let mut bitline = 1_u128 << 64;
+        for _ in 0..32 {
+            bitline = bitline.elementary_ca_step(90, Boundary::Zero);
+        }
+        assert_eq!(bitline, (1_u128 << 96) | (1 << 32));
+        // Rule 204 is the identity and rule 51 the complement.
+        let bitline = 0x0123_4567_89ab_cdef_u64;
+        assert_eq!(bitline.elementary_ca_step(204, Boundary::Wrap), bitline);
+        assert_eq!(bitline.elementary_ca_step(51, Boundary::Zero), !bitline);
+        // Rule 170 shifts towards the MSB; rule 240 towards the LSB.
+        assert_eq!(
+            0x8001_u16.elementary_ca_step(170, Boundary::Wrap),
+            0x0003_u16
+        );
+        assert_eq!(0x8001_u16.elementary_ca_step(240, Boundary::Zero), 0x4000);
+        assert_eq!(
+            0x8001_u32.elementary_ca_step(240, Boundary::Wrap),
+            0x8000_4000_u32
+        );
+    }
+
+    fn naive_rle(bits: &str) -> Vec<usize> {
+        let mut runs = vec![0];
+        let mut current = '0';
+        for bit in bits.chars() {
+            if bit != current {
+                runs.push(0);
+                current = bit;
+            }
+            *runs.last_mut().unwrap() += 1;
+        }
+        if runs.len() > 1 && runs[0] == 0 && bits.starts_with('0') {
+            runs.remove(0);
+        }
+        runs
+    }
+
+    #[test]
+    fn test_to_rle_u8_exhaustive() {
+        for bitline in 0..=255_u8 {
+            let runs = bitline.to_rle();
+            assert_eq!(runs, naive_rle(&bitline.bit_repr()), "{:08b}", bitline);
+            assert_eq!(runs.iter().sum::<usize>(), 8);
+            assert_eq!(u8::from_rle(&runs), bitline);
+        }
+    }
+
+    #[test]
+    fn test_rle_round_trip_wide() {
+        let mut state = 0x9e3779b97f4a7c15_u64;
+        for _ in 0..200 {
+            xorshift64(&mut state);
+            // widened so that long runs are common
+            let sparse = state & (state >> 3) & (state >> 7);
+            let wide = (sparse as u128) << 64 | (state | (state << 5)) as u128;
+            assert_eq!(u16::from_rle(&(sparse as u16).to_rle()), sparse as u16);
+            assert_eq!(u32::from_rle(&(sparse as u32).to_rle()), sparse as u32);
+            assert_eq!(u64::from_rle(&sparse.to_rle()), sparse);
+            assert_eq!(u128::from_rle(&wide.to_rle()), wide);
+            assert_eq!(wide.to_rle(), naive_rle(&wide.bit_repr()));
+        }
+        assert_eq!(u128::as_full().to_rle(), vec![0, 128]);
+        assert_eq!(u128::as_empty().to_rle(), vec![128]);
+        assert_eq!(1_u64.to_rle(), vec![63, 1]);
+        assert_eq!(u64::from_rle(&[0, 0, 63, 0, 0, 1]), 1);
+    }
+
+    #[test]
+    fn test_try_from_rle() {
+        assert_eq!(u16::try_from_rle(&[15, 1]), Some(1));
+        assert_eq!(u16::try_from_rle(&[15]), None);
+        assert_eq!(u16::try_from_rle(&[]), None);
+        assert_eq!(u128::try_from_rle(&[0, 128, 0]), Some(u128::as_full()));
+        assert_eq!(u32::try_from_rle(&[usize::MAX, 1, 32]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "run lengths do not sum to the bitline length")]
+    fn test_from_rle_panics_on_wrong_length() {
+        u32::from_rle(&[16, 17]);
+    }
+
+    macro_rules! assert_byte_conversions {
+        ($t:ty, $value:expr) => {{
+            let value: $t = $value;
+            let mut bytes = vec![0; <$t>::bytes_length()];
+            value.to_be_bit_bytes(&mut bytes);
+            // Position `i` of `bit_repr` is bit `7 - i % 8` of byte `i / 8`.
+            let bits: String = bytes.iter().map(|byte| format!("{:08b}", byte)).collect();
+            assert_eq!(bits, value.bit_repr());
+            assert_eq!(<$t>::from_be_bit_bytes(&bytes), value);
+            assert_eq!(<$t>::try_from_be_bit_bytes(&bytes), Some(value));
+            assert_eq!(<$t>::try_from_be_bit_bytes(&bytes[1..]), None);
+            assert_eq!(
+                value.to_hex(),
+                format!("{:0width$x}", value, width = <$t>::length() / 4)
+            );
+            assert_eq!(<$t>::from_hex(&value.to_hex()), Ok(value));
+            assert_eq!(<$t>::from_hex(&value.to_hex().to_uppercase()), Ok(value));
+            assert_eq!(<$t>::from_base64(&value.to_base64()), Ok(value));
+            let mut hex = vec![0; crate::encoding::hex_length(bytes.len())];
+            assert_eq!(value.encode_hex_into(&mut hex), value.to_hex());
+            let mut base64 = vec![0; crate::encoding::base64_length(bytes.len())];
+            assert_eq!(value.encode_base64_into(&mut base64), value.to_base64());
+            assert_eq!(
+                <$t>::from_hex("0"),
+                Err(crate::encoding::DecodeError::InvalidLength)
+            );
+        }};
+    }
+
+    #[test]
+    fn test_byte_conversions() {
+        let mut state = 0x9e3779b97f4a7c15_u64;
+        for _ in 0..50 {
+            xorshift64(&mut state);
+            assert_byte_conversions!(u8, state as u8);
+            assert_byte_conversions!(u16, state as u16);
+            assert_byte_conversions!(u32, state as u32);
+            assert_byte_conversions!(u64, state);
+            assert_byte_conversions!(u128, (state as u128) << 64 | state.rotate_left(7) as u128);
+        }
+        assert_eq!(u128::MAX.to_base64(), "/////////////////////w==");
+        assert_eq!(0_u8.to_base64(), "AA==");
+    }
+
+    #[test]
+    #[should_panic(expected = "output length does not match the encoded length")]
+    fn test_encode_hex_into_panics_on_wrong_length() {
+        0_u32.encode_hex_into(&mut [0; 9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "byte count does not match the bitline length")]
+    fn test_to_be_bit_bytes_panics_on_wrong_length() {
+        0_u32.to_be_bit_bytes(&mut [0; 3]);
+    }
+
     fn assert_bijection(function: fn(u8) -> u8) {
         // bijection means no collision.
         let mut counter = HashMap::new();
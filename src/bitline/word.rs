@@ -0,0 +1,92 @@
+use core::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr};
+
+use crate::bitline::base::Bitline;
+
+/// A bitline integer with the operators and arithmetic that word-parallel algorithms need.
+///
+/// Unlike the positions of [`Bitline`], the constants and conversions here count bits from
+/// the least significant end, as integer arithmetic does. This is implemented for `u8`
+/// through `u128`.
+///
+/// # Examples
+/// ```
+/// use bittersweet::bitline::BitlineWord;
+/// fn lowest_bits<W: BitlineWord>(n: u32) -> W {
+///     (W::ONE << n).wrapping_add(!W::ZERO)
+/// }
+/// assert_eq!(lowest_bits::<u8>(3), 0b0000_0111);
+/// assert_eq!(u16::from_byte(0xab).low_byte(), 0xab);
+/// assert_eq!(u32::from_u128(0x1_2345_6789), 0x2345_6789);
+/// ```
+pub trait BitlineWord:
+    Bitline
+    + Copy
+    + Eq
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    /// The word with no bit set.
+    const ZERO: Self;
+
+    /// The word with only the least significant bit set.
+    const ONE: Self;
+
+    /// Add `other`, wrapping around at the word boundary.
+    fn wrapping_add(self, other: Self) -> Self;
+
+    /// Return the word whose least significant byte is `byte`.
+    fn from_byte(byte: u8) -> Self;
+
+    /// Return the least significant byte of the word.
+    fn low_byte(self) -> u8;
+
+    /// Return the low `Self::length()` bits of `value`.
+    fn from_u128(value: u128) -> Self;
+
+    /// Return the word as a `u128`.
+    fn to_u128(self) -> u128;
+}
+
+macro_rules! impl_BitlineWord {
+    ($T:ty) => {
+        impl BitlineWord for $T {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            #[inline]
+            fn wrapping_add(self, other: Self) -> Self {
+                <$T>::wrapping_add(self, other)
+            }
+
+            #[inline]
+            fn from_byte(byte: u8) -> Self {
+                <$T>::from(byte)
+            }
+
+            #[inline]
+            fn low_byte(self) -> u8 {
+                self as u8
+            }
+
+            #[inline]
+            fn from_u128(value: u128) -> Self {
+                value as $T
+            }
+
+            #[inline]
+            fn to_u128(self) -> u128 {
+                self as u128
+            }
+        }
+    };
+}
+
+impl_BitlineWord!(u8);
+impl_BitlineWord!(u16);
+impl_BitlineWord!(u32);
+impl_BitlineWord!(u64);
+impl_BitlineWord!(u128);
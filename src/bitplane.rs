@@ -0,0 +1,254 @@
+//! Bit-plane slicing and reassembly for arrays of unsigned samples.
+//!
+//! Plane `k` collects bit `k` of every sample, with the MSB-first numbering of
+//! [`crate::bitline`]: plane 0 holds the most significant bit of each sample. A `u8` sample
+//! therefore has 8 planes, a `u16` 16 and a `u32` 32.
+//!
+//! Within a plane, samples are packed into `u64` words in order, MSB first: sample `i` is
+//! bit `63 - i % 64` of word `i / 64`. The bits past the last sample in the final word are
+//! zero.
+//!
+//! The conversion works on blocks of 64 samples, splitting each block into 8×8 tiles of
+//! bytes that are transposed with [`transpose8x8_u64`]. The `block_*` functions convert one
+//! such block without allocating; with the `alloc` feature, [`to_bit_planes`] and
+//! [`from_bit_planes`] (and their `_u16`/`_u32` variants) convert slices of any length.
+//!
+//! # Example
+//!
+//! ```
+//! use bittersweet::bitplane::{from_bit_planes, to_bit_planes};
+//! let samples = [0b1000_0001_u8, 0b0000_0001, 0b1111_1111];
+//! let planes = to_bit_planes(&samples);
+//! assert_eq!(planes[0], vec![0b101 << 61]); // the MSB of each sample
+//! assert_eq!(planes[7], vec![0b111 << 61]); // the LSB of each sample
+//! assert_eq!(from_bit_planes(&planes, samples.len()), samples);
+//! ```
+//!
+//! See also: [`crate::matrix`] for the transposes used here.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::matrix::transpose8x8_u64;
+
+macro_rules! impl_bit_planes {
+    (
+        $T:ty, $BYTES:literal, $PLANES:literal, $Bits:literal,
+        $block_to:ident, $block_from:ident, $to:ident, $from:ident
+    ) => {
+        #[doc = concat!("Split a block of 64 `", stringify!($T), "` samples into its ", $Bits, " bit planes.")]
+        ///
+        /// Word `k` of the result is plane `k`, whose MSB is bit `k` (MSB-first) of
+        /// `samples[0]`. See the [module-level documentation](self) for the layout.
+        pub fn $block_to(samples: &[$T; 64]) -> [u64; $PLANES] {
+            let mut planes = [0_u64; $PLANES];
+            for (tile, chunk) in samples.chunks(8).enumerate() {
+                for byte in 0..$BYTES {
+                    // Row `i` of the tile is byte `byte` (MSB-first) of sample `i`.
+                    let shift = 8 * ($BYTES - 1 - byte);
+                    let rows = chunk
+                        .iter()
+                        .fold(0_u64, |rows, sample| rows << 8 | ((sample >> shift) & 0xff) as u64);
+                    // After the transpose, row `k` holds bit `k` of every sample in the tile.
+                    let columns = transpose8x8_u64(rows).to_be_bytes();
+                    for (k, column) in columns.iter().enumerate() {
+                        planes[byte * 8 + k] |= (*column as u64) << (56 - 8 * tile);
+                    }
+                }
+            }
+            planes
+        }
+
+        #[doc = concat!("Reassemble a block of 64 `", stringify!($T), "` samples from its ", $Bits, " bit planes.")]
+        ///
+        #[doc = concat!("This is the inverse of [`", stringify!($block_to), "`].")]
+        pub fn $block_from(planes: &[u64; $PLANES]) -> [$T; 64] {
+            let mut samples = [0; 64];
+            for (tile, chunk) in samples.chunks_mut(8).enumerate() {
+                for byte in 0..$BYTES {
+                    // Row `k` of the tile is bit `k` of byte `byte` of the tile's 8 samples.
+                    let rows = planes[byte * 8..byte * 8 + 8]
+                        .iter()
+                        .fold(0_u64, |rows, plane| rows << 8 | (plane >> (56 - 8 * tile)) & 0xff);
+                    let shift = 8 * ($BYTES - 1 - byte);
+                    let bytes = transpose8x8_u64(rows).to_be_bytes();
+                    for (sample, byte) in chunk.iter_mut().zip(bytes.iter()) {
+                        *sample |= (*byte as $T) << shift;
+                    }
+                }
+            }
+            samples
+        }
+
+        #[doc = concat!("Split `", stringify!($T), "` samples into their ", $Bits, " bit planes.")]
+        ///
+        /// Each plane has `(samples.len() + 63) / 64` words, and the bits past the last sample
+        /// are zero. See the [module-level documentation](self) for the layout.
+        #[cfg(feature = "alloc")]
+        pub fn $to(samples: &[$T]) -> [Vec<u64>; $PLANES] {
+            let words = (samples.len() + 63) / 64;
+            let mut planes: [Vec<u64>; $PLANES] = Default::default();
+            for plane in planes.iter_mut() {
+                plane.reserve_exact(words);
+            }
+            for chunk in samples.chunks(64) {
+                let mut block = [0; 64];
+                block[..chunk.len()].copy_from_slice(chunk);
+                for (plane, word) in planes.iter_mut().zip($block_to(&block).iter()) {
+                    plane.push(*word);
+                }
+            }
+            planes
+        }
+
+        #[doc = concat!("Reassemble `len` `", stringify!($T), "` samples from their ", $Bits, " bit planes.")]
+        ///
+        #[doc = concat!("This is the inverse of [`", stringify!($to), "`]. Bits past the last sample are ignored.")]
+        ///
+        /// # Panics
+        ///
+        /// Panics if a plane does not have exactly `(len + 63) / 64` words.
+        #[cfg(feature = "alloc")]
+        pub fn $from(planes: &[Vec<u64>; $PLANES], len: usize) -> Vec<$T> {
+            let words = (len + 63) / 64;
+            assert!(
+                planes.iter().all(|plane| plane.len() == words),
+                "plane length does not match the sample count"
+            );
+            let mut samples = Vec::with_capacity(len);
+            for word in 0..words {
+                let mut block = [0; $PLANES];
+                for (bits, plane) in block.iter_mut().zip(planes.iter()) {
+                    *bits = plane[word];
+                }
+                let count = core::cmp::min(64, len - word * 64);
+                samples.extend_from_slice(&$block_from(&block)[..count]);
+            }
+            samples
+        }
+    };
+}
+
+impl_bit_planes!(
+    u8,
+    1,
+    8,
+    "8",
+    block_to_bit_planes,
+    block_from_bit_planes,
+    to_bit_planes,
+    from_bit_planes
+);
+impl_bit_planes!(
+    u16,
+    2,
+    16,
+    "16",
+    block_to_bit_planes_u16,
+    block_from_bit_planes_u16,
+    to_bit_planes_u16,
+    from_bit_planes_u16
+);
+impl_bit_planes!(
+    u32,
+    4,
+    32,
+    "32",
+    block_to_bit_planes_u32,
+    block_from_bit_planes_u32,
+    to_bit_planes_u32,
+    from_bit_planes_u32
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::xorshift64;
+
+    fn naive_planes(samples: &[u32], bits: usize) -> Vec<Vec<u64>> {
+        let words = (samples.len() + 63) / 64;
+        let mut planes = vec![vec![0_u64; words]; bits];
+        for (i, sample) in samples.iter().enumerate() {
+            for (k, plane) in planes.iter_mut().enumerate() {
+                if (sample >> (bits - 1 - k)) & 1 == 1 {
+                    plane[i / 64] |= 1 << (63 - i % 64);
+                }
+            }
+        }
+        planes
+    }
+
+    #[test]
+    fn test_block_round_trip() {
+        let mut state = 0x9e3779b97f4a7c15;
+        let mut bytes = [0_u8; 64];
+        let mut words = [0_u16; 64];
+        let mut dwords = [0_u32; 64];
+        for i in 0..64 {
+            let x = xorshift64(&mut state);
+            bytes[i] = x as u8;
+            words[i] = x as u16;
+            dwords[i] = x as u32;
+        }
+        assert_eq!(block_from_bit_planes(&block_to_bit_planes(&bytes)), bytes);
+        assert_eq!(
+            block_from_bit_planes_u16(&block_to_bit_planes_u16(&words)),
+            words
+        );
+        assert_eq!(
+            block_from_bit_planes_u32(&block_to_bit_planes_u32(&dwords)),
+            dwords
+        );
+    }
+
+    #[test]
+    fn test_block_single_bits() {
+        let mut samples = [0_u16; 64];
+        samples[5] = 0x8000;
+        samples[63] = 0x0001;
+        let mut expected = [0_u64; 16];
+        expected[0] = 1 << 58;
+        expected[15] = 1;
+        assert_eq!(block_to_bit_planes_u16(&samples), expected);
+    }
+
+    #[test]
+    fn test_matches_naive() {
+        let mut state = 42;
+        for &len in [0, 1, 7, 63, 64, 65, 200].iter() {
+            let samples: Vec<u32> = (0..len).map(|_| xorshift64(&mut state) as u32).collect();
+
+            let bytes: Vec<u8> = samples.iter().map(|s| *s as u8).collect();
+            let planes = to_bit_planes(&bytes);
+            let widened: Vec<u32> = bytes.iter().map(|b| *b as u32).collect();
+            assert_eq!(planes.to_vec(), naive_planes(&widened, 8));
+            assert_eq!(from_bit_planes(&planes, len), bytes);
+
+            let words: Vec<u16> = samples.iter().map(|s| *s as u16).collect();
+            let planes = to_bit_planes_u16(&words);
+            let widened: Vec<u32> = words.iter().map(|w| *w as u32).collect();
+            assert_eq!(planes.to_vec(), naive_planes(&widened, 16));
+            assert_eq!(from_bit_planes_u16(&planes, len), words);
+
+            let planes = to_bit_planes_u32(&samples);
+            assert_eq!(planes.to_vec(), naive_planes(&samples, 32));
+            assert_eq!(from_bit_planes_u32(&planes, len), samples);
+        }
+    }
+
+    #[test]
+    fn test_from_bit_planes_ignores_padding() {
+        let mut planes: [Vec<u64>; 8] = Default::default();
+        for plane in planes.iter_mut() {
+            plane.push(u64::MAX);
+        }
+        assert_eq!(from_bit_planes(&planes, 3), vec![0xff; 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "plane length does not match")]
+    fn test_from_bit_planes_panics_on_short_planes() {
+        let planes: [Vec<u64>; 8] = Default::default();
+        from_bit_planes(&planes, 1);
+    }
+}
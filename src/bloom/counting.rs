@@ -0,0 +1,384 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use super::error::BloomError;
+use super::filter::BloomFilter;
+use super::hasher::DefaultHashBuilder;
+use super::params::{
+    optimal_parameters, probes, read_header, read_words, reduce, write_header, write_words,
+    HEADER_LENGTH,
+};
+
+/// Number of 4-bit counters packed in a `u64` word.
+const COUNTERS_PER_WORD: usize = 16;
+
+/// The largest counter value; a counter that reaches it stays there.
+const COUNTER_MAX: u64 = 0xf;
+
+/// A counting Bloom filter, whose items can be removed as well as inserted.
+///
+/// Every bit of a [`BloomFilter`] becomes a 4-bit counter, 16 to a `u64` word with counter 0
+/// in the most significant nibble. Inserting an item increments its counters and removing
+/// it decrements them. A counter that reaches 15 saturates and is never decremented again,
+/// so removals cannot cause false negatives, at the cost of some items never fully leaving.
+/// Removing an item that was never inserted can remove other items, as with any counting
+/// filter.
+///
+/// # Examples
+/// ```
+/// use bittersweet::bloom::CountingBloomFilter;
+/// let mut filter = CountingBloomFilter::with_rate(1000, 0.01);
+/// filter.insert("apple");
+/// filter.insert("pear");
+/// assert!(filter.remove("apple"));
+/// assert!(!filter.contains("apple"));
+/// assert!(filter.contains("pear"));
+/// ```
+#[derive(Clone)]
+pub struct CountingBloomFilter<S = DefaultHashBuilder> {
+    words: Vec<u64>,
+    num_counters: usize,
+    num_hashes: u32,
+    hash_builder: S,
+}
+
+impl CountingBloomFilter {
+    /// Return an empty filter of `num_counters` counters that updates `num_hashes` counters
+    /// per item.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_counters` or `num_hashes` is zero.
+    pub fn new(num_counters: usize, num_hashes: u32) -> Self {
+        Self::with_hasher(num_counters, num_hashes, DefaultHashBuilder::default())
+    }
+
+    /// Return an empty filter sized like [`BloomFilter::with_rate`], with one counter for
+    /// each bit of the Bloom filter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `expected_items` is zero or `false_positive_rate` is not strictly between 0
+    /// and 1.
+    pub fn with_rate(expected_items: usize, false_positive_rate: f64) -> Self {
+        Self::with_rate_and_hasher(
+            expected_items,
+            false_positive_rate,
+            DefaultHashBuilder::default(),
+        )
+    }
+
+    /// Read a filter written by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BloomError::UnexpectedEnd`] if `bytes` is too short and
+    /// [`BloomError::InvalidParameters`] if the header describes an impossible filter.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BloomError> {
+        Self::from_bytes_with_hasher(bytes, DefaultHashBuilder::default())
+    }
+}
+
+impl<S: BuildHasher> CountingBloomFilter<S> {
+    /// Return an empty filter like [`new`](CountingBloomFilter::new) that hashes items with
+    /// `hash_builder`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_counters` or `num_hashes` is zero.
+    pub fn with_hasher(num_counters: usize, num_hashes: u32, hash_builder: S) -> Self {
+        assert!(
+            num_counters > 0,
+            "a counting Bloom filter needs at least one counter"
+        );
+        assert!(num_hashes > 0, "a Bloom filter needs at least one hash");
+        CountingBloomFilter {
+            words: vec![0; word_count(num_counters)],
+            num_counters,
+            num_hashes,
+            hash_builder,
+        }
+    }
+
+    /// Return an empty filter like [`with_rate`](CountingBloomFilter::with_rate) that
+    /// hashes items with `hash_builder`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `expected_items` is zero or `false_positive_rate` is not strictly between 0
+    /// and 1.
+    pub fn with_rate_and_hasher(
+        expected_items: usize,
+        false_positive_rate: f64,
+        hash_builder: S,
+    ) -> Self {
+        let (num_counters, num_hashes) = optimal_parameters(expected_items, false_positive_rate);
+        Self::with_hasher(num_counters, num_hashes, hash_builder)
+    }
+
+    /// Read a filter written by [`to_bytes`](Self::to_bytes) that hashes items with
+    /// `hash_builder`, which must hash like the builder of the filter written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BloomError::UnexpectedEnd`] if `bytes` is too short and
+    /// [`BloomError::InvalidParameters`] if the header describes an impossible filter.
+    pub fn from_bytes_with_hasher(bytes: &[u8], hash_builder: S) -> Result<Self, BloomError> {
+        let (num_hashes, num_counters, rest) = read_header(bytes)?;
+        let mut words = read_words(rest, word_count(num_counters))?;
+        let tail = num_counters % COUNTERS_PER_WORD;
+        if let (Some(last), true) = (words.last_mut(), tail != 0) {
+            *last &= !0 << (4 * (COUNTERS_PER_WORD - tail));
+        }
+        Ok(CountingBloomFilter {
+            words,
+            num_counters,
+            num_hashes,
+            hash_builder,
+        })
+    }
+
+    /// Add `item`, returning true if it was not already reported as present.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        let mut inserted = false;
+        for probe in probes(self.hash(item), self.num_hashes) {
+            let index = reduce(probe, self.num_counters);
+            let counter = self.counter(index);
+            inserted |= counter == 0;
+            if counter < COUNTER_MAX {
+                self.words[index / COUNTERS_PER_WORD] += 1 << shift(index);
+            }
+        }
+        inserted
+    }
+
+    /// Remove one insertion of `item`, returning false and leaving the filter unchanged if
+    /// the item is certainly not present.
+    pub fn remove<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        let hash = self.hash(item);
+        if !self.contains_hash(hash) {
+            return false;
+        }
+        for probe in probes(hash, self.num_hashes) {
+            let index = reduce(probe, self.num_counters);
+            // Only removals of items never inserted can have emptied the counter already.
+            let counter = self.counter(index);
+            if counter != 0 && counter < COUNTER_MAX {
+                self.words[index / COUNTERS_PER_WORD] -= 1 << shift(index);
+            }
+        }
+        true
+    }
+
+    /// Return true if `item` may be present, and false if it certainly is not.
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        self.contains_hash(self.hash(item))
+    }
+
+    /// Return an upper bound on the number of times `item` was inserted and not removed:
+    /// the smallest of its counters, where 15 means 15 or more.
+    pub fn count<T: Hash + ?Sized>(&self, item: &T) -> u32 {
+        probes(self.hash(item), self.num_hashes)
+            .map(|probe| self.counter(reduce(probe, self.num_counters)) as u32)
+            .min()
+            .unwrap_or(0)
+    }
+
+    fn contains_hash(&self, hash: u64) -> bool {
+        probes(hash, self.num_hashes)
+            .all(|probe| self.counter(reduce(probe, self.num_counters)) != 0)
+    }
+
+    fn hash<T: Hash + ?Sized>(&self, item: &T) -> u64 {
+        let mut hasher = self.hash_builder.build_hasher();
+        item.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl<S> CountingBloomFilter<S> {
+    /// Return the number of counters of the filter.
+    pub fn num_counters(&self) -> usize {
+        self.num_counters
+    }
+
+    /// Return the number of counters updated for each item.
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Return the hash builder of the filter.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Return true if nothing is present.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Remove every item.
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Return the length in bytes of [`to_bytes`](Self::to_bytes)'s output.
+    pub fn serialized_size(&self) -> usize {
+        HEADER_LENGTH + 8 * self.words.len()
+    }
+
+    /// Return the filter as bytes, laid out like [`BloomFilter::to_bytes`] with the number
+    /// of counters in place of the number of bits and the packed counter words in place of
+    /// the bits.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bloom::CountingBloomFilter;
+    /// let mut filter = CountingBloomFilter::new(100, 3);
+    /// filter.insert(&42);
+    /// let bytes = filter.to_bytes();
+    /// assert_eq!(bytes.len(), filter.serialized_size());
+    /// assert_eq!(CountingBloomFilter::from_bytes(&bytes), Ok(filter));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.serialized_size());
+        write_header(&mut bytes, self.num_hashes, self.num_counters);
+        write_words(&mut bytes, &self.words);
+        bytes
+    }
+
+    fn counter(&self, index: usize) -> u64 {
+        (self.words[index / COUNTERS_PER_WORD] >> shift(index)) & COUNTER_MAX
+    }
+}
+
+impl<S: BuildHasher + Clone> CountingBloomFilter<S> {
+    /// Return the plain Bloom filter with a bit set for every nonzero counter, which
+    /// reports the same items in an eighth of the space.
+    pub fn to_bloom_filter(&self) -> BloomFilter<S> {
+        let mut filter = BloomFilter::with_hasher(
+            self.num_counters,
+            self.num_hashes,
+            self.hash_builder.clone(),
+        );
+        for index in (0..self.num_counters).filter(|&index| self.counter(index) != 0) {
+            filter.words[index / 64] |= 1 << (63 - index % 64);
+        }
+        filter
+    }
+}
+
+/// Filters are equal when they have the same parameters and counters; hash builders are not
+/// compared.
+impl<S> PartialEq for CountingBloomFilter<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.num_counters == other.num_counters
+            && self.num_hashes == other.num_hashes
+            && self.words == other.words
+    }
+}
+
+impl<S> Eq for CountingBloomFilter<S> {}
+
+impl<S> fmt::Debug for CountingBloomFilter<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CountingBloomFilter")
+            .field("num_counters", &self.num_counters)
+            .field("num_hashes", &self.num_hashes)
+            .finish()
+    }
+}
+
+impl<T: Hash, S: BuildHasher> Extend<T> for CountingBloomFilter<S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(&item);
+        }
+    }
+}
+
+/// Return the number of words holding `num_counters` counters.
+fn word_count(num_counters: usize) -> usize {
+    num_counters / COUNTERS_PER_WORD + usize::from(num_counters % COUNTERS_PER_WORD != 0)
+}
+
+/// Return the shift of counter `index` within its word, counter 0 being the top nibble.
+#[inline]
+fn shift(index: usize) -> usize {
+    4 * (COUNTERS_PER_WORD - 1 - index % COUNTERS_PER_WORD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut filter = CountingBloomFilter::with_rate(1000, 0.01);
+        filter.extend(0..1000_u32);
+        assert!((0..1000_u32).all(|i| filter.contains(&i)));
+        for i in 0..500_u32 {
+            assert!(filter.remove(&i));
+        }
+        assert!((500..1000_u32).all(|i| filter.contains(&i)));
+        let remaining = (0..500_u32).filter(|i| filter.contains(i)).count();
+        assert!(remaining < 25, "{}", remaining);
+        for i in 500..1000_u32 {
+            filter.remove(&i);
+        }
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn test_remove_absent_item_is_a_no_op() {
+        let mut filter = CountingBloomFilter::new(1 << 12, 3);
+        filter.insert("a");
+        let before = filter.clone();
+        assert!(!filter.remove("b"));
+        assert_eq!(filter, before);
+    }
+
+    #[test]
+    fn test_counters_saturate() {
+        let mut filter = CountingBloomFilter::new(1 << 12, 3);
+        for _ in 0..20 {
+            filter.insert("a");
+        }
+        assert_eq!(filter.count("a"), 15);
+        for _ in 0..20 {
+            assert!(filter.remove("a"));
+        }
+        // Saturated counters never drop, so the item can no longer be removed.
+        assert!(filter.contains("a"));
+        assert_eq!(filter.count("b"), 0);
+    }
+
+    #[test]
+    fn test_to_bloom_filter() {
+        let mut counting = CountingBloomFilter::new(1000, 4);
+        let mut plain = BloomFilter::new(1000, 4);
+        for i in 0..100_u32 {
+            counting.insert(&i);
+            counting.insert(&i);
+            plain.insert(&i);
+        }
+        assert_eq!(counting.to_bloom_filter(), plain);
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        for &num_counters in &[1, 15, 16, 17, 1000] {
+            let mut filter = CountingBloomFilter::new(num_counters, 2);
+            filter.extend(["x", "y", "x"]);
+            let bytes = filter.to_bytes();
+            assert_eq!(bytes.len(), filter.serialized_size());
+            assert_eq!(CountingBloomFilter::from_bytes(&bytes), Ok(filter));
+            assert_eq!(
+                CountingBloomFilter::from_bytes(&bytes[..bytes.len() - 1]),
+                Err(BloomError::UnexpectedEnd)
+            );
+        }
+    }
+}
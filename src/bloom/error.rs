@@ -0,0 +1,24 @@
+use core::fmt;
+
+/// An error from reading a serialized Bloom filter, such as
+/// [`BloomFilter::from_bytes`](super::BloomFilter::from_bytes).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BloomError {
+    /// The bytes end before the serialized filter does.
+    UnexpectedEnd,
+    /// The header describes an impossible filter: no bits, no hash functions, or a size
+    /// that does not fit in memory.
+    InvalidParameters,
+}
+
+impl fmt::Display for BloomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BloomError::UnexpectedEnd => f.write_str("unexpected end of serialized Bloom filter"),
+            BloomError::InvalidParameters => f.write_str("invalid Bloom filter parameters"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BloomError {}
@@ -0,0 +1,414 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use super::error::BloomError;
+use super::hasher::DefaultHashBuilder;
+use super::params::{
+    optimal_parameters, powi, probes, read_header, read_words, reduce, write_header, write_words,
+    HEADER_LENGTH,
+};
+
+/// A Bloom filter: a set that may report false positives but never false negatives.
+///
+/// Each item sets `num_hashes` of the filter's `num_bits` bits, chosen by double hashing one
+/// 64-bit hash from the hash builder `S`. Bit `i` is stored MSB-first in word `i / 64`, as
+/// everywhere else in the crate.
+///
+/// # Examples
+/// ```
+/// use bittersweet::bloom::BloomFilter;
+/// let mut filter = BloomFilter::with_rate(1000, 0.01);
+/// assert!(filter.insert("apple"));
+/// assert!(!filter.insert("apple"));
+/// assert!(filter.contains("apple"));
+/// assert!(!filter.contains("pear"));
+/// assert_eq!((filter.num_bits(), filter.num_hashes()), (9586, 7));
+/// ```
+#[derive(Clone)]
+pub struct BloomFilter<S = DefaultHashBuilder> {
+    pub(crate) words: Vec<u64>,
+    pub(crate) num_bits: usize,
+    pub(crate) num_hashes: u32,
+    pub(crate) hash_builder: S,
+}
+
+impl BloomFilter {
+    /// Return an empty filter of `num_bits` bits that sets `num_hashes` bits per item.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits` or `num_hashes` is zero.
+    pub fn new(num_bits: usize, num_hashes: u32) -> Self {
+        Self::with_hasher(num_bits, num_hashes, DefaultHashBuilder::default())
+    }
+
+    /// Return an empty filter sized to hold `expected_items` items with a false positive
+    /// rate of at most `false_positive_rate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `expected_items` is zero or `false_positive_rate` is not strictly between 0
+    /// and 1.
+    pub fn with_rate(expected_items: usize, false_positive_rate: f64) -> Self {
+        Self::with_rate_and_hasher(
+            expected_items,
+            false_positive_rate,
+            DefaultHashBuilder::default(),
+        )
+    }
+
+    /// Read a filter written by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BloomError::UnexpectedEnd`] if `bytes` is too short and
+    /// [`BloomError::InvalidParameters`] if the header describes an impossible filter.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BloomError> {
+        Self::from_bytes_with_hasher(bytes, DefaultHashBuilder::default())
+    }
+}
+
+impl<S: BuildHasher> BloomFilter<S> {
+    /// Return an empty filter like [`new`](BloomFilter::new) that hashes items with
+    /// `hash_builder`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits` or `num_hashes` is zero.
+    pub fn with_hasher(num_bits: usize, num_hashes: u32, hash_builder: S) -> Self {
+        assert!(num_bits > 0, "a Bloom filter needs at least one bit");
+        assert!(num_hashes > 0, "a Bloom filter needs at least one hash");
+        BloomFilter {
+            words: vec![0; (num_bits + 63) / 64],
+            num_bits,
+            num_hashes,
+            hash_builder,
+        }
+    }
+
+    /// Return an empty filter like [`with_rate`](BloomFilter::with_rate) that hashes items
+    /// with `hash_builder`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `expected_items` is zero or `false_positive_rate` is not strictly between 0
+    /// and 1.
+    pub fn with_rate_and_hasher(
+        expected_items: usize,
+        false_positive_rate: f64,
+        hash_builder: S,
+    ) -> Self {
+        let (num_bits, num_hashes) = optimal_parameters(expected_items, false_positive_rate);
+        Self::with_hasher(num_bits, num_hashes, hash_builder)
+    }
+
+    /// Read a filter written by [`to_bytes`](Self::to_bytes) that hashes items with
+    /// `hash_builder`, which must hash like the builder of the filter written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BloomError::UnexpectedEnd`] if `bytes` is too short and
+    /// [`BloomError::InvalidParameters`] if the header describes an impossible filter.
+    pub fn from_bytes_with_hasher(bytes: &[u8], hash_builder: S) -> Result<Self, BloomError> {
+        let (num_hashes, num_bits, rest) = read_header(bytes)?;
+        let mut words = read_words(rest, num_bits / 64 + usize::from(num_bits % 64 != 0))?;
+        // Bits past the end never reach a query, so drop them rather than trust the input.
+        if let Some(last) = words.last_mut() {
+            *last &= !0 << ((64 - num_bits % 64) % 64);
+        }
+        Ok(BloomFilter {
+            words,
+            num_bits,
+            num_hashes,
+            hash_builder,
+        })
+    }
+
+    /// Add `item`, returning true if it was not already reported as present.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        let mut inserted = false;
+        for probe in probes(self.hash(item), self.num_hashes) {
+            let index = reduce(probe, self.num_bits);
+            let mask = 1 << (63 - index % 64);
+            inserted |= self.words[index / 64] & mask == 0;
+            self.words[index / 64] |= mask;
+        }
+        inserted
+    }
+
+    /// Return true if `item` may have been inserted, and false if it certainly was not.
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        probes(self.hash(item), self.num_hashes).all(|probe| {
+            let index = reduce(probe, self.num_bits);
+            self.words[index / 64] & (1 << (63 - index % 64)) != 0
+        })
+    }
+
+    fn hash<T: Hash + ?Sized>(&self, item: &T) -> u64 {
+        let mut hasher = self.hash_builder.build_hasher();
+        item.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl<S> BloomFilter<S> {
+    /// Return the number of bits of the filter.
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+
+    /// Return the number of bits set for each item.
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Return the hash builder of the filter.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Return the filter bits, MSB-first in `u64` words; bits past `num_bits` are unset.
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Return the number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Return true if nothing has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Remove every item.
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Return the chance that an item never inserted is reported as present, estimated
+    /// from the fraction of bits set.
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        powi(
+            self.count_ones() as f64 / self.num_bits as f64,
+            self.num_hashes,
+        )
+    }
+
+    /// Return true if `other` has the same number of bits and hashes, so the two filters
+    /// can be combined. Their hash builders must also hash alike, which cannot be checked.
+    pub fn is_compatible(&self, other: &Self) -> bool {
+        self.num_bits == other.num_bits && self.num_hashes == other.num_hashes
+    }
+
+    /// Return the length in bytes of [`to_bytes`](Self::to_bytes)'s output.
+    pub fn serialized_size(&self) -> usize {
+        HEADER_LENGTH + 8 * self.words.len()
+    }
+
+    /// Return the filter as bytes: the number of hashes as a little-endian `u32`, the
+    /// number of bits as a little-endian `u64`, then the words of
+    /// [`as_words`](Self::as_words) as little-endian `u64`s. The hash builder is not
+    /// stored.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bloom::BloomFilter;
+    /// let mut filter = BloomFilter::new(100, 3);
+    /// filter.insert(&42);
+    /// let bytes = filter.to_bytes();
+    /// assert_eq!(bytes.len(), filter.serialized_size());
+    /// assert_eq!(BloomFilter::from_bytes(&bytes), Ok(filter));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.serialized_size());
+        write_header(&mut bytes, self.num_hashes, self.num_bits);
+        write_words(&mut bytes, &self.words);
+        bytes
+    }
+}
+
+impl<S: Clone> BloomFilter<S> {
+    /// Return a filter reporting every item of `self` or `other`, exactly as if all their
+    /// items had been inserted into one filter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the filters are not [compatible](Self::is_compatible).
+    pub fn union(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Return a filter reporting the items of both `self` and `other`. Its false positive
+    /// rate may be higher than that of a filter built from the common items alone.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the filters are not [compatible](Self::is_compatible).
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a & b)
+    }
+
+    fn combine(&self, other: &Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        assert!(
+            self.is_compatible(other),
+            "Bloom filters must have the same number of bits and hashes"
+        );
+        BloomFilter {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(&a, &b)| op(a, b))
+                .collect(),
+            num_bits: self.num_bits,
+            num_hashes: self.num_hashes,
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+}
+
+/// Filters are equal when they have the same parameters and bits; hash builders are not
+/// compared.
+impl<S> PartialEq for BloomFilter<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_compatible(other) && self.words == other.words
+    }
+}
+
+impl<S> Eq for BloomFilter<S> {}
+
+impl<S> fmt::Debug for BloomFilter<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BloomFilter")
+            .field("num_bits", &self.num_bits)
+            .field("num_hashes", &self.num_hashes)
+            .field("count_ones", &self.count_ones())
+            .finish()
+    }
+}
+
+impl<T: Hash, S: BuildHasher> Extend<T> for BloomFilter<S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(&item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::hash::BuildHasherDefault;
+
+    #[test]
+    fn test_no_false_negatives() {
+        let mut filter = BloomFilter::with_rate(5000, 0.01);
+        filter.extend(0..5000_u32);
+        assert!((0..5000_u32).all(|i| filter.contains(&i)));
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        let mut filter = BloomFilter::with_rate(10_000, 0.01);
+        filter.extend(0..10_000_u64);
+        let false_positives = (10_000..110_000_u64).filter(|i| filter.contains(i)).count();
+        // The expected count is about 1000; allow for sampling noise.
+        assert!(false_positives < 1300, "{}", false_positives);
+        let estimate = filter.estimated_false_positive_rate();
+        assert!(estimate > 0.005 && estimate < 0.015, "{}", estimate);
+    }
+
+    #[test]
+    fn test_insert_reports_new_items() {
+        let mut filter = BloomFilter::new(1 << 16, 4);
+        assert!(filter.is_empty());
+        assert!(filter.insert("a"));
+        assert!(!filter.insert("a"));
+        assert_eq!(filter.count_ones(), 4);
+        filter.clear();
+        assert!(filter.is_empty());
+        assert!(!filter.contains("a"));
+    }
+
+    #[test]
+    fn test_union_and_intersection() {
+        let mut a = BloomFilter::new(4096, 3);
+        let mut b = BloomFilter::new(4096, 3);
+        a.extend(0..100_u32);
+        b.extend(50..150_u32);
+        let mut both = BloomFilter::new(4096, 3);
+        both.extend(0..150_u32);
+        assert_eq!(a.union(&b), both);
+        let common = a.intersection(&b);
+        assert!((50..100_u32).all(|i| common.contains(&i)));
+        assert!(a.includes_words_of(&common) && b.includes_words_of(&common));
+    }
+
+    #[test]
+    #[should_panic(expected = "Bloom filters must have the same number of bits and hashes")]
+    fn test_union_panics_on_incompatible_filters() {
+        BloomFilter::new(64, 3).union(&BloomFilter::new(64, 4));
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        for &num_bits in &[1, 63, 64, 65, 1000] {
+            let mut filter = BloomFilter::new(num_bits, 2);
+            filter.extend(["x", "y", "z"]);
+            let bytes = filter.to_bytes();
+            assert_eq!(bytes.len(), filter.serialized_size());
+            let decoded = BloomFilter::from_bytes(&bytes);
+            assert_eq!(decoded, Ok(filter));
+            assert_eq!(
+                BloomFilter::from_bytes(&bytes[..bytes.len() - 1]),
+                Err(BloomError::UnexpectedEnd)
+            );
+        }
+        let mut bytes = BloomFilter::new(4, 1).to_bytes();
+        // Only the top four bits of the little-endian word are inside the filter.
+        bytes[HEADER_LENGTH] = 0xff;
+        bytes[HEADER_LENGTH + 7] = 0xff;
+        assert_eq!(
+            BloomFilter::from_bytes(&bytes).map(|f| f.count_ones()),
+            Ok(4)
+        );
+    }
+
+    #[test]
+    fn test_custom_hasher() {
+        #[derive(Default)]
+        struct Identity(u64);
+        impl Hasher for Identity {
+            fn write(&mut self, bytes: &[u8]) {
+                for &byte in bytes {
+                    self.0 = self.0 << 8 | u64::from(byte);
+                }
+            }
+            fn write_u64(&mut self, i: u64) {
+                self.0 = i;
+            }
+            fn finish(&self) -> u64 {
+                self.0
+            }
+        }
+        let mut filter = BloomFilter::with_hasher(64, 1, BuildHasherDefault::<Identity>::default());
+        filter.insert(&(1_u64 << 63));
+        assert_eq!(filter.as_words(), &[1 << 31]);
+    }
+
+    impl<S> BloomFilter<S> {
+        fn includes_words_of(&self, other: &Self) -> bool {
+            self.words
+                .iter()
+                .zip(&other.words)
+                .all(|(a, b)| a & b == *b)
+        }
+    }
+}
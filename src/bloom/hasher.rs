@@ -0,0 +1,124 @@
+use core::hash::{BuildHasherDefault, Hasher};
+
+/// The default hash builder of the Bloom filters, building [`BloomHasher`]s.
+pub type DefaultHashBuilder = BuildHasherDefault<BloomHasher>;
+
+/// Multiplier of the word mixing step, the 64-bit golden ratio.
+const MULTIPLIER: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A fast, unkeyed 64-bit hasher whose output depends only on the bytes hashed.
+///
+/// Integers are hashed as little-endian bytes, and `usize` and `isize` as 64-bit values, so a
+/// filter built on one platform answers the same on another after
+/// [`to_bytes`](super::BloomFilter::to_bytes) and
+/// [`from_bytes`](super::BloomFilter::from_bytes). The hasher is not keyed: it offers no
+/// protection against inputs chosen to collide.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BloomHasher {
+    state: u64,
+    length: u64,
+}
+
+impl BloomHasher {
+    #[inline]
+    fn mix(&mut self, word: u64) {
+        self.state = (self.state.rotate_left(23) ^ word).wrapping_mul(MULTIPLIER);
+    }
+}
+
+impl Hasher for BloomHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            self.mix(u64::from_le_bytes(word));
+        }
+        let remainder = chunks.remainder();
+        if !remainder.is_empty() {
+            let mut word = [0; 8];
+            word[..remainder.len()].copy_from_slice(remainder);
+            // Tag the partial word with its length so trailing zero bytes still count.
+            word[7] ^= remainder.len() as u8;
+            self.mix(u64::from_le_bytes(word));
+        }
+        self.length = self.length.wrapping_add(bytes.len() as u64);
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write(&[i]);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    /// Return the hash, passing the state through the SplitMix64 finalizer.
+    fn finish(&self) -> u64 {
+        finalize(self.state ^ self.length)
+    }
+}
+
+/// Scramble all 64 bits of `x` into each other (the SplitMix64 finalizer).
+#[inline]
+pub(crate) fn finalize(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::hash::{BuildHasher, Hash};
+
+    fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hasher = DefaultHashBuilder::default().build_hasher();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_hasher_is_stable() {
+        // Pinned so serialized filters keep answering the same across releases.
+        assert_eq!(hash(&0_u64), 15378420243238726120);
+        assert_eq!(hash("bittersweet"), 21722947767095733);
+        assert_eq!(hash(&0_u64), hash(&0_usize));
+        assert_eq!(hash(&-1_i32), hash(&u32::MAX));
+        assert_eq!(hash("bittersweet"), hash(&*"bittersweet".to_string()));
+        assert_eq!(BloomHasher::default().finish(), 0);
+    }
+
+    #[test]
+    fn test_hasher_distinguishes_inputs() {
+        assert_ne!(hash(&[0_u8][..]), hash(&[0_u8, 0][..]));
+        assert_ne!(hash(&(1_u8, 0_u8)), hash(&(0_u8, 1_u8)));
+        assert_ne!(hash("ab"), hash("ba"));
+        let mut hashes: alloc::vec::Vec<u64> = (0..10_000_u32).map(|i| hash(&i)).collect();
+        hashes.sort_unstable();
+        hashes.dedup();
+        assert_eq!(hashes.len(), 10_000);
+    }
+}
@@ -0,0 +1,44 @@
+//! Bloom filters over `u64` words.
+//!
+//! A Bloom filter answers membership queries in a fixed number of bits: it never forgets an
+//! item inserted, but may report items that were not. Three variants share the same sizing,
+//! hashing and serialization:
+//!
+//! - [`BloomFilter`] sets `k` bits anywhere in an `m`-bit array;
+//! - [`CountingBloomFilter`] keeps a 4-bit counter per bit, so items can also be removed;
+//! - [`BlockedBloomFilter`] keeps the `k` bits of an item in one 512-bit block, a single
+//!   cache line, for faster queries at a slightly higher false positive rate.
+//!
+//! `with_rate` sizes a filter from the expected number of items and the target false positive
+//! rate. Each item is hashed once into 64 bits by a [`BuildHasher`](core::hash::BuildHasher),
+//! the [`DefaultHashBuilder`] unless another is given to `with_hasher`, and its `k` positions
+//! are derived from that hash by double hashing. The default hasher is deterministic and
+//! platform-independent, so filters written with `to_bytes` can be read back with
+//! `from_bytes` anywhere.
+//!
+//! # Example
+//!
+//! ```
+//! use bittersweet::bloom::BloomFilter;
+//! let mut seen = BloomFilter::with_rate(10_000, 0.001);
+//! let mut other = BloomFilter::with_rate(10_000, 0.001);
+//! seen.extend(["GET /", "GET /about"]);
+//! other.insert("POST /login");
+//! let all = seen.union(&other);
+//! assert!(all.contains("GET /about") && all.contains("POST /login"));
+//! let restored = BloomFilter::from_bytes(&all.to_bytes()).unwrap();
+//! assert!(restored.contains("GET /"));
+//! ```
+
+mod blocked;
+mod counting;
+mod error;
+mod filter;
+mod hasher;
+mod params;
+// re-export
+pub use blocked::BlockedBloomFilter;
+pub use counting::CountingBloomFilter;
+pub use error::BloomError;
+pub use filter::BloomFilter;
+pub use hasher::{BloomHasher, DefaultHashBuilder};
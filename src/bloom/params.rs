@@ -0,0 +1,173 @@
+use alloc::vec::Vec;
+use core::f64::consts::LN_2;
+
+use super::error::BloomError;
+use super::hasher::finalize;
+
+/// Return the number of bits and hash functions that keep a filter holding
+/// `expected_items` items at a false positive rate of at most `false_positive_rate`.
+///
+/// These are the classic optimums `m = -n ln p / (ln 2)²` and `k = (m / n) ln 2`.
+pub(crate) fn optimal_parameters(expected_items: usize, false_positive_rate: f64) -> (usize, u32) {
+    assert!(
+        expected_items > 0,
+        "the expected number of items must be positive"
+    );
+    assert!(
+        false_positive_rate > f64::MIN_POSITIVE && false_positive_rate < 1.0,
+        "the false positive rate must be between 0 and 1"
+    );
+    let items = expected_items as f64;
+    let bits = ceil(-items * ln(false_positive_rate) / (LN_2 * LN_2));
+    // Truncating after adding one half rounds to the nearest count.
+    let hashes = ((bits as f64 / items * LN_2 + 0.5) as u32).max(1);
+    (bits.max(1), hashes)
+}
+
+/// Return the natural logarithm of a positive, normal `x`.
+///
+/// `core` has no `f64::ln` without `std`, so this splits `x` into `m·2^e` with `m` in
+/// `[1, 2)` and sums the series `ln m = 2 atanh((m - 1) / (m + 1))`, which converges quickly
+/// since the argument is at most 1/3.
+fn ln(x: f64) -> f64 {
+    let bits = x.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let mantissa = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+    let z = (mantissa - 1.0) / (mantissa + 1.0);
+    let z2 = z * z;
+    let mut term = z;
+    let mut sum = 0.0;
+    let mut n = 1.0;
+    while n < 40.0 {
+        sum += term / n;
+        term *= z2;
+        n += 2.0;
+    }
+    exponent as f64 * LN_2 + 2.0 * sum
+}
+
+/// Round a non-negative `x` up to an integer.
+fn ceil(x: f64) -> usize {
+    let truncated = x as usize;
+    if (truncated as f64) < x {
+        truncated + 1
+    } else {
+        truncated
+    }
+}
+
+/// Return `base` raised to the power `exponent`, for false positive estimates.
+pub(crate) fn powi(base: f64, exponent: u32) -> f64 {
+    (0..exponent).fold(1.0, |product, _| product * base)
+}
+
+/// Map a uniformly distributed `x` onto `0..n` with a multiply-shift instead of a division.
+#[inline]
+pub(crate) fn reduce(x: u64, n: usize) -> usize {
+    ((u128::from(x) * n as u128) >> 64) as usize
+}
+
+/// The probe sequence of one item: `hashes` values derived from a single 64-bit hash by
+/// double hashing, `h1 + i·h2` for `i` in `0..hashes`.
+#[inline]
+pub(crate) fn probes(hash: u64, hashes: u32) -> impl Iterator<Item = u64> {
+    let step = finalize(hash) | 1;
+    (0..u64::from(hashes)).map(move |i| hash.wrapping_add(i.wrapping_mul(step)))
+}
+
+/// Append the common header of every serialized filter: the hash count and a size, both
+/// little-endian.
+pub(crate) fn write_header(bytes: &mut Vec<u8>, hashes: u32, size: usize) {
+    bytes.extend_from_slice(&hashes.to_le_bytes());
+    bytes.extend_from_slice(&(size as u64).to_le_bytes());
+}
+
+/// The length in bytes of the header written by [`write_header`].
+pub(crate) const HEADER_LENGTH: usize = 12;
+
+/// Read the header written by [`write_header`], returning the hash count, the size and the
+/// bytes after the header.
+pub(crate) fn read_header(bytes: &[u8]) -> Result<(u32, usize, &[u8]), BloomError> {
+    if bytes.len() < HEADER_LENGTH {
+        return Err(BloomError::UnexpectedEnd);
+    }
+    let mut hashes = [0; 4];
+    hashes.copy_from_slice(&bytes[..4]);
+    let mut size = [0; 8];
+    size.copy_from_slice(&bytes[4..HEADER_LENGTH]);
+    let hashes = u32::from_le_bytes(hashes);
+    let size =
+        usize::try_from(u64::from_le_bytes(size)).map_err(|_| BloomError::InvalidParameters)?;
+    if hashes == 0 || size == 0 {
+        return Err(BloomError::InvalidParameters);
+    }
+    Ok((hashes, size, &bytes[HEADER_LENGTH..]))
+}
+
+/// Append `words` as little-endian `u64`s.
+pub(crate) fn write_words(bytes: &mut Vec<u8>, words: &[u64]) {
+    for word in words {
+        bytes.extend_from_slice(&word.to_le_bytes());
+    }
+}
+
+/// Read exactly `count` little-endian `u64`s from the start of `bytes`.
+pub(crate) fn read_words(bytes: &[u8], count: usize) -> Result<Vec<u64>, BloomError> {
+    let length = count.checked_mul(8).ok_or(BloomError::InvalidParameters)?;
+    if bytes.len() < length {
+        return Err(BloomError::UnexpectedEnd);
+    }
+    Ok(bytes[..length]
+        .chunks_exact(8)
+        .map(|chunk| {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            u64::from_le_bytes(word)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ln_matches_std() {
+        for &x in &[
+            1e-300, 1e-9, 0.001, 0.01, 0.1, 0.5, 0.75, 0.999, 1.0, 2.0, 1e10,
+        ] {
+            let expected = f64::ln(x);
+            assert!((ln(x) - expected).abs() <= 1e-12 * expected.abs().max(1.0));
+        }
+    }
+
+    #[test]
+    fn test_optimal_parameters() {
+        // The usual reference points: 1% needs about 9.59 bits and 7 hashes per item.
+        assert_eq!(optimal_parameters(1000, 0.01), (9586, 7));
+        assert_eq!(optimal_parameters(1_000_000, 0.001), (14_377_588, 10));
+        assert_eq!(optimal_parameters(1, 0.5), (2, 1));
+        assert_eq!(optimal_parameters(100, 0.9).1, 1);
+    }
+
+    #[test]
+    fn test_reduce() {
+        assert_eq!(reduce(0, 10), 0);
+        assert_eq!(reduce(u64::MAX, 10), 9);
+        assert_eq!(reduce(1 << 63, 10), 5);
+    }
+
+    #[test]
+    fn test_header_roundtrip() {
+        let mut bytes = Vec::new();
+        write_header(&mut bytes, 7, 1 << 40);
+        assert_eq!(bytes.len(), HEADER_LENGTH);
+        bytes.push(0xaa);
+        assert_eq!(read_header(&bytes), Ok((7, 1 << 40, &[0xaa][..])));
+        assert_eq!(read_header(&bytes[..11]), Err(BloomError::UnexpectedEnd));
+        assert_eq!(
+            read_header(&[0; HEADER_LENGTH]),
+            Err(BloomError::InvalidParameters)
+        );
+    }
+}
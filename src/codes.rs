@@ -0,0 +1,622 @@
+//! Universal and parameterized integer codes over bit streams.
+//!
+//! Every code is a pair of functions: `write_*` appends the code word of a value to a
+//! [`BitWriter`], and `read_*` decodes one code word from a [`BitReader`]. Code words are
+//! written in stream order, so they can be mixed freely with other fields.
+//!
+//! - [Elias gamma](write_gamma), [delta](write_delta) and [omega](write_omega) codes and
+//!   [Fibonacci](write_fibonacci) coding encode positive integers (`1..=u64::MAX`) without
+//!   any parameter.
+//! - [Golomb](write_golomb) codes with divisor `m` and [Rice](write_rice) codes with
+//!   divisor `2^k` encode non-negative integers and suit geometrically distributed values
+//!   such as the gaps of a posting list.
+//!
+//! Unary parts are written as `1` bits terminated by a `0`. A `write_*` function returns
+//! [`StreamError::BufferFull`] without writing anything if the whole code word does not fit.
+//! A `read_*` function returns
+//! [`StreamError::UnexpectedEnd`] if the stream ends inside a code word and
+//! [`StreamError::InvalidCode`] if the code word is malformed or its value does not fit in
+//! a `u64`.
+//!
+//! # Example
+//!
+//! ```
+//! use bittersweet::codes::{read_gamma, read_rice, write_gamma, write_rice};
+//! use bittersweet::stream::{BitReader, BitWriter};
+//! let mut writer = BitWriter::new(Vec::new());
+//! write_gamma(&mut writer, 5).unwrap(); // 00101
+//! write_rice(&mut writer, 9, 2).unwrap(); // 110 01
+//! assert_eq!(writer.as_bytes(), [0b0010_1110, 0b0100_0000]);
+//!
+//! let bytes = writer.into_inner();
+//! let mut reader = BitReader::new(&bytes);
+//! assert_eq!(read_gamma(&mut reader), Ok(5));
+//! assert_eq!(read_rice(&mut reader, 2), Ok(9));
+//! ```
+
+use crate::bitline::Bitline;
+use crate::stream::{BitBuffer, BitReader, BitWriter, StreamError};
+
+/// Return the number of bits of `n` after its leading zeros, `floor(log2(n)) + 1`.
+#[inline]
+fn significant_bits(n: u64) -> u32 {
+    64 - n.leading_zeros()
+}
+
+/// Fail with [`StreamError::BufferFull`] unless `writer` has room for `len` more bits, so
+/// that a code word is written whole or not at all.
+#[inline]
+fn reserve<B: BitBuffer>(writer: &BitWriter<B>, len: u64) -> Result<(), StreamError> {
+    match writer.remaining_bits() {
+        Some(remaining) if (remaining as u64) < len => Err(StreamError::BufferFull),
+        _ => Ok(()),
+    }
+}
+
+/// Write `count` `1` bits.
+fn write_ones<B: BitBuffer>(writer: &mut BitWriter<B>, mut count: u64) -> Result<(), StreamError> {
+    while count > 0 {
+        let width = core::cmp::min(count, 64);
+        writer.write_bits(u64::MAX, width as u32)?;
+        count -= width;
+    }
+    Ok(())
+}
+
+/// Count and consume the `1` bits before the next `0`, which is consumed too.
+fn read_unary(reader: &mut BitReader) -> Result<u64, StreamError> {
+    let mut count = 0_u64;
+    while reader.read_bit()? {
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Count and consume the `0` bits before the next `1`, which is left in the stream.
+fn read_leading_zeros(reader: &mut BitReader) -> Result<u32, StreamError> {
+    let mut zeros = 0;
+    loop {
+        let available = core::cmp::min(reader.remaining_bits(), 64) as u32;
+        if available == 0 {
+            return Err(StreamError::UnexpectedEnd);
+        }
+        // Look ahead a whole window at once and find its first set bit.
+        let window = reader.peek_bits(available)? << (64 - available);
+        match window.first_index() {
+            Some(index) => {
+                reader.skip(index)?;
+                return Ok(zeros + index as u32);
+            }
+            None => {
+                reader.skip(available as usize)?;
+                zeros += available;
+            }
+        }
+    }
+}
+
+/// Write the Elias gamma code of `n`: `floor(log2(n))` zeros followed by `n` in binary.
+///
+/// # Examples
+/// ```
+/// use bittersweet::codes::write_gamma;
+/// use bittersweet::stream::BitWriter;
+/// let mut writer = BitWriter::new(Vec::new());
+/// write_gamma(&mut writer, 1).unwrap(); // 1
+/// write_gamma(&mut writer, 2).unwrap(); // 010
+/// write_gamma(&mut writer, 4).unwrap(); // 00100
+/// assert_eq!(writer.as_bytes(), [0b1010_0010, 0b0000_0000]);
+/// assert_eq!(writer.bit_position(), 9);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `n` is 0.
+pub fn write_gamma<B: BitBuffer>(writer: &mut BitWriter<B>, n: u64) -> Result<(), StreamError> {
+    assert!(n != 0, "value must be positive");
+    reserve(writer, gamma_len(n) as u64)?;
+    let bits = significant_bits(n);
+    writer.write_bits(0, bits - 1)?;
+    writer.write_bits(n, bits)
+}
+
+/// Read an Elias gamma code word written by [`write_gamma`].
+pub fn read_gamma(reader: &mut BitReader) -> Result<u64, StreamError> {
+    let zeros = read_leading_zeros(reader)?;
+    if zeros > 63 {
+        return Err(StreamError::InvalidCode);
+    }
+    reader.read_bits(zeros + 1)
+}
+
+/// Return the length in bits of the Elias gamma code of `n`.
+///
+/// # Panics
+///
+/// Panics if `n` is 0.
+#[inline]
+pub fn gamma_len(n: u64) -> usize {
+    assert!(n != 0, "value must be positive");
+    2 * significant_bits(n) as usize - 1
+}
+
+/// Write the Elias delta code of `n`: the gamma code of its bit length followed by `n` in
+/// binary without its leading `1`.
+///
+/// # Examples
+/// ```
+/// use bittersweet::codes::write_delta;
+/// use bittersweet::stream::BitWriter;
+/// let mut writer = BitWriter::new(Vec::new());
+/// write_delta(&mut writer, 10).unwrap(); // 00100 010
+/// assert_eq!(writer.as_bytes(), [0b0010_0010]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `n` is 0.
+pub fn write_delta<B: BitBuffer>(writer: &mut BitWriter<B>, n: u64) -> Result<(), StreamError> {
+    assert!(n != 0, "value must be positive");
+    reserve(writer, delta_len(n) as u64)?;
+    let bits = significant_bits(n);
+    write_gamma(writer, bits as u64)?;
+    writer.write_bits(n, bits - 1)
+}
+
+/// Read an Elias delta code word written by [`write_delta`].
+pub fn read_delta(reader: &mut BitReader) -> Result<u64, StreamError> {
+    let bits = read_gamma(reader)?;
+    if bits > 64 {
+        return Err(StreamError::InvalidCode);
+    }
+    let low = reader.read_bits(bits as u32 - 1)?;
+    Ok(1 << (bits - 1) | low)
+}
+
+/// Return the length in bits of the Elias delta code of `n`.
+///
+/// # Panics
+///
+/// Panics if `n` is 0.
+#[inline]
+pub fn delta_len(n: u64) -> usize {
+    assert!(n != 0, "value must be positive");
+    let bits = significant_bits(n) as u64;
+    gamma_len(bits) + bits as usize - 1
+}
+
+/// Write the Elias omega code of `n`: recursively, the binary forms of `n`, of its bit
+/// length minus one, and so on down to a length of 1, in reverse order, then a `0`.
+///
+/// # Examples
+/// ```
+/// use bittersweet::codes::write_omega;
+/// use bittersweet::stream::BitWriter;
+/// let mut writer = BitWriter::new(Vec::new());
+/// write_omega(&mut writer, 17).unwrap(); // 10 100 10001 0
+/// assert_eq!(writer.as_bytes(), [0b1010_0100, 0b0100_0000]);
+/// assert_eq!(writer.bit_position(), 11);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `n` is 0.
+pub fn write_omega<B: BitBuffer>(writer: &mut BitWriter<B>, n: u64) -> Result<(), StreamError> {
+    assert!(n != 0, "value must be positive");
+    // A u64 needs at most 4 groups: 64 bits, then 63 (6 bits), 5 (3 bits) and 2 (2 bits).
+    let mut groups = [0_u64; 8];
+    let mut count = 0;
+    let mut n = n;
+    while n > 1 {
+        groups[count] = n;
+        count += 1;
+        n = significant_bits(n) as u64 - 1;
+    }
+    let len: u32 = groups[..count]
+        .iter()
+        .map(|group| significant_bits(*group))
+        .sum();
+    reserve(writer, len as u64 + 1)?;
+    for group in groups[..count].iter().rev() {
+        writer.write_bits(*group, significant_bits(*group))?;
+    }
+    writer.write_bit(false)
+}
+
+/// Read an Elias omega code word written by [`write_omega`].
+pub fn read_omega(reader: &mut BitReader) -> Result<u64, StreamError> {
+    let mut n = 1_u64;
+    while reader.read_bit()? {
+        // The group starts with the `1` just read and has `n` more bits.
+        if n > 63 {
+            return Err(StreamError::InvalidCode);
+        }
+        n = 1 << n | reader.read_bits(n as u32)?;
+    }
+    Ok(n)
+}
+
+/// Write the Golomb code of `n` with divisor `m`: the quotient `n / m` in unary, then the
+/// remainder `n % m` in truncated binary.
+///
+/// The truncated binary form uses `b - 1` bits for the `2^b - m` smallest remainders and
+/// `b` bits for the others, where `b = ceil(log2(m))`.
+///
+/// # Examples
+/// ```
+/// use bittersweet::codes::write_golomb;
+/// use bittersweet::stream::BitWriter;
+/// let mut writer = BitWriter::new(Vec::new());
+/// write_golomb(&mut writer, 42, 10).unwrap(); // 1111 0 010
+/// assert_eq!(writer.as_bytes(), [0b1111_0010]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `m` is 0.
+pub fn write_golomb<B: BitBuffer>(
+    writer: &mut BitWriter<B>,
+    n: u64,
+    m: u64,
+) -> Result<(), StreamError> {
+    assert!(m != 0, "divisor must be positive");
+    let (quotient, remainder) = (n / m, n % m);
+    let (bits, cutoff) = truncated_binary(m);
+    let remainder_bits = if remainder < cutoff { bits - 1 } else { bits };
+    reserve(writer, quotient.saturating_add(1 + remainder_bits as u64))?;
+    write_ones(writer, quotient)?;
+    writer.write_bit(false)?;
+    if remainder < cutoff {
+        writer.write_bits(remainder, bits - 1)
+    } else {
+        writer.write_bits(remainder + cutoff, bits)
+    }
+}
+
+/// Read a Golomb code word with divisor `m` written by [`write_golomb`].
+///
+/// # Panics
+///
+/// Panics if `m` is 0.
+pub fn read_golomb(reader: &mut BitReader, m: u64) -> Result<u64, StreamError> {
+    assert!(m != 0, "divisor must be positive");
+    let quotient = read_unary(reader)?;
+    let (bits, cutoff) = truncated_binary(m);
+    if bits == 0 {
+        // With a divisor of 1 there is no remainder to read.
+        return Ok(quotient);
+    }
+    let mut remainder = reader.read_bits(bits - 1)?;
+    if remainder >= cutoff {
+        remainder = (remainder << 1 | reader.read_bits(1)?) - cutoff;
+    }
+    quotient
+        .checked_mul(m)
+        .and_then(|n| n.checked_add(remainder))
+        .ok_or(StreamError::InvalidCode)
+}
+
+/// Return `ceil(log2(m))` and the number of remainders that use one bit fewer.
+#[inline]
+fn truncated_binary(m: u64) -> (u32, u64) {
+    let bits = significant_bits(m - 1);
+    let cutoff = 1_u64.checked_shl(bits).unwrap_or(0).wrapping_sub(m);
+    (bits, cutoff)
+}
+
+/// Write the Rice code of `n` with parameter `k`: the Golomb code with divisor `2^k`,
+/// which is `n >> k` in unary followed by the low `k` bits of `n`.
+///
+/// # Panics
+///
+/// Panics if `k` is greater than 63.
+pub fn write_rice<B: BitBuffer>(
+    writer: &mut BitWriter<B>,
+    n: u64,
+    k: u32,
+) -> Result<(), StreamError> {
+    reserve(writer, (n >> k).saturating_add(1 + k as u64))?;
+    write_ones(writer, n >> k)?;
+    writer.write_bit(false)?;
+    writer.write_bits(n, k)
+}
+
+/// Read a Rice code word with parameter `k` written by [`write_rice`].
+///
+/// # Panics
+///
+/// Panics if `k` is greater than 63.
+pub fn read_rice(reader: &mut BitReader, k: u32) -> Result<u64, StreamError> {
+    assert!(k <= 63, "parameter out of range");
+    let quotient = read_unary(reader)?;
+    if quotient > u64::MAX >> k {
+        return Err(StreamError::InvalidCode);
+    }
+    Ok(quotient << k | reader.read_bits(k)?)
+}
+
+/// Return the length in bits of the Rice code of `n` with parameter `k`.
+///
+/// # Panics
+///
+/// Panics if `k` is greater than 63.
+#[inline]
+pub fn rice_len(n: u64, k: u32) -> u64 {
+    assert!(k <= 63, "parameter out of range");
+    (n >> k) + 1 + k as u64
+}
+
+/// The Fibonacci numbers `F(2) = 1, F(3) = 2, 3, 5, …` up to the largest that fits in a
+/// `u64`, `F(93)`.
+const FIBONACCI: [u64; 92] = fibonacci();
+
+const fn fibonacci() -> [u64; 92] {
+    let mut table = [0; 92];
+    table[0] = 1;
+    table[1] = 2;
+    let mut i = 2;
+    while i < 92 {
+        table[i] = table[i - 1] + table[i - 2];
+        i += 1;
+    }
+    table
+}
+
+/// Write the Fibonacci code of `n`: its Zeckendorf representation as a sum of
+/// non-consecutive Fibonacci numbers, smallest first, followed by an extra `1`.
+///
+/// No code word contains `11` except at its end, so code words are self-delimiting.
+///
+/// # Examples
+/// ```
+/// use bittersweet::codes::write_fibonacci;
+/// use bittersweet::stream::BitWriter;
+/// let mut writer = BitWriter::new(Vec::new());
+/// write_fibonacci(&mut writer, 4).unwrap(); // 1 + 3: 101 1
+/// write_fibonacci(&mut writer, 11).unwrap(); // 3 + 8: 00101 1
+/// assert_eq!(writer.as_bytes(), [0b1011_0010, 0b1100_0000]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `n` is 0.
+pub fn write_fibonacci<B: BitBuffer>(writer: &mut BitWriter<B>, n: u64) -> Result<(), StreamError> {
+    assert!(n != 0, "value must be positive");
+    // Choose the Fibonacci numbers greedily from the largest one that fits.
+    let mut used = [false; 92];
+    let mut top = 0;
+    let mut rest = n;
+    for i in (0..92).rev() {
+        if FIBONACCI[i] <= rest {
+            if rest == n {
+                top = i;
+            }
+            used[i] = true;
+            rest -= FIBONACCI[i];
+        }
+    }
+    reserve(writer, top as u64 + 2)?;
+    for &bit in used[..=top].iter() {
+        writer.write_bit(bit)?;
+    }
+    writer.write_bit(true)
+}
+
+/// Read a Fibonacci code word written by [`write_fibonacci`].
+pub fn read_fibonacci(reader: &mut BitReader) -> Result<u64, StreamError> {
+    let mut n = 0_u64;
+    let mut previous = false;
+    for fibonacci in FIBONACCI.iter() {
+        let bit = reader.read_bit()?;
+        if bit && previous {
+            return Ok(n);
+        }
+        if bit {
+            n = n.checked_add(*fibonacci).ok_or(StreamError::InvalidCode)?;
+        }
+        previous = bit;
+    }
+    // The only code word that reaches this far ends right after `F(93)`.
+    if previous && reader.read_bit()? {
+        return Ok(n);
+    }
+    Err(StreamError::InvalidCode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::xorshift64;
+
+    /// Pseudo-random positive values spread over every magnitude.
+    fn values() -> Vec<u64> {
+        let mut state = 0x9e3779b97f4a7c15;
+        let mut values: Vec<u64> = (1..=64)
+            .chain([u64::MAX, u64::MAX - 1, 1 << 63].iter().copied())
+            .collect();
+        for _ in 0..500 {
+            let shift = xorshift64(&mut state) % 64;
+            values.push((xorshift64(&mut state) >> shift).max(1));
+        }
+        values
+    }
+
+    /// Encode one value and return the code word as a string of `0` and `1`.
+    fn code_word<F>(write: F) -> String
+    where
+        F: FnOnce(&mut BitWriter<Vec<u8>>) -> Result<(), StreamError>,
+    {
+        let mut writer = BitWriter::new(Vec::new());
+        write(&mut writer).unwrap();
+        let len = writer.bit_position();
+        let bits: String = writer.as_bytes().iter().map(|b| b.bit_repr()).collect();
+        bits[..len].to_string()
+    }
+
+    /// Write every value, then read them all back in order.
+    fn assert_round_trip<W, R>(values: &[u64], write: W, read: R)
+    where
+        W: Fn(&mut BitWriter<Vec<u8>>, u64) -> Result<(), StreamError>,
+        R: Fn(&mut BitReader) -> Result<u64, StreamError>,
+    {
+        let mut writer = BitWriter::new(Vec::new());
+        for &value in values {
+            write(&mut writer, value).unwrap();
+        }
+        let bytes = writer.into_inner();
+        let mut reader = BitReader::new(&bytes);
+        for &value in values {
+            assert_eq!(read(&mut reader), Ok(value));
+        }
+        assert!(reader.remaining_bits() < 8);
+    }
+
+    #[test]
+    fn test_known_code_words() {
+        assert_eq!(code_word(|w| write_gamma(w, 1)), "1");
+        assert_eq!(code_word(|w| write_gamma(w, 5)), "00101");
+        assert_eq!(code_word(|w| write_delta(w, 1)), "1");
+        assert_eq!(code_word(|w| write_delta(w, 2)), "0100");
+        assert_eq!(code_word(|w| write_delta(w, 10)), "00100010");
+        assert_eq!(code_word(|w| write_omega(w, 1)), "0");
+        assert_eq!(code_word(|w| write_omega(w, 2)), "100");
+        assert_eq!(code_word(|w| write_omega(w, 17)), "10100100010");
+        assert_eq!(code_word(|w| write_golomb(w, 42, 10)), "11110010");
+        assert_eq!(code_word(|w| write_golomb(w, 9, 10)), "01111");
+        assert_eq!(code_word(|w| write_golomb(w, 3, 1)), "1110");
+        assert_eq!(code_word(|w| write_rice(w, 9, 2)), "11001");
+        assert_eq!(code_word(|w| write_rice(w, 0, 0)), "0");
+        assert_eq!(code_word(|w| write_fibonacci(w, 1)), "11");
+        assert_eq!(code_word(|w| write_fibonacci(w, 2)), "011");
+        assert_eq!(code_word(|w| write_fibonacci(w, 3)), "0011");
+        assert_eq!(code_word(|w| write_fibonacci(w, 11)), "001011");
+    }
+
+    #[test]
+    fn test_lengths() {
+        for &value in values().iter() {
+            assert_eq!(code_word(|w| write_gamma(w, value)).len(), gamma_len(value));
+            assert_eq!(code_word(|w| write_delta(w, value)).len(), delta_len(value));
+            let value = value >> 50;
+            assert_eq!(
+                code_word(|w| write_rice(w, value, 3)).len() as u64,
+                rice_len(value, 3)
+            );
+        }
+    }
+
+    #[test]
+    fn test_elias_round_trip() {
+        let values = values();
+        assert_round_trip(&values, write_gamma, read_gamma);
+        assert_round_trip(&values, write_delta, read_delta);
+        assert_round_trip(&values, write_omega, read_omega);
+    }
+
+    #[test]
+    fn test_fibonacci_round_trip() {
+        let values = values();
+        assert_round_trip(&values, write_fibonacci, read_fibonacci);
+        assert_eq!(code_word(|w| write_fibonacci(w, u64::MAX)).len(), 93);
+    }
+
+    #[test]
+    fn test_golomb_and_rice_round_trip() {
+        // Keep the quotients, and so the unary parts, small.
+        let small: Vec<u64> = values().iter().map(|v| v >> 52).chain(0..20).collect();
+        for &m in [1, 2, 3, 5, 7, 8, 10, 100, 4096].iter() {
+            let scaled: Vec<u64> = small.iter().map(|v| v * m / 64).collect();
+            assert_round_trip(&scaled, |w, n| write_golomb(w, n, m), |r| read_golomb(r, m));
+        }
+        for &k in [0, 1, 4, 12].iter() {
+            assert_round_trip(&small, |w, n| write_rice(w, n, k), |r| read_rice(r, k));
+        }
+        // Huge divisors keep the quotient at most 1 even for the largest values.
+        let large = [0, 1, u64::MAX, u64::MAX - 1, 1 << 63, (1 << 63) - 1];
+        for &m in [u64::MAX, 1 << 63, (1 << 63) + 1].iter() {
+            assert_round_trip(&large, |w, n| write_golomb(w, n, m), |r| read_golomb(r, m));
+        }
+        assert_round_trip(&large, |w, n| write_rice(w, n, 63), |r| read_rice(r, 63));
+    }
+
+    #[test]
+    fn test_buffer_full_writes_nothing() {
+        type Write = fn(&mut BitWriter<&mut [u8]>) -> Result<(), StreamError>;
+        // Every code word is longer than the 13 bits left after the prefix.
+        let writes: [Write; 6] = [
+            |w| write_gamma(w, 1 << 7),
+            |w| write_delta(w, 1 << 10),
+            |w| write_omega(w, 1 << 12),
+            |w| write_golomb(w, 100, 10),
+            |w| write_rice(w, 50, 2),
+            |w| write_fibonacci(w, 1000),
+        ];
+        for write in writes.iter() {
+            let mut buffer = [0xff_u8; 2];
+            let mut writer = BitWriter::new(&mut buffer[..]);
+            writer.write_bits(0b101, 3).unwrap();
+            assert_eq!(write(&mut writer), Err(StreamError::BufferFull));
+            assert_eq!(writer.bit_position(), 3);
+            assert_eq!(buffer, [0b1010_0000, 0xff]);
+        }
+        let mut buffer = [0_u8; 2];
+        let mut writer = BitWriter::new(&mut buffer[..]);
+        write_rice(&mut writer, 50, 2).unwrap();
+        assert_eq!(writer.remaining_bits(), Some(1));
+    }
+
+    #[test]
+    fn test_truncated_stream() {
+        for len in 0..5 {
+            let mut writer = BitWriter::new(Vec::new());
+            write_gamma(&mut writer, 0x1234).unwrap();
+            let bytes = writer.into_inner();
+            let mut reader = BitReader::new(&bytes[..len / 2]);
+            assert_eq!(read_gamma(&mut reader), Err(StreamError::UnexpectedEnd));
+        }
+        let mut reader = BitReader::new(&[0b1010_1010]);
+        assert_eq!(read_fibonacci(&mut reader), Err(StreamError::UnexpectedEnd));
+        let mut reader = BitReader::new(&[0xff]);
+        assert_eq!(read_rice(&mut reader, 0), Err(StreamError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_invalid_codes() {
+        // 64 zeros before the first one do not fit a gamma code of a u64.
+        let mut bytes = vec![0_u8; 8];
+        bytes.extend_from_slice(&[0xff; 9]);
+        assert_eq!(
+            read_gamma(&mut BitReader::new(&bytes)),
+            Err(StreamError::InvalidCode)
+        );
+        // Omega groups longer than 64 bits.
+        assert_eq!(
+            read_omega(&mut BitReader::new(&[0xff; 32])),
+            Err(StreamError::InvalidCode)
+        );
+        // A Rice quotient that overflows once shifted.
+        let mut bytes = vec![0xff_u8; 1];
+        bytes.push(0);
+        assert_eq!(
+            read_rice(&mut BitReader::new(&bytes), 61),
+            Err(StreamError::InvalidCode)
+        );
+        // Alternating bits never terminate a Fibonacci code word within 92 bits.
+        assert_eq!(
+            read_fibonacci(&mut BitReader::new(&[0b1010_1010; 16])),
+            Err(StreamError::InvalidCode)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "value must be positive")]
+    fn test_gamma_rejects_zero() {
+        let _ = write_gamma(&mut BitWriter::new(Vec::new()), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "divisor must be positive")]
+    fn test_golomb_rejects_zero_divisor() {
+        let _ = write_golomb(&mut BitWriter::new(Vec::new()), 1, 0);
+    }
+}
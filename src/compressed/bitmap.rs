@@ -0,0 +1,559 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::{FromIterator, FusedIterator};
+use core::slice;
+
+use super::container::{Container, ContainerIter};
+
+/// A compressed set of `u32` values.
+///
+/// Values are grouped by their high 16 bits, and the low halves of each group are stored in
+/// a container chosen for its size: a sorted array, a 65536-bit bitmap or a list of runs.
+/// See the [module documentation](super) for when each form is used.
+///
+/// # Examples
+/// ```
+/// use bittersweet::compressed::CompressedBitmap;
+/// let mut bitmap = CompressedBitmap::new();
+/// assert!(bitmap.insert(7));
+/// assert!(!bitmap.insert(7));
+/// bitmap.insert(1 << 20);
+/// assert!(bitmap.contains(7));
+/// assert_eq!(bitmap.len(), 2);
+/// assert_eq!(bitmap.iter().collect::<Vec<_>>(), vec![7, 1 << 20]);
+/// ```
+#[derive(Clone, Default)]
+pub struct CompressedBitmap {
+    pub(crate) keys: Vec<u16>,
+    pub(crate) containers: Vec<Container>,
+}
+
+impl CompressedBitmap {
+    /// Return an empty bitmap.
+    pub fn new() -> Self {
+        CompressedBitmap {
+            keys: Vec::new(),
+            containers: Vec::new(),
+        }
+    }
+
+    /// Return the number of values in the bitmap.
+    pub fn len(&self) -> u64 {
+        self.containers
+            .iter()
+            .map(|container| container.len() as u64)
+            .sum()
+    }
+
+    /// Return true if the bitmap has no values.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Remove every value.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.containers.clear();
+    }
+
+    /// Return true if `value` is in the bitmap.
+    pub fn contains(&self, value: u32) -> bool {
+        let (high, low) = split(value);
+        match self.keys.binary_search(&high) {
+            Ok(i) => self.containers[i].contains(low),
+            Err(_) => false,
+        }
+    }
+
+    /// Add `value`, returning true if it was not already present.
+    pub fn insert(&mut self, value: u32) -> bool {
+        let (high, low) = split(value);
+        match self.keys.binary_search(&high) {
+            Ok(i) => self.containers[i].insert(low),
+            Err(i) => {
+                self.keys.insert(i, high);
+                self.containers.insert(i, Container::Array(vec![low]));
+                true
+            }
+        }
+    }
+
+    /// Remove `value`, returning true if it was present.
+    pub fn remove(&mut self, value: u32) -> bool {
+        let (high, low) = split(value);
+        match self.keys.binary_search(&high) {
+            Ok(i) => {
+                let removed = self.containers[i].remove(low);
+                if self.containers[i].is_empty() {
+                    self.keys.remove(i);
+                    self.containers.remove(i);
+                }
+                removed
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Count the values less than `value`.
+    ///
+    /// As with [`Bitline::rank_1`](crate::bitline::Bitline::rank_1), the count covers the
+    /// half-open range `[0, value)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::compressed::CompressedBitmap;
+    /// let bitmap: CompressedBitmap = [3, 5, 1 << 20].iter().copied().collect();
+    /// assert_eq!(bitmap.rank(3), 0);
+    /// assert_eq!(bitmap.rank(4), 1);
+    /// assert_eq!(bitmap.rank(u32::MAX), 3);
+    /// ```
+    pub fn rank(&self, value: u32) -> u64 {
+        let (high, low) = split(value);
+        let mut count = 0;
+        for (&key, container) in self.keys.iter().zip(self.containers.iter()) {
+            match key.cmp(&high) {
+                Ordering::Less => count += container.len() as u64,
+                Ordering::Equal => return count + container.rank(low as u32) as u64,
+                Ordering::Greater => break,
+            }
+        }
+        count
+    }
+
+    /// Return the `nth` smallest value (`nth` is 0-indexed: 0 = the minimum), or `None` if
+    /// the bitmap has `nth` or fewer values.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::compressed::CompressedBitmap;
+    /// let bitmap: CompressedBitmap = [3, 5, 1 << 20].iter().copied().collect();
+    /// assert_eq!(bitmap.select(0), Some(3));
+    /// assert_eq!(bitmap.select(2), Some(1 << 20));
+    /// assert_eq!(bitmap.select(3), None);
+    /// ```
+    pub fn select(&self, nth: u64) -> Option<u32> {
+        let mut remaining = nth;
+        for (&key, container) in self.keys.iter().zip(self.containers.iter()) {
+            let len = container.len() as u64;
+            if remaining < len {
+                let low = container.select(remaining as u32)?;
+                return Some(join(key, low));
+            }
+            remaining -= len;
+        }
+        None
+    }
+
+    /// Return an iterator over the values in ascending order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            keys: self.keys.iter(),
+            containers: self.containers.iter(),
+            current: None,
+        }
+    }
+
+    /// Return the values in `self` or in `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::compressed::CompressedBitmap;
+    /// let a: CompressedBitmap = (0..10).collect();
+    /// let b: CompressedBitmap = (5..15).collect();
+    /// assert_eq!(a.union(&b), (0..15).collect());
+    /// ```
+    pub fn union(&self, other: &Self) -> Self {
+        self.merge(other, true, true, Container::union)
+    }
+
+    /// Return the values in both `self` and `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::compressed::CompressedBitmap;
+    /// let a: CompressedBitmap = (0..10).collect();
+    /// let b: CompressedBitmap = (5..15).collect();
+    /// assert_eq!(a.intersection(&b), (5..10).collect());
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.merge(other, false, false, Container::intersection)
+    }
+
+    /// Return the values in `self` but not in `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::compressed::CompressedBitmap;
+    /// let a: CompressedBitmap = (0..10).collect();
+    /// let b: CompressedBitmap = (5..15).collect();
+    /// assert_eq!(a.difference(&b), (0..5).collect());
+    /// ```
+    pub fn difference(&self, other: &Self) -> Self {
+        self.merge(other, true, false, Container::difference)
+    }
+
+    /// Return the values in exactly one of `self` and `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::compressed::CompressedBitmap;
+    /// let a: CompressedBitmap = (0..10).collect();
+    /// let b: CompressedBitmap = (5..15).collect();
+    /// assert_eq!(a.symmetric_difference(&b), (0..5).chain(10..15).collect());
+    /// ```
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.merge(other, true, true, Container::symmetric_difference)
+    }
+
+    /// Return true if every value of `other` is in `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::compressed::CompressedBitmap;
+    /// let bitmap: CompressedBitmap = (0..100).collect();
+    /// assert!(bitmap.includes(&(10..20).collect()));
+    /// assert!(!bitmap.includes(&(90..110).collect()));
+    /// assert!(bitmap.includes(&CompressedBitmap::new()));
+    /// ```
+    pub fn includes(&self, other: &Self) -> bool {
+        let mut i = 0;
+        for (key, container) in other.keys.iter().zip(other.containers.iter()) {
+            match self.keys[i..].binary_search(key) {
+                Ok(offset) => {
+                    i += offset;
+                    if !self.containers[i].includes(container) {
+                        return false;
+                    }
+                }
+                Err(_) => return false,
+            }
+        }
+        true
+    }
+
+    /// Return true if `self` and `other` have a value in common.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::compressed::CompressedBitmap;
+    /// let bitmap: CompressedBitmap = (0..100).collect();
+    /// assert!(bitmap.overlaps(&(90..110).collect()));
+    /// assert!(!bitmap.overlaps(&(100..110).collect()));
+    /// assert!(!bitmap.overlaps(&CompressedBitmap::new()));
+    /// ```
+    pub fn overlaps(&self, other: &Self) -> bool {
+        let (mut i, mut j) = (0, 0);
+        while i < self.keys.len() && j < other.keys.len() {
+            match self.keys[i].cmp(&other.keys[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    if self.containers[i].overlaps(&other.containers[j]) {
+                        return true;
+                    }
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        false
+    }
+
+    /// Re-encode every container in its smallest form.
+    ///
+    /// Insertions and removals only switch between arrays and bitmaps; call this after
+    /// building a bitmap value by value to turn long runs of consecutive values into run
+    /// containers. Collecting from an iterator and the set operations already do so.
+    pub fn optimize(&mut self) {
+        for container in self.containers.iter_mut() {
+            let taken = core::mem::replace(container, Container::Array(Vec::new()));
+            *container = taken.optimize();
+        }
+    }
+
+    /// Combine the containers of both bitmaps key by key. Containers whose key is only in
+    /// `self` or only in `other` are kept if `keep_self` or `keep_other` is set.
+    fn merge<F>(&self, other: &Self, keep_self: bool, keep_other: bool, op: F) -> Self
+    where
+        F: Fn(&Container, &Container) -> Container,
+    {
+        let mut result = CompressedBitmap::new();
+        let mut push = |key: u16, container: Container| {
+            if !container.is_empty() {
+                result.keys.push(key);
+                result.containers.push(container);
+            }
+        };
+        let (mut i, mut j) = (0, 0);
+        while i < self.keys.len() || j < other.keys.len() {
+            let order = match (self.keys.get(i), other.keys.get(j)) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                _ => Ordering::Greater,
+            };
+            match order {
+                Ordering::Less => {
+                    if keep_self {
+                        push(self.keys[i], self.containers[i].clone());
+                    }
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    if keep_other {
+                        push(other.keys[j], other.containers[j].clone());
+                    }
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    push(self.keys[i], op(&self.containers[i], &other.containers[j]));
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Split a value into its high (container key) and low (container value) halves.
+#[inline]
+fn split(value: u32) -> (u16, u16) {
+    ((value >> 16) as u16, value as u16)
+}
+
+#[inline]
+fn join(high: u16, low: u16) -> u32 {
+    (high as u32) << 16 | low as u32
+}
+
+impl PartialEq for CompressedBitmap {
+    /// Compare the values, whatever containers hold them.
+    fn eq(&self, other: &Self) -> bool {
+        self.keys == other.keys
+            && self
+                .containers
+                .iter()
+                .zip(other.containers.iter())
+                .all(|(a, b)| a.same_values(b))
+    }
+}
+
+impl Eq for CompressedBitmap {}
+
+impl fmt::Debug for CompressedBitmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl Extend<u32> for CompressedBitmap {
+    fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl FromIterator<u32> for CompressedBitmap {
+    /// Collect the values and re-encode every container in its smallest form.
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut bitmap = CompressedBitmap::new();
+        bitmap.extend(iter);
+        bitmap.optimize();
+        bitmap
+    }
+}
+
+impl<'a> IntoIterator for &'a CompressedBitmap {
+    type Item = u32;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the values of a [`CompressedBitmap`] in ascending order.
+///
+/// This struct is created by [`CompressedBitmap::iter`].
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    keys: slice::Iter<'a, u16>,
+    containers: slice::Iter<'a, Container>,
+    current: Option<(u16, ContainerIter<'a>)>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            if let Some((key, values)) = &mut self.current {
+                if let Some(low) = values.next() {
+                    return Some(join(*key, low));
+                }
+            }
+            let key = *self.keys.next()?;
+            let container = self.containers.next()?;
+            self.current = Some((key, container.iter()));
+        }
+    }
+}
+
+impl FusedIterator for Iter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::test_util::xorshift64;
+
+    /// Values clustered so that every container form appears: sparse keys, dense keys and
+    /// long runs.
+    fn pseudo_random_values(count: usize, seed: u64) -> Vec<u32> {
+        let mut state = seed;
+        let mut values = Vec::with_capacity(count);
+        while values.len() < count {
+            xorshift64(&mut state);
+            let key = (state % 6) as u32;
+            let low = (state >> 32) as u32 & 0xffff;
+            match key {
+                0 | 1 => values.push(key << 16 | low),
+                2 => values.extend((0..200).map(|i| key << 16 | (low + i * 3) & 0xffff)),
+                3 => values.extend(
+                    (0..(state >> 20) % 300).map(|i| key << 16 | (low + i as u32) & 0xffff),
+                ),
+                _ => values.push((state >> 8) as u32),
+            }
+        }
+        values
+    }
+
+    fn kind(container: &Container) -> &'static str {
+        match container {
+            Container::Array(_) => "array",
+            Container::Bitmap { .. } => "bitmap",
+            Container::Run(_) => "run",
+        }
+    }
+
+    fn check_against(bitmap: &CompressedBitmap, expected: &BTreeSet<u32>) {
+        assert_eq!(bitmap.len(), expected.len() as u64);
+        assert!(bitmap.iter().eq(expected.iter().copied()));
+        assert_eq!(bitmap.is_empty(), expected.is_empty());
+    }
+
+    #[test]
+    fn test_insert_remove_contains() {
+        let values = pseudo_random_values(20000, 0x9e3779b97f4a7c15);
+        let mut bitmap = CompressedBitmap::new();
+        let mut expected = BTreeSet::new();
+        for &value in values.iter() {
+            assert_eq!(bitmap.insert(value), expected.insert(value));
+        }
+        check_against(&bitmap, &expected);
+        for &value in values.iter().step_by(3) {
+            assert_eq!(bitmap.remove(value), expected.remove(&value));
+            assert!(!bitmap.contains(value));
+        }
+        check_against(&bitmap, &expected);
+        bitmap.optimize();
+        check_against(&bitmap, &expected);
+        for &value in values.iter() {
+            assert_eq!(bitmap.contains(value), expected.contains(&value));
+            assert_eq!(bitmap.remove(value), expected.remove(&value));
+        }
+        assert!(bitmap.is_empty());
+        assert!(bitmap.keys.is_empty());
+    }
+
+    #[test]
+    fn test_containers_are_chosen_by_size() {
+        let mut bitmap: CompressedBitmap = (0..100_000)
+            .chain((1 << 20..(1 << 20) + 5000).map(|v| v * 2))
+            .chain([5 << 16 | 1, 5 << 16 | 9].iter().copied())
+            .collect();
+        bitmap.remove(70_000);
+        let kinds: Vec<&str> = bitmap.containers.iter().map(kind).collect();
+        assert_eq!(kinds, vec!["run", "run", "array", "bitmap"]);
+        assert_eq!(bitmap.len(), 100_000 - 1 + 2 + 5000);
+    }
+
+    #[test]
+    fn test_set_operations_match_btreeset() {
+        let mut kinds = BTreeSet::new();
+        for seed in 1..6_u64 {
+            let a_values = pseudo_random_values(8000, seed);
+            let b_values = pseudo_random_values(8000, seed * 7919);
+            let a: CompressedBitmap = a_values.iter().copied().collect();
+            let b: CompressedBitmap = b_values.iter().copied().collect();
+            let a_set: BTreeSet<u32> = a_values.iter().copied().collect();
+            let b_set: BTreeSet<u32> = b_values.iter().copied().collect();
+            kinds.extend(a.containers.iter().map(kind));
+            check_against(&a.union(&b), &a_set.union(&b_set).copied().collect());
+            check_against(
+                &a.intersection(&b),
+                &a_set.intersection(&b_set).copied().collect(),
+            );
+            check_against(
+                &a.difference(&b),
+                &a_set.difference(&b_set).copied().collect(),
+            );
+            check_against(
+                &a.symmetric_difference(&b),
+                &a_set.symmetric_difference(&b_set).copied().collect(),
+            );
+            assert_eq!(a.overlaps(&b), !a_set.is_disjoint(&b_set));
+            assert!(a.union(&b).includes(&a));
+            assert!(a.includes(&a.intersection(&b)));
+            assert_eq!(a.includes(&b), a_set.is_superset(&b_set));
+            assert!(!a.difference(&b).overlaps(&b));
+            assert_eq!(a.union(&b).difference(&b), a.difference(&b));
+        }
+        assert_eq!(kinds.len(), 3);
+    }
+
+    #[test]
+    fn test_rank_select() {
+        let values = pseudo_random_values(10000, 42);
+        let bitmap: CompressedBitmap = values.iter().copied().collect();
+        let sorted: Vec<u32> = values
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        for (i, &value) in sorted.iter().enumerate() {
+            assert_eq!(bitmap.rank(value), i as u64);
+            assert_eq!(bitmap.select(i as u64), Some(value));
+        }
+        assert_eq!(bitmap.select(sorted.len() as u64), None);
+        assert_eq!(bitmap.rank(0), 0);
+        let full: CompressedBitmap = [u32::MAX].iter().copied().collect();
+        assert_eq!(full.rank(u32::MAX), 0);
+        assert_eq!(full.select(0), Some(u32::MAX));
+    }
+
+    #[test]
+    fn test_equality_ignores_container_forms() {
+        let mut a: CompressedBitmap = (0..5000).collect();
+        let b = a.clone();
+        a.insert(1 << 16);
+        a.remove(1 << 16);
+        assert_eq!(a, b);
+        let mut c = CompressedBitmap::new();
+        c.extend(0..5000);
+        assert!(matches!(c.containers[0], Container::Bitmap { .. }));
+        assert!(matches!(b.containers[0], Container::Run(_)));
+        assert_eq!(b, c);
+        c.remove(0);
+        assert_ne!(b, c);
+        assert_eq!(
+            format!("{:?}", a.intersection(&(3..6).collect())),
+            "{3, 4, 5}"
+        );
+    }
+}
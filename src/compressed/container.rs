@@ -0,0 +1,624 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::{self, Ordering};
+use core::iter::FusedIterator;
+use core::slice;
+
+/// The largest cardinality stored as a sorted array; denser containers use a bitmap.
+pub(crate) const ARRAY_MAX: u32 = 4096;
+
+/// The number of `u64` words of a bitmap container, one bit for each of the 65536 values.
+pub(crate) const BITMAP_WORDS: usize = 1024;
+
+/// A set of 16-bit values, the low halves of the elements sharing one high half.
+///
+/// Bitmap containers store value `v` at bit `v % 64` of word `v / 64`, so that ascending
+/// values are visited with `trailing_zeros`. Run containers store inclusive `(start, last)`
+/// intervals, sorted and separated by at least one missing value.
+#[derive(Clone, Debug)]
+pub(crate) enum Container {
+    Array(Vec<u16>),
+    Bitmap {
+        words: Box<[u64; BITMAP_WORDS]>,
+        len: u32,
+    },
+    Run(Vec<(u16, u16)>),
+}
+
+impl Container {
+    pub(crate) fn len(&self) -> u32 {
+        match self {
+            Container::Array(values) => values.len() as u32,
+            Container::Bitmap { len, .. } => *len,
+            Container::Run(runs) => runs
+                .iter()
+                .map(|&(start, last)| (last - start) as u32 + 1)
+                .sum(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        match self {
+            Container::Array(values) => values.is_empty(),
+            Container::Bitmap { len, .. } => *len == 0,
+            Container::Run(runs) => runs.is_empty(),
+        }
+    }
+
+    pub(crate) fn contains(&self, value: u16) -> bool {
+        match self {
+            Container::Array(values) => values.binary_search(&value).is_ok(),
+            Container::Bitmap { words, .. } => {
+                words[value as usize / 64] & (1 << (value % 64)) != 0
+            }
+            Container::Run(runs) => {
+                let i = runs.partition_point(|&(start, _)| start <= value);
+                i > 0 && runs[i - 1].1 >= value
+            }
+        }
+    }
+
+    /// Insert `value`, returning whether it was absent.
+    ///
+    /// An array reaching [`ARRAY_MAX`] becomes a bitmap, and a run container that
+    /// fragments past the size of the alternatives is re-encoded.
+    pub(crate) fn insert(&mut self, value: u16) -> bool {
+        match self {
+            Container::Array(values) => match values.binary_search(&value) {
+                Ok(_) => false,
+                Err(i) => {
+                    if values.len() as u32 == ARRAY_MAX {
+                        let mut bitmap = Container::Bitmap {
+                            words: self.to_words(),
+                            len: ARRAY_MAX,
+                        };
+                        bitmap.insert(value);
+                        *self = bitmap;
+                    } else {
+                        values.insert(i, value);
+                    }
+                    true
+                }
+            },
+            Container::Bitmap { words, len } => {
+                let word = &mut words[value as usize / 64];
+                let bit = 1 << (value % 64);
+                if *word & bit != 0 {
+                    return false;
+                }
+                *word |= bit;
+                *len += 1;
+                true
+            }
+            Container::Run(runs) => {
+                let i = runs.partition_point(|&(start, _)| start <= value);
+                if i > 0 && runs[i - 1].1 >= value {
+                    return false;
+                }
+                // Neither neighbor contains `value`, so `value - 1` and `value + 1` do not
+                // overflow when they are tested.
+                let joins_left = i > 0 && runs[i - 1].1 == value - 1;
+                let joins_right = i < runs.len() && runs[i].0 == value + 1;
+                match (joins_left, joins_right) {
+                    (true, true) => {
+                        runs[i - 1].1 = runs[i].1;
+                        runs.remove(i);
+                    }
+                    (true, false) => runs[i - 1].1 = value,
+                    (false, true) => runs[i].0 = value,
+                    (false, false) => runs.insert(i, (value, value)),
+                }
+                self.reencode_if_fragmented();
+                true
+            }
+        }
+    }
+
+    /// Remove `value`, returning whether it was present.
+    ///
+    /// A bitmap dropping to [`ARRAY_MAX`] becomes an array, and a run container that
+    /// fragments past the size of the alternatives is re-encoded.
+    pub(crate) fn remove(&mut self, value: u16) -> bool {
+        match self {
+            Container::Array(values) => match values.binary_search(&value) {
+                Ok(i) => {
+                    values.remove(i);
+                    true
+                }
+                Err(_) => false,
+            },
+            Container::Bitmap { words, len } => {
+                let word = &mut words[value as usize / 64];
+                let bit = 1 << (value % 64);
+                if *word & bit == 0 {
+                    return false;
+                }
+                *word &= !bit;
+                *len -= 1;
+                if *len == ARRAY_MAX {
+                    *self = Container::Array(self.iter().collect());
+                }
+                true
+            }
+            Container::Run(runs) => {
+                let i = runs.partition_point(|&(start, _)| start <= value);
+                if i == 0 || runs[i - 1].1 < value {
+                    return false;
+                }
+                let (start, last) = runs[i - 1];
+                if start == last {
+                    runs.remove(i - 1);
+                } else if value == start {
+                    runs[i - 1].0 = start + 1;
+                } else if value == last {
+                    runs[i - 1].1 = last - 1;
+                } else {
+                    runs[i - 1].1 = value - 1;
+                    runs.insert(i, (value + 1, last));
+                }
+                self.reencode_if_fragmented();
+                true
+            }
+        }
+    }
+
+    /// Count the values less than `value`, which may be 65536 to count every value.
+    pub(crate) fn rank(&self, value: u32) -> u32 {
+        match self {
+            Container::Array(values) => values.partition_point(|&v| (v as u32) < value) as u32,
+            Container::Bitmap { words, .. } => {
+                let index = value as usize / 64;
+                let full: u32 = words[..index].iter().map(|word| word.count_ones()).sum();
+                let partial = match words.get(index) {
+                    Some(word) => (word & ((1 << (value % 64)) - 1)).count_ones(),
+                    None => 0,
+                };
+                full + partial
+            }
+            Container::Run(runs) => runs
+                .iter()
+                .take_while(|&&(start, _)| (start as u32) < value)
+                .map(|&(start, last)| cmp::min(last as u32 + 1, value) - start as u32)
+                .sum(),
+        }
+    }
+
+    /// Return the `nth` smallest value (0-indexed).
+    pub(crate) fn select(&self, nth: u32) -> Option<u16> {
+        match self {
+            Container::Array(values) => values.get(nth as usize).copied(),
+            Container::Bitmap { words, .. } => {
+                let mut remaining = nth;
+                for (i, &word) in words.iter().enumerate() {
+                    let count = word.count_ones();
+                    if remaining < count {
+                        let mut word = word;
+                        for _ in 0..remaining {
+                            word &= word - 1;
+                        }
+                        return Some((i * 64) as u16 + word.trailing_zeros() as u16);
+                    }
+                    remaining -= count;
+                }
+                None
+            }
+            Container::Run(runs) => {
+                let mut remaining = nth;
+                for &(start, last) in runs {
+                    let count = (last - start) as u32 + 1;
+                    if remaining < count {
+                        return Some(start + remaining as u16);
+                    }
+                    remaining -= count;
+                }
+                None
+            }
+        }
+    }
+
+    pub(crate) fn iter(&self) -> ContainerIter<'_> {
+        match self {
+            Container::Array(values) => ContainerIter::Array(values.iter()),
+            Container::Bitmap { words, .. } => ContainerIter::Bitmap {
+                words: words.iter(),
+                base: 0,
+                word: 0,
+            },
+            Container::Run(runs) => ContainerIter::Run {
+                runs: runs.iter(),
+                next: 1,
+                last: 0,
+            },
+        }
+    }
+
+    /// Return the values as bitmap words.
+    pub(crate) fn to_words(&self) -> Box<[u64; BITMAP_WORDS]> {
+        if let Container::Bitmap { words, .. } = self {
+            return words.clone();
+        }
+        let mut words = Box::new([0; BITMAP_WORDS]);
+        match self {
+            Container::Run(runs) => {
+                for &(start, last) in runs {
+                    fill_words(&mut words, start as usize, last as usize + 1);
+                }
+            }
+            _ => {
+                for value in self.iter() {
+                    words[value as usize / 64] |= 1 << (value % 64);
+                }
+            }
+        }
+        words
+    }
+
+    /// Return the number of maximal runs of consecutive values.
+    pub(crate) fn count_runs(&self) -> u32 {
+        match self {
+            Container::Array(values) => {
+                let breaks = values.windows(2).filter(|w| w[1] != w[0] + 1).count();
+                (breaks + usize::from(!values.is_empty())) as u32
+            }
+            Container::Bitmap { words, .. } => {
+                let mut carry = 0;
+                let mut runs = 0;
+                for &word in words.iter() {
+                    // A run starts at every set bit whose lower neighbor is clear.
+                    runs += (word & !((word << 1) | carry)).count_ones();
+                    carry = word >> 63;
+                }
+                runs
+            }
+            Container::Run(runs) => runs.len() as u32,
+        }
+    }
+
+    /// Re-encode the container in whichever of the three forms is smallest.
+    ///
+    /// The sizes are those of the serialized forms: 2 bytes per array value, 8192 bytes for
+    /// a bitmap and 2 + 4 bytes per run. Arrays hold at most [`ARRAY_MAX`] values.
+    pub(crate) fn optimize(self) -> Container {
+        let len = self.len();
+        let run_bytes = 2 + 4 * self.count_runs();
+        let other_bytes = if len <= ARRAY_MAX { 2 * len } else { 8192 };
+        if run_bytes < other_bytes {
+            match self {
+                Container::Run(_) => self,
+                _ => Container::Run(runs_of(self.iter())),
+            }
+        } else if len <= ARRAY_MAX {
+            match self {
+                Container::Array(_) => self,
+                _ => Container::Array(self.iter().collect()),
+            }
+        } else {
+            match self {
+                Container::Bitmap { .. } => self,
+                _ => Container::Bitmap {
+                    words: self.to_words(),
+                    len,
+                },
+            }
+        }
+    }
+
+    /// Leave the run form once it is no smaller than an array or a bitmap.
+    fn reencode_if_fragmented(&mut self) {
+        if let Container::Run(runs) = self {
+            let run_bytes = 2 + 4 * runs.len() as u32;
+            let len = self.len();
+            if run_bytes >= cmp::min(2 * len, 8192) {
+                let container = core::mem::replace(self, Container::Array(Vec::new()));
+                *self = container.optimize();
+            }
+        }
+    }
+
+    /// Return the number of values in the inclusive range `start..=last`.
+    fn range_len(&self, start: u16, last: u16) -> u32 {
+        self.rank(last as u32 + 1) - self.rank(start as u32)
+    }
+
+    pub(crate) fn union(&self, other: &Container) -> Container {
+        match (self, other) {
+            (Container::Array(a), Container::Array(b)) => {
+                Container::Array(merge(a, b, true, true, true)).optimize()
+            }
+            _ => combine_words(self, other, |a, b| a | b),
+        }
+    }
+
+    pub(crate) fn intersection(&self, other: &Container) -> Container {
+        match (self, other) {
+            (Container::Array(values), _) => Container::Array(
+                values
+                    .iter()
+                    .copied()
+                    .filter(|&v| other.contains(v))
+                    .collect(),
+            )
+            .optimize(),
+            (_, Container::Array(_)) => other.intersection(self),
+            _ => combine_words(self, other, |a, b| a & b),
+        }
+    }
+
+    pub(crate) fn difference(&self, other: &Container) -> Container {
+        match self {
+            Container::Array(values) => Container::Array(
+                values
+                    .iter()
+                    .copied()
+                    .filter(|&v| !other.contains(v))
+                    .collect(),
+            )
+            .optimize(),
+            _ => combine_words(self, other, |a, b| a & !b),
+        }
+    }
+
+    pub(crate) fn symmetric_difference(&self, other: &Container) -> Container {
+        match (self, other) {
+            (Container::Array(a), Container::Array(b)) => {
+                Container::Array(merge(a, b, true, false, true)).optimize()
+            }
+            _ => combine_words(self, other, |a, b| a ^ b),
+        }
+    }
+
+    /// Return whether every value of `other` is in `self`.
+    pub(crate) fn includes(&self, other: &Container) -> bool {
+        if other.len() > self.len() {
+            return false;
+        }
+        match (self, other) {
+            (_, Container::Run(runs)) => runs
+                .iter()
+                .all(|&(start, last)| self.range_len(start, last) == (last - start) as u32 + 1),
+            (Container::Bitmap { words: a, .. }, Container::Bitmap { words: b, .. }) => {
+                a.iter().zip(b.iter()).all(|(a, b)| b & !a == 0)
+            }
+            _ => other.iter().all(|v| self.contains(v)),
+        }
+    }
+
+    /// Return whether `self` and `other` share a value.
+    pub(crate) fn overlaps(&self, other: &Container) -> bool {
+        match (self, other) {
+            (Container::Array(values), _) => values.iter().any(|&v| other.contains(v)),
+            (_, Container::Array(_)) => other.overlaps(self),
+            (Container::Run(runs), _) => runs
+                .iter()
+                .any(|&(start, last)| other.range_len(start, last) > 0),
+            (_, Container::Run(_)) => other.overlaps(self),
+            (Container::Bitmap { words: a, .. }, Container::Bitmap { words: b, .. }) => {
+                a.iter().zip(b.iter()).any(|(a, b)| a & b != 0)
+            }
+        }
+    }
+
+    /// Return whether both containers hold the same values, whatever their forms.
+    pub(crate) fn same_values(&self, other: &Container) -> bool {
+        self.len() == other.len() && self.includes(other)
+    }
+}
+
+/// Set bits `begin..end` of bitmap words.
+fn fill_words(words: &mut [u64; BITMAP_WORDS], begin: usize, end: usize) {
+    let mut position = begin;
+    while position < end {
+        let offset = position % 64;
+        let width = cmp::min(64 - offset, end - position);
+        words[position / 64] |= (u64::MAX >> (64 - width)) << offset;
+        position += width;
+    }
+}
+
+/// Group ascending values into inclusive `(start, last)` runs.
+fn runs_of<I: Iterator<Item = u16>>(values: I) -> Vec<(u16, u16)> {
+    let mut runs: Vec<(u16, u16)> = Vec::new();
+    for value in values {
+        match runs.last_mut() {
+            Some(run) if run.1 as u32 + 1 == value as u32 => run.1 = value,
+            _ => runs.push((value, value)),
+        }
+    }
+    runs
+}
+
+/// Merge two sorted arrays, keeping the values only in `a`, in both, or only in `b`.
+fn merge(a: &[u16], b: &[u16], only_a: bool, both: bool, only_b: bool) -> Vec<u16> {
+    let mut result = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => {
+                if only_a {
+                    result.push(a[i]);
+                }
+                i += 1;
+            }
+            Ordering::Greater => {
+                if only_b {
+                    result.push(b[j]);
+                }
+                j += 1;
+            }
+            Ordering::Equal => {
+                if both {
+                    result.push(a[i]);
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    if only_a {
+        result.extend_from_slice(&a[i..]);
+    }
+    if only_b {
+        result.extend_from_slice(&b[j..]);
+    }
+    result
+}
+
+/// Combine two containers word by word and re-encode the result.
+fn combine_words<F: Fn(u64, u64) -> u64>(a: &Container, b: &Container, op: F) -> Container {
+    let mut words = a.to_words();
+    let other = b.to_words();
+    let mut len = 0;
+    for (word, other) in words.iter_mut().zip(other.iter()) {
+        *word = op(*word, *other);
+        len += word.count_ones();
+    }
+    Container::Bitmap { words, len }.optimize()
+}
+
+/// An iterator over the values of a container in ascending order.
+#[derive(Clone, Debug)]
+pub(crate) enum ContainerIter<'a> {
+    Array(slice::Iter<'a, u16>),
+    Bitmap {
+        words: slice::Iter<'a, u64>,
+        base: u32,
+        word: u64,
+    },
+    Run {
+        runs: slice::Iter<'a, (u16, u16)>,
+        next: u32,
+        last: u32,
+    },
+}
+
+impl Iterator for ContainerIter<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        match self {
+            ContainerIter::Array(values) => values.next().copied(),
+            ContainerIter::Bitmap { words, base, word } => {
+                while *word == 0 {
+                    *word = *words.next()?;
+                    *base += 64;
+                }
+                // `base` is one word ahead of the word being drained.
+                let value = *base - 64 + word.trailing_zeros();
+                *word &= *word - 1;
+                Some(value as u16)
+            }
+            ContainerIter::Run { runs, next, last } => {
+                if *next > *last {
+                    let &(start, end) = runs.next()?;
+                    *next = start as u32;
+                    *last = end as u32;
+                }
+                let value = *next;
+                *next += 1;
+                Some(value as u16)
+            }
+        }
+    }
+}
+
+impl FusedIterator for ContainerIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_values(values: &[u16]) -> Container {
+        let mut container = Container::Array(Vec::new());
+        for &value in values {
+            container.insert(value);
+        }
+        container
+    }
+
+    fn kind(container: &Container) -> &'static str {
+        match container {
+            Container::Array(_) => "array",
+            Container::Bitmap { .. } => "bitmap",
+            Container::Run(_) => "run",
+        }
+    }
+
+    #[test]
+    fn test_array_becomes_bitmap_and_back() {
+        let values: Vec<u16> = (0..=ARRAY_MAX as u16).map(|v| v * 13).collect();
+        let mut container = from_values(&values[..ARRAY_MAX as usize]);
+        assert_eq!(kind(&container), "array");
+        assert!(container.insert(values[ARRAY_MAX as usize]));
+        assert_eq!(kind(&container), "bitmap");
+        assert_eq!(container.len(), ARRAY_MAX + 1);
+        assert!(container.remove(0));
+        assert_eq!(kind(&container), "array");
+        assert!(container.iter().eq(values[1..].iter().copied()));
+    }
+
+    #[test]
+    fn test_optimize_chooses_smallest_form() {
+        let dense = Container::Array((0..1000).collect()).optimize();
+        assert_eq!(kind(&dense), "run");
+        let sparse = Container::Run((0..100).map(|v| (v * 3, v * 3)).collect()).optimize();
+        assert_eq!(kind(&sparse), "array");
+        let noisy = Container::Array((0..20000).map(|v| v * 3).collect()).optimize();
+        assert_eq!(kind(&noisy), "bitmap");
+        assert_eq!(noisy.len(), 20000);
+        assert_eq!(noisy.count_runs(), 20000);
+    }
+
+    #[test]
+    fn test_run_insert_and_remove() {
+        let mut container = Container::Run(vec![(10, 20), (30, 40)]);
+        assert!(!container.insert(15));
+        assert!(container.insert(21));
+        assert!(container.insert(29));
+        assert!(container.insert(9));
+        assert!(container.remove(35));
+        assert!(!container.remove(35));
+        assert!(container.remove(9));
+        assert!(
+            matches!(&container, Container::Run(runs) if runs == &[(10, 21), (29, 34), (36, 40)])
+        );
+        assert_eq!(container.len(), 12 + 6 + 5);
+        // Fragmenting the runs switches to an array.
+        for value in (10..21).step_by(2) {
+            container.remove(value);
+        }
+        assert_eq!(kind(&container), "array");
+        let mut full = Container::Run(vec![(0, u16::MAX)]);
+        assert!(!full.insert(u16::MAX));
+        assert!(full.remove(u16::MAX));
+        assert!(full.insert(u16::MAX));
+        assert_eq!(full.len(), 65536);
+    }
+
+    #[test]
+    fn test_rank_select_iter_agree_across_forms() {
+        let values: Vec<u16> = (0..3000_u32)
+            .map(|v| ((v * v * 7) % 65536) as u16)
+            .collect();
+        let array = from_values(&values);
+        let sorted: Vec<u16> = array.iter().collect();
+        let forms = [
+            array.clone(),
+            Container::Bitmap {
+                words: array.to_words(),
+                len: array.len(),
+            },
+            Container::Run(runs_of(array.iter())),
+        ];
+        for container in forms.iter() {
+            assert!(container.iter().eq(sorted.iter().copied()));
+            assert_eq!(container.count_runs(), forms[2].count_runs());
+            for (i, &value) in sorted.iter().enumerate() {
+                assert_eq!(container.rank(value as u32), i as u32);
+                assert_eq!(container.select(i as u32), Some(value));
+            }
+            assert_eq!(container.rank(65536), sorted.len() as u32);
+            assert_eq!(container.select(sorted.len() as u32), None);
+            assert!(container.same_values(&forms[0]));
+        }
+    }
+}
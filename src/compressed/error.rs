@@ -0,0 +1,26 @@
+use core::fmt;
+
+/// An error from [`CompressedBitmap::deserialize_roaring`](super::CompressedBitmap::deserialize_roaring).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoaringError {
+    /// The bytes end before the serialized bitmap does.
+    UnexpectedEnd,
+    /// The bytes do not start with either serialization cookie.
+    InvalidCookie,
+    /// A container is malformed: keys or values out of order, overlapping runs, or a
+    /// cardinality that does not match the container contents.
+    InvalidContainer,
+}
+
+impl fmt::Display for RoaringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoaringError::UnexpectedEnd => f.write_str("unexpected end of serialized bitmap"),
+            RoaringError::InvalidCookie => f.write_str("invalid serialization cookie"),
+            RoaringError::InvalidContainer => f.write_str("invalid container in serialized bitmap"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RoaringError {}
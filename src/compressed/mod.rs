@@ -0,0 +1,41 @@
+//! Compressed sets of `u32` values in the style of Roaring bitmaps.
+//!
+//! A [`CompressedBitmap`] splits every value into a 16-bit key (the high half) and a 16-bit
+//! low half, and stores the low halves sharing a key in one container. Each container takes
+//! whichever of three forms is smallest:
+//!
+//! - a sorted array of up to 4096 values, 2 bytes per value;
+//! - a bitmap of 65536 bits (8 KiB), for denser containers;
+//! - a sorted list of runs of consecutive values, 4 bytes per run.
+//!
+//! Inserting and removing values switch between arrays and bitmaps at 4096 values, and
+//! leave the run form once it is no longer the smallest. Collecting from an iterator, the
+//! set operations and [`CompressedBitmap::optimize`] pick the smallest of all three forms.
+//!
+//! [`CompressedBitmap::serialize_roaring`] and [`CompressedBitmap::deserialize_roaring`]
+//! read and write the [portable Roaring format] of the Java, C and Go libraries, byte for
+//! byte.
+//!
+//! [portable Roaring format]: https://github.com/RoaringBitmap/RoaringFormatSpec
+//!
+//! # Example
+//!
+//! ```
+//! use bittersweet::compressed::CompressedBitmap;
+//! let evens: CompressedBitmap = (0..1_000_000).map(|v| v * 2).collect();
+//! let block: CompressedBitmap = (500_000..600_000).collect();
+//! let both = evens.intersection(&block);
+//! assert_eq!(both.len(), 50_000);
+//! assert_eq!(both.select(0), Some(500_000));
+//! assert_eq!(both.rank(500_010), 5);
+//! assert!(evens.includes(&both) && block.includes(&both));
+//! assert!(!evens.overlaps(&(1_000_001..1_000_002).collect()));
+//! ```
+
+mod bitmap;
+mod container;
+mod error;
+mod serialize;
+// re-export
+pub use bitmap::{CompressedBitmap, Iter};
+pub use error::RoaringError;
@@ -0,0 +1,410 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use super::bitmap::CompressedBitmap;
+use super::container::{Container, ARRAY_MAX, BITMAP_WORDS};
+use super::error::RoaringError;
+
+/// The cookie of bitmaps without run containers, followed by a 32-bit container count.
+const SERIAL_COOKIE_NO_RUNCONTAINER: u32 = 12346;
+
+/// The cookie of bitmaps with run containers, sharing its 32 bits with the container count.
+const SERIAL_COOKIE: u16 = 12347;
+
+/// Bitmaps with run containers only have an offset header from this many containers on.
+const NO_OFFSET_THRESHOLD: usize = 4;
+
+/// The largest number of containers, one for each 16-bit key.
+const MAX_CONTAINERS: usize = 1 << 16;
+
+impl CompressedBitmap {
+    /// Return the length in bytes of [`serialize_roaring`](Self::serialize_roaring)'s output.
+    pub fn serialized_size(&self) -> usize {
+        let size = self.containers.len();
+        let header = if self.has_run_containers() {
+            let offsets = if size >= NO_OFFSET_THRESHOLD {
+                4 * size
+            } else {
+                0
+            };
+            4 + (size + 7) / 8 + 4 * size + offsets
+        } else {
+            8 + 8 * size
+        };
+        header + self.containers.iter().map(container_size).sum::<usize>()
+    }
+
+    /// Serialize the bitmap in the portable Roaring format.
+    ///
+    /// The output follows the [Roaring format specification] shared by the Java, C and Go
+    /// libraries: a cookie, the key and cardinality of every container, their offsets, and
+    /// then the containers themselves, all little-endian. Every container is written in its
+    /// current form, so call [`optimize`](Self::optimize) first to write the smallest
+    /// encoding.
+    ///
+    /// [Roaring format specification]: https://github.com/RoaringBitmap/RoaringFormatSpec
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::compressed::CompressedBitmap;
+    /// let bitmap: CompressedBitmap = [1, 2, 3].iter().copied().collect();
+    /// let bytes = bitmap.serialize_roaring();
+    /// assert_eq!(bytes.len(), bitmap.serialized_size());
+    /// assert_eq!(CompressedBitmap::deserialize_roaring(&bytes), Ok(bitmap));
+    /// ```
+    pub fn serialize_roaring(&self) -> Vec<u8> {
+        let size = self.containers.len();
+        let has_runs = self.has_run_containers();
+        let mut bytes = Vec::with_capacity(self.serialized_size());
+        if has_runs {
+            bytes.extend_from_slice(&SERIAL_COOKIE.to_le_bytes());
+            bytes.extend_from_slice(&((size - 1) as u16).to_le_bytes());
+            let mut flags = alloc::vec![0_u8; (size + 7) / 8];
+            for (i, container) in self.containers.iter().enumerate() {
+                if let Container::Run(_) = container {
+                    flags[i / 8] |= 1 << (i % 8);
+                }
+            }
+            bytes.extend_from_slice(&flags);
+        } else {
+            bytes.extend_from_slice(&SERIAL_COOKIE_NO_RUNCONTAINER.to_le_bytes());
+            bytes.extend_from_slice(&(size as u32).to_le_bytes());
+        }
+        for (key, container) in self.keys.iter().zip(self.containers.iter()) {
+            bytes.extend_from_slice(&key.to_le_bytes());
+            bytes.extend_from_slice(&((container.len() - 1) as u16).to_le_bytes());
+        }
+        if !has_runs || size >= NO_OFFSET_THRESHOLD {
+            let mut offset = bytes.len() + 4 * size;
+            for container in self.containers.iter() {
+                bytes.extend_from_slice(&(offset as u32).to_le_bytes());
+                offset += container_size(container);
+            }
+        }
+        for container in self.containers.iter() {
+            match container {
+                Container::Array(values) => {
+                    for value in values.iter() {
+                        bytes.extend_from_slice(&value.to_le_bytes());
+                    }
+                }
+                Container::Bitmap { words, .. } => {
+                    for word in words.iter() {
+                        bytes.extend_from_slice(&word.to_le_bytes());
+                    }
+                }
+                Container::Run(runs) => {
+                    bytes.extend_from_slice(&(runs.len() as u16).to_le_bytes());
+                    for &(start, last) in runs.iter() {
+                        bytes.extend_from_slice(&start.to_le_bytes());
+                        bytes.extend_from_slice(&(last - start).to_le_bytes());
+                    }
+                }
+            }
+        }
+        bytes
+    }
+
+    /// Deserialize a bitmap written in the portable Roaring format.
+    ///
+    /// Containers keep the form they were written in, so serializing the result again
+    /// reproduces the input. The bitmap is read from the start of `bytes` and any bytes after
+    /// it are ignored; its [`serialized_size`](Self::serialized_size) tells where it ends.
+    /// The offset header is skipped, as containers are read in order.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::compressed::{CompressedBitmap, RoaringError};
+    /// // Cookie 12346, one container with key 0 holding the two values 1 and 5.
+    /// let bytes = [
+    ///     0x3a, 0x30, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 16, 0, 0, 0, 1, 0, 5, 0,
+    /// ];
+    /// let bitmap = CompressedBitmap::deserialize_roaring(&bytes).unwrap();
+    /// assert_eq!(bitmap.iter().collect::<Vec<_>>(), vec![1, 5]);
+    /// assert_eq!(
+    ///     CompressedBitmap::deserialize_roaring(&bytes[..19]),
+    ///     Err(RoaringError::UnexpectedEnd)
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RoaringError::UnexpectedEnd`] if `bytes` is too short,
+    /// [`RoaringError::InvalidCookie`] if it does not start with a serialization cookie, and
+    /// [`RoaringError::InvalidContainer`] if the containers are malformed.
+    pub fn deserialize_roaring(bytes: &[u8]) -> Result<Self, RoaringError> {
+        let mut input = Input { bytes };
+        let cookie = input.read_u32()?;
+        let (size, run_flags) = if cookie == SERIAL_COOKIE_NO_RUNCONTAINER {
+            (input.read_u32()? as usize, None)
+        } else if cookie as u16 == SERIAL_COOKIE {
+            let size = (cookie >> 16) as usize + 1;
+            (size, Some(input.take((size + 7) / 8)?))
+        } else {
+            return Err(RoaringError::InvalidCookie);
+        };
+        if size > MAX_CONTAINERS {
+            return Err(RoaringError::InvalidContainer);
+        }
+
+        let descriptions = input.take(4 * size)?;
+        if run_flags.is_none() || size >= NO_OFFSET_THRESHOLD {
+            input.take(4 * size)?;
+        }
+        let mut bitmap = CompressedBitmap::new();
+        for (i, description) in descriptions.chunks_exact(4).enumerate() {
+            let key = u16::from_le_bytes([description[0], description[1]]);
+            let len = u16::from_le_bytes([description[2], description[3]]) as u32 + 1;
+            if bitmap.keys.last().map_or(false, |&last| last >= key) {
+                return Err(RoaringError::InvalidContainer);
+            }
+            let is_run = run_flags.map_or(false, |flags| flags[i / 8] & (1 << (i % 8)) != 0);
+            let container = if is_run {
+                read_run(&mut input)?
+            } else if len <= ARRAY_MAX {
+                read_array(&mut input, len)?
+            } else {
+                read_bitmap(&mut input)?
+            };
+            if container.len() != len {
+                return Err(RoaringError::InvalidContainer);
+            }
+            bitmap.keys.push(key);
+            bitmap.containers.push(container);
+        }
+        Ok(bitmap)
+    }
+
+    fn has_run_containers(&self) -> bool {
+        self.containers
+            .iter()
+            .any(|container| matches!(container, Container::Run(_)))
+    }
+}
+
+/// Return the serialized length of a container's values in bytes.
+fn container_size(container: &Container) -> usize {
+    match container {
+        Container::Array(values) => 2 * values.len(),
+        Container::Bitmap { .. } => 8 * BITMAP_WORDS,
+        Container::Run(runs) => 2 + 4 * runs.len(),
+    }
+}
+
+/// Read a run container, merging runs that touch.
+fn read_run(input: &mut Input<'_>) -> Result<Container, RoaringError> {
+    let count = input.read_u16()?;
+    let mut runs: Vec<(u16, u16)> = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let start = input.read_u16()?;
+        let last = start
+            .checked_add(input.read_u16()?)
+            .ok_or(RoaringError::InvalidContainer)?;
+        match runs.last_mut() {
+            Some(previous) if previous.1 >= start => return Err(RoaringError::InvalidContainer),
+            Some(previous) if previous.1 as u32 + 1 == start as u32 => previous.1 = last,
+            _ => runs.push((start, last)),
+        }
+    }
+    Ok(Container::Run(runs))
+}
+
+/// Read an array container of `len` strictly increasing values.
+fn read_array(input: &mut Input<'_>, len: u32) -> Result<Container, RoaringError> {
+    let bytes = input.take(2 * len as usize)?;
+    let values: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    if values.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(RoaringError::InvalidContainer);
+    }
+    Ok(Container::Array(values))
+}
+
+/// Read a bitmap container of 65536 bits.
+fn read_bitmap(input: &mut Input<'_>) -> Result<Container, RoaringError> {
+    let bytes = input.take(8 * BITMAP_WORDS)?;
+    let mut words = Box::new([0_u64; BITMAP_WORDS]);
+    let mut len = 0;
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(8)) {
+        let mut le_bytes = [0; 8];
+        le_bytes.copy_from_slice(chunk);
+        *word = u64::from_le_bytes(le_bytes);
+        len += word.count_ones();
+    }
+    Ok(Container::Bitmap { words, len })
+}
+
+/// The unread part of a serialized bitmap.
+struct Input<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Input<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], RoaringError> {
+        if len > self.bytes.len() {
+            return Err(RoaringError::UnexpectedEnd);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, RoaringError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, RoaringError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The fixtures were written by the `roaring` crate (0.11.5) with `serialize_into`, from
+    // the values built by the functions below; `_with_runs` and `runs_` fixtures were
+    // `optimize`d first.
+    const EMPTY: &[u8] = include_bytes!("../../tests/fixtures/roaring/empty.bin");
+    const SPARSE: &[u8] = include_bytes!("../../tests/fixtures/roaring/sparse.bin");
+    const MIXED: &[u8] = include_bytes!("../../tests/fixtures/roaring/mixed.bin");
+    const MIXED_WITH_RUNS: &[u8] =
+        include_bytes!("../../tests/fixtures/roaring/mixed_with_runs.bin");
+    const RUNS_WITHOUT_OFFSETS: &[u8] =
+        include_bytes!("../../tests/fixtures/roaring/runs_without_offsets.bin");
+
+    fn sparse() -> Vec<u32> {
+        vec![0, 1, 2, 1000, 65535, 65536, 1 << 20, u32::MAX]
+    }
+
+    fn mixed() -> Vec<u32> {
+        (0..100_000)
+            .filter(|v| v % 3 == 0)
+            .chain(200_000..200_100)
+            .chain((0..500).map(|v| v * 1009))
+            .collect()
+    }
+
+    fn mixed_with_runs() -> Vec<u32> {
+        let mut values = mixed();
+        values.extend(1 << 24..(1 << 24) + 100_000);
+        values
+    }
+
+    fn runs_without_offsets() -> Vec<u32> {
+        (10..20).chain(1000..3000).chain(70_000..70_001).collect()
+    }
+
+    /// Build a bitmap by insertion only, which never creates run containers.
+    fn inserted(values: &[u32]) -> CompressedBitmap {
+        let mut bitmap = CompressedBitmap::new();
+        bitmap.extend(values.iter().copied());
+        bitmap
+    }
+
+    #[test]
+    fn test_fixtures_deserialize_to_expected_values() {
+        let cases: [(&[u8], Vec<u32>); 5] = [
+            (EMPTY, vec![]),
+            (SPARSE, sparse()),
+            (MIXED, mixed()),
+            (MIXED_WITH_RUNS, mixed_with_runs()),
+            (RUNS_WITHOUT_OFFSETS, runs_without_offsets()),
+        ];
+        for (bytes, values) in cases.iter() {
+            let bitmap = CompressedBitmap::deserialize_roaring(bytes).unwrap();
+            assert_eq!(bitmap, inserted(values));
+            assert_eq!(bitmap.serialized_size(), bytes.len());
+            assert_eq!(&bitmap.serialize_roaring(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_serialize_matches_fixtures() {
+        assert_eq!(CompressedBitmap::new().serialize_roaring(), EMPTY);
+        assert_eq!(inserted(&sparse()).serialize_roaring(), SPARSE);
+        assert_eq!(inserted(&mixed()).serialize_roaring(), MIXED);
+        let optimized: CompressedBitmap = mixed_with_runs().into_iter().collect();
+        assert_eq!(optimized.serialize_roaring(), MIXED_WITH_RUNS);
+        let optimized: CompressedBitmap = runs_without_offsets().into_iter().collect();
+        assert_eq!(optimized.serialize_roaring(), RUNS_WITHOUT_OFFSETS);
+    }
+
+    #[test]
+    fn test_deserialize_ignores_trailing_bytes() {
+        let mut bytes = SPARSE.to_vec();
+        bytes.extend_from_slice(&[0xff; 3]);
+        let bitmap = CompressedBitmap::deserialize_roaring(&bytes).unwrap();
+        assert_eq!(bitmap, inserted(&sparse()));
+    }
+
+    #[test]
+    fn test_deserialize_rejects_truncated_input() {
+        for bytes in [SPARSE, MIXED_WITH_RUNS, RUNS_WITHOUT_OFFSETS].iter() {
+            for len in 0..bytes.len() {
+                assert_eq!(
+                    CompressedBitmap::deserialize_roaring(&bytes[..len]),
+                    Err(RoaringError::UnexpectedEnd),
+                    "length {}",
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_deserialize_rejects_malformed_input() {
+        let mut bytes = SPARSE.to_vec();
+        bytes[1] ^= 0x80;
+        assert_eq!(
+            CompressedBitmap::deserialize_roaring(&bytes),
+            Err(RoaringError::InvalidCookie)
+        );
+        // One container with the values 5 and 1, out of order.
+        let unsorted = [
+            0x3a, 0x30, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 16, 0, 0, 0, 5, 0, 1, 0,
+        ];
+        assert_eq!(
+            CompressedBitmap::deserialize_roaring(&unsorted),
+            Err(RoaringError::InvalidContainer)
+        );
+        // One run container declaring 3 values but holding the 2 values of run 7..=8.
+        let short_run = [0x3b, 0x30, 0, 0, 1, 0, 0, 2, 0, 1, 0, 7, 0, 1, 0];
+        assert_eq!(
+            CompressedBitmap::deserialize_roaring(&short_run),
+            Err(RoaringError::InvalidContainer)
+        );
+        // Two overlapping runs.
+        let overlapping = [
+            0x3b, 0x30, 0, 0, 1, 0, 0, 2, 0, 2, 0, 7, 0, 1, 0, 8, 0, 0, 0,
+        ];
+        assert_eq!(
+            CompressedBitmap::deserialize_roaring(&overlapping),
+            Err(RoaringError::InvalidContainer)
+        );
+        // Touching runs 7..=8 and 9..=9 are merged into one.
+        let touching = [
+            0x3b, 0x30, 0, 0, 1, 0, 0, 2, 0, 2, 0, 7, 0, 1, 0, 9, 0, 0, 0,
+        ];
+        let bitmap = CompressedBitmap::deserialize_roaring(&touching).unwrap();
+        assert!(matches!(&bitmap.containers[0], Container::Run(runs) if runs == &[(7, 9)]));
+    }
+
+    #[test]
+    fn test_round_trip_after_set_operations() {
+        let a: CompressedBitmap = mixed_with_runs().into_iter().collect();
+        let b: CompressedBitmap = (50_000..300_000).step_by(7).collect();
+        for bitmap in [a.union(&b), a.intersection(&b), a.symmetric_difference(&b)].iter() {
+            let bytes = bitmap.serialize_roaring();
+            assert_eq!(bytes.len(), bitmap.serialized_size());
+            assert_eq!(
+                CompressedBitmap::deserialize_roaring(&bytes).as_ref(),
+                Ok(bitmap)
+            );
+        }
+    }
+}
@@ -0,0 +1,131 @@
+/// The parameters of a CRC in the Rocksoft model.
+///
+/// The CRC of a message starts from a `width`-bit register holding `init`. Each input byte,
+/// bit-reversed first if `reflect_in` is set, is shifted into the register most significant
+/// bit first, and the register is reduced modulo `x^width + poly` after every bit. At the
+/// end, the register is bit-reversed if `reflect_out` is set, then XORed with `xor_out`.
+///
+/// `poly`, `init`, `xor_out` and `check` are `width`-bit values in the low bits of `W`, as
+/// the CRC RevEng catalogue writes them.
+///
+/// # Examples
+/// ```
+/// use bittersweet::crc::{BitwiseCrc, CrcAlgorithm};
+/// // CRC-5/USB
+/// let crc5 = CrcAlgorithm {
+///     width: 5,
+///     poly: 0x05_u8,
+///     init: 0x1f,
+///     reflect_in: true,
+///     reflect_out: true,
+///     xor_out: 0x1f,
+///     check: 0x19,
+/// };
+/// assert_eq!(BitwiseCrc::new(crc5).checksum(b"123456789"), crc5.check);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CrcAlgorithm<W> {
+    /// The number of bits of the CRC, from 1 to the width of `W`.
+    pub width: usize,
+    /// The generator polynomial without its `x^width` term, most significant bit for
+    /// `x^(width - 1)`.
+    pub poly: W,
+    /// The register value before the first byte.
+    pub init: W,
+    /// Whether each input byte is bit-reversed, least significant bit first.
+    pub reflect_in: bool,
+    /// Whether the final register is bit-reversed.
+    pub reflect_out: bool,
+    /// The value XORed into the final register.
+    pub xor_out: W,
+    /// The CRC of the ASCII bytes `123456789`.
+    pub check: W,
+}
+
+/// CRC-8/SMBUS, often called just CRC-8.
+pub const CRC_8_SMBUS: CrcAlgorithm<u8> = CrcAlgorithm {
+    width: 8,
+    poly: 0x07,
+    init: 0x00,
+    reflect_in: false,
+    reflect_out: false,
+    xor_out: 0x00,
+    check: 0xf4,
+};
+
+/// CRC-16/IBM-3740, also known as CRC-16/CCITT-FALSE and CRC-16/AUTOSAR.
+pub const CRC_16_IBM_3740: CrcAlgorithm<u16> = CrcAlgorithm {
+    width: 16,
+    poly: 0x1021,
+    init: 0xffff,
+    reflect_in: false,
+    reflect_out: false,
+    xor_out: 0x0000,
+    check: 0x29b1,
+};
+
+/// CRC-16/KERMIT, also known as CRC-16/CCITT and CRC-16/CCITT-TRUE.
+pub const CRC_16_KERMIT: CrcAlgorithm<u16> = CrcAlgorithm {
+    width: 16,
+    poly: 0x1021,
+    init: 0x0000,
+    reflect_in: true,
+    reflect_out: true,
+    xor_out: 0x0000,
+    check: 0x2189,
+};
+
+/// CRC-16/XMODEM, also known as CRC-16/ACORN and CRC-16/LTE.
+pub const CRC_16_XMODEM: CrcAlgorithm<u16> = CrcAlgorithm {
+    width: 16,
+    poly: 0x1021,
+    init: 0x0000,
+    reflect_in: false,
+    reflect_out: false,
+    xor_out: 0x0000,
+    check: 0x31c3,
+};
+
+/// CRC-32/ISO-HDLC, the CRC-32 of Ethernet, zlib and PNG.
+pub const CRC_32_ISO_HDLC: CrcAlgorithm<u32> = CrcAlgorithm {
+    width: 32,
+    poly: 0x04c1_1db7,
+    init: 0xffff_ffff,
+    reflect_in: true,
+    reflect_out: true,
+    xor_out: 0xffff_ffff,
+    check: 0xcbf4_3926,
+};
+
+/// CRC-32/ISCSI, also known as CRC-32C (Castagnoli), the CRC-32 of iSCSI, ext4 and SSE 4.2.
+pub const CRC_32_ISCSI: CrcAlgorithm<u32> = CrcAlgorithm {
+    width: 32,
+    poly: 0x1edc_6f41,
+    init: 0xffff_ffff,
+    reflect_in: true,
+    reflect_out: true,
+    xor_out: 0xffff_ffff,
+    check: 0xe306_9283,
+};
+
+/// CRC-64/ECMA-182, the unreflected CRC-64 of ECMA-182.
+pub const CRC_64_ECMA_182: CrcAlgorithm<u64> = CrcAlgorithm {
+    width: 64,
+    poly: 0x42f0_e1eb_a9ea_3693,
+    init: 0x0000_0000_0000_0000,
+    reflect_in: false,
+    reflect_out: false,
+    xor_out: 0x0000_0000_0000_0000,
+    check: 0x6c40_df5f_0b49_7347,
+};
+
+/// CRC-64/XZ, the reflected ECMA-182 CRC-64 of xz and Go's `crc64.ECMA`.
+pub const CRC_64_XZ: CrcAlgorithm<u64> = CrcAlgorithm {
+    width: 64,
+    poly: 0x42f0_e1eb_a9ea_3693,
+    init: 0xffff_ffff_ffff_ffff,
+    reflect_in: true,
+    reflect_out: true,
+    xor_out: 0xffff_ffff_ffff_ffff,
+    check: 0x995d_c9bb_df19_39fa,
+};
@@ -0,0 +1,480 @@
+use core::fmt;
+
+use super::{CrcAlgorithm, CrcWord};
+
+/// A calculator for one [`CrcAlgorithm`], with `TABLES` lookup tables of 256 words.
+///
+/// `TABLES` selects the mode: 0 computes bit by bit, 1 byte by byte through one table, and 8
+/// eight bytes at a time through eight tables (slice-by-8). The aliases [`BitwiseCrc`],
+/// [`TableCrc`] and [`Slice8Crc`] name the three modes.
+///
+/// # Examples
+/// ```
+/// use bittersweet::crc::{BitwiseCrc, Slice8Crc, CRC_64_XZ};
+/// let message = b"The quick brown fox jumps over the lazy dog";
+/// let fast = Slice8Crc::new(CRC_64_XZ);
+/// let small = BitwiseCrc::new(CRC_64_XZ);
+/// assert_eq!(fast.checksum(message), small.checksum(message));
+/// assert_eq!(fast.checksum(b"123456789"), CRC_64_XZ.check);
+/// ```
+#[derive(Clone)]
+pub struct Crc<W, const TABLES: usize> {
+    algorithm: CrcAlgorithm<W>,
+    /// The polynomial in register order: bit-reversed into the low `width` bits for a
+    /// reflected algorithm, and shifted to the top of the word otherwise.
+    poly: W,
+    /// The initial register value, in the same order as `poly`.
+    init: W,
+    /// `tables[k][b]` is the register after byte `b` and `k` zero bytes, from a zero
+    /// register.
+    tables: [[W; 256]; TABLES],
+}
+
+/// A CRC calculator without tables, which shifts the register once per bit.
+pub type BitwiseCrc<W> = Crc<W, 0>;
+
+/// A CRC calculator with one 256-entry table, which looks up one byte at a time.
+pub type TableCrc<W> = Crc<W, 1>;
+
+/// A CRC calculator with eight 256-entry tables, which looks up eight bytes at a time.
+pub type Slice8Crc<W> = Crc<W, 8>;
+
+impl<W: CrcWord, const TABLES: usize> Crc<W, TABLES> {
+    /// Return a calculator for `algorithm`, building its tables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `TABLES` is not 0, 1 or 8, or if the width of `algorithm` is 0 or larger
+    /// than the word.
+    pub fn new(algorithm: CrcAlgorithm<W>) -> Self {
+        assert!(
+            TABLES == 0 || TABLES == 1 || TABLES == 8,
+            "a CRC calculator has 0, 1 or 8 tables"
+        );
+        assert!(
+            algorithm.width >= 1 && algorithm.width <= W::length(),
+            "the CRC width does not fit in the word"
+        );
+        let shift = (W::length() - algorithm.width) as u32;
+        let (poly, init) = if algorithm.reflect_in {
+            (
+                reflect(algorithm.poly, algorithm.width),
+                reflect(algorithm.init, algorithm.width),
+            )
+        } else {
+            (algorithm.poly << shift, algorithm.init << shift)
+        };
+        let mut crc = Crc {
+            algorithm,
+            poly,
+            init,
+            tables: [[W::ZERO; 256]; TABLES],
+        };
+        for k in 0..TABLES {
+            for byte in 0..=255 {
+                let entry = if k == 0 {
+                    crc.update_bitwise(W::ZERO, &[byte])
+                } else {
+                    crc.update_table(crc.tables[k - 1][usize::from(byte)], &[0])
+                };
+                crc.tables[k][usize::from(byte)] = entry;
+            }
+        }
+        crc
+    }
+
+    /// Return the algorithm of the calculator.
+    #[inline]
+    pub fn algorithm(&self) -> &CrcAlgorithm<W> {
+        &self.algorithm
+    }
+
+    /// Return the CRC of `bytes`.
+    #[inline]
+    pub fn checksum(&self, bytes: &[u8]) -> W {
+        self.finalize(self.update(self.init, bytes))
+    }
+
+    /// Return a digest for computing a CRC over several slices.
+    #[inline]
+    pub fn digest(&self) -> CrcDigest<'_, W, TABLES> {
+        CrcDigest {
+            crc: self,
+            state: self.init,
+        }
+    }
+
+    /// Feed `bytes` into the register `state`.
+    #[inline]
+    fn update(&self, state: W, bytes: &[u8]) -> W {
+        match TABLES {
+            0 => self.update_bitwise(state, bytes),
+            1 => self.update_table(state, bytes),
+            _ => self.update_slice8(state, bytes),
+        }
+    }
+
+    fn update_bitwise(&self, state: W, bytes: &[u8]) -> W {
+        let mut state = state;
+        if self.algorithm.reflect_in {
+            for &byte in bytes {
+                state = state ^ W::from_byte(byte);
+                for _ in 0..8 {
+                    let carry = state & W::ONE != W::ZERO;
+                    state = state >> 1;
+                    if carry {
+                        state = state ^ self.poly;
+                    }
+                }
+            }
+        } else {
+            let top = W::ONE << (W::length() - 1) as u32;
+            for &byte in bytes {
+                state = state ^ (W::from_byte(byte) << (W::length() - 8) as u32);
+                for _ in 0..8 {
+                    let carry = state & top != W::ZERO;
+                    state = state << 1;
+                    if carry {
+                        state = state ^ self.poly;
+                    }
+                }
+            }
+        }
+        state
+    }
+
+    fn update_table(&self, state: W, bytes: &[u8]) -> W {
+        let table = &self.tables[0];
+        let mut state = state;
+        if self.algorithm.reflect_in {
+            for &byte in bytes {
+                let index = usize::from(state.low_byte() ^ byte);
+                state = shift_out_low_byte(state) ^ table[index];
+            }
+        } else {
+            for &byte in bytes {
+                let index = usize::from(register_byte(state, 0, false) ^ byte);
+                state = shift_out_high_byte(state) ^ table[index];
+            }
+        }
+        state
+    }
+
+    fn update_slice8(&self, state: W, bytes: &[u8]) -> W {
+        let reflected = self.algorithm.reflect_in;
+        let register_bytes = W::length() / 8;
+        let mut state = state;
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            // The register is at most eight bytes, so all of it is shifted out by the chunk.
+            let mut next = W::ZERO;
+            for (i, &byte) in chunk.iter().enumerate() {
+                let index = if i < register_bytes {
+                    byte ^ register_byte(state, i, reflected)
+                } else {
+                    byte
+                };
+                next = next ^ self.tables[7 - i][usize::from(index)];
+            }
+            state = next;
+        }
+        self.update_table(state, chunks.remainder())
+    }
+
+    /// Turn the register `state` into the CRC.
+    fn finalize(&self, state: W) -> W {
+        let algorithm = &self.algorithm;
+        let register = if algorithm.reflect_in {
+            reflect(state, algorithm.width)
+        } else {
+            state >> (W::length() - algorithm.width) as u32
+        };
+        let output = if algorithm.reflect_out {
+            reflect(register, algorithm.width)
+        } else {
+            register
+        };
+        output ^ algorithm.xor_out
+    }
+}
+
+impl<W: fmt::Debug, const TABLES: usize> fmt::Debug for Crc<W, TABLES> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Crc")
+            .field("algorithm", &self.algorithm)
+            .field("tables", &TABLES)
+            .finish()
+    }
+}
+
+/// A CRC computation over several slices, returned by [`Crc::digest`].
+///
+/// # Examples
+/// ```
+/// use bittersweet::crc::{TableCrc, CRC_16_IBM_3740};
+/// let crc = TableCrc::new(CRC_16_IBM_3740);
+/// let mut digest = crc.digest();
+/// for packet in [&b"1234"[..], b"", b"56789"] {
+///     digest.update(packet);
+/// }
+/// assert_eq!(digest.finalize(), 0x29b1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CrcDigest<'a, W, const TABLES: usize> {
+    crc: &'a Crc<W, TABLES>,
+    state: W,
+}
+
+impl<'a, W: CrcWord, const TABLES: usize> CrcDigest<'a, W, TABLES> {
+    /// Feed `bytes` into the CRC.
+    #[inline]
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.state = self.crc.update(self.state, bytes);
+    }
+
+    /// Return the CRC of the bytes fed so far. More bytes can still be fed afterwards.
+    #[inline]
+    pub fn finalize(&self) -> W {
+        self.crc.finalize(self.state)
+    }
+}
+
+/// Return the low `width` bits of `value` in reverse order.
+#[inline]
+fn reflect<W: CrcWord>(value: W, width: usize) -> W {
+    value.bin_to_bit_reversal_permutation() >> (W::length() - width) as u32
+}
+
+/// Return byte `i` of the register: counted from the least significant byte for a reflected
+/// register, and from the most significant byte otherwise.
+#[inline]
+fn register_byte<W: CrcWord>(state: W, i: usize, reflected: bool) -> u8 {
+    let shift = if reflected {
+        8 * i
+    } else {
+        W::length() - 8 - 8 * i
+    };
+    (state >> shift as u32).low_byte()
+}
+
+/// Shift the register right by one byte.
+#[inline]
+fn shift_out_low_byte<W: CrcWord>(state: W) -> W {
+    if W::length() == 8 {
+        W::ZERO
+    } else {
+        state >> 8
+    }
+}
+
+/// Shift the register left by one byte.
+#[inline]
+fn shift_out_high_byte<W: CrcWord>(state: W) -> W {
+    if W::length() == 8 {
+        W::ZERO
+    } else {
+        state << 8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+    use crate::test_util::xorshift64;
+
+    /// The CRC of `bytes` following the Rocksoft model literally, one bit at a time.
+    fn reference_crc(
+        width: usize,
+        poly: u64,
+        init: u64,
+        reflect_in: bool,
+        reflect_out: bool,
+        xor_out: u64,
+        bytes: &[u8],
+    ) -> u64 {
+        let mask = u64::MAX >> (64 - width);
+        let reverse = |value: u64, bits: usize| value.reverse_bits() >> (64 - bits);
+        let mut register = init;
+        for &byte in bytes {
+            let byte = if reflect_in {
+                byte.reverse_bits()
+            } else {
+                byte
+            };
+            for k in (0..8).rev() {
+                let feedback = (register >> (width - 1) & 1) ^ u64::from(byte >> k & 1);
+                register = register << 1 & mask;
+                if feedback == 1 {
+                    register ^= poly;
+                }
+            }
+        }
+        let output = if reflect_out {
+            reverse(register, width)
+        } else {
+            register
+        };
+        output ^ xor_out
+    }
+
+    macro_rules! assert_catalog_check {
+        ($($algorithm:expr),*) => {
+            $(
+                let algorithm = $algorithm;
+                assert_eq!(BitwiseCrc::new(algorithm).checksum(b"123456789"), algorithm.check);
+                assert_eq!(TableCrc::new(algorithm).checksum(b"123456789"), algorithm.check);
+                assert_eq!(Slice8Crc::new(algorithm).checksum(b"123456789"), algorithm.check);
+            )*
+        };
+    }
+
+    #[test]
+    fn test_catalog_check_values() {
+        assert_catalog_check!(
+            CRC_8_SMBUS,
+            CRC_16_IBM_3740,
+            CRC_16_KERMIT,
+            CRC_16_XMODEM,
+            CRC_32_ISO_HDLC,
+            CRC_32_ISCSI,
+            CRC_64_ECMA_182,
+            CRC_64_XZ
+        );
+    }
+
+    fn algorithm<W>(
+        width: usize,
+        poly: W,
+        init: W,
+        reflect_in: bool,
+        reflect_out: bool,
+        xor_out: W,
+        check: W,
+    ) -> CrcAlgorithm<W> {
+        CrcAlgorithm {
+            width,
+            poly,
+            init,
+            reflect_in,
+            reflect_out,
+            xor_out,
+            check,
+        }
+    }
+
+    #[test]
+    fn test_odd_width_check_values() {
+        // CRC-3/ROHC, CRC-5/USB and CRC-7/MMC
+        assert_catalog_check!(
+            algorithm(3, 0x3_u8, 0x7, true, true, 0x0, 0x6),
+            algorithm(5, 0x05_u8, 0x1f, true, true, 0x1f, 0x19),
+            algorithm(7, 0x09_u8, 0x00, false, false, 0x00, 0x75)
+        );
+        // CRC-12/UMTS reflects only its output; CRC-15/CAN and CRC-24/OPENPGP
+        assert_catalog_check!(
+            algorithm(12, 0x80f_u16, 0x000, false, true, 0x000, 0xdaf),
+            algorithm(15, 0x4599_u16, 0x0000, false, false, 0x0000, 0x059e),
+            algorithm(
+                24,
+                0x86_4cfb_u32,
+                0xb7_04ce,
+                false,
+                false,
+                0x00_0000,
+                0x21_cf02
+            )
+        );
+        // CRC-40/GSM
+        assert_catalog_check!(algorithm(
+            40,
+            0x00_0482_0009_u64,
+            0x00_0000_0000,
+            false,
+            false,
+            0xff_ffff_ffff,
+            0xd4_164f_c646
+        ));
+    }
+
+    macro_rules! assert_modes_match_reference {
+        ($T:ty) => {
+            let mut state = 0x9e3779b97f4a7c15;
+            let data: Vec<u8> = (0..40).map(|_| xorshift64(&mut state) as u8).collect();
+            for width in 1..=<$T>::BITS as usize {
+                for &(reflect_in, reflect_out) in
+                    &[(false, false), (false, true), (true, false), (true, true)]
+                {
+                    let mask = u64::MAX >> (64 - width);
+                    let poly = xorshift64(&mut state) & mask;
+                    let init = xorshift64(&mut state) & mask;
+                    let xor_out = xorshift64(&mut state) & mask;
+                    let algorithm = CrcAlgorithm {
+                        width,
+                        poly: poly as $T,
+                        init: init as $T,
+                        reflect_in,
+                        reflect_out,
+                        xor_out: xor_out as $T,
+                        check: 0,
+                    };
+                    let bitwise = BitwiseCrc::new(algorithm);
+                    let table = TableCrc::new(algorithm);
+                    let slice8 = Slice8Crc::new(algorithm);
+                    for len in [0, 1, 7, 8, 9, 16, 23, 40] {
+                        let bytes = &data[..len];
+                        let expected = reference_crc(
+                            width,
+                            poly,
+                            init,
+                            reflect_in,
+                            reflect_out,
+                            xor_out,
+                            bytes,
+                        ) as $T;
+                        assert_eq!(bitwise.checksum(bytes), expected);
+                        assert_eq!(table.checksum(bytes), expected);
+                        assert_eq!(slice8.checksum(bytes), expected);
+                    }
+                }
+            }
+        };
+    }
+
+    #[test]
+    fn test_modes_match_reference() {
+        assert_modes_match_reference!(u8);
+        assert_modes_match_reference!(u16);
+        assert_modes_match_reference!(u32);
+        assert_modes_match_reference!(u64);
+    }
+
+    #[test]
+    fn test_digest_matches_checksum() {
+        let crc = Slice8Crc::new(CRC_32_ISO_HDLC);
+        let data: Vec<u8> = (0..=255).collect();
+        for split in [0, 1, 5, 8, 13, 200, 256] {
+            let mut digest = crc.digest();
+            digest.update(&data[..split]);
+            digest.update(&data[split..]);
+            assert_eq!(digest.finalize(), crc.checksum(&data));
+        }
+        // The CRC of nothing is the initial value after the output transformations.
+        assert_eq!(crc.digest().finalize(), 0);
+        assert_eq!(TableCrc::new(CRC_16_IBM_3740).checksum(b""), 0xffff);
+    }
+
+    #[test]
+    #[should_panic(expected = "the CRC width does not fit in the word")]
+    fn test_new_panics_on_wide_algorithm() {
+        let mut algorithm = CRC_8_SMBUS;
+        algorithm.width = 9;
+        TableCrc::new(algorithm);
+    }
+
+    #[test]
+    #[should_panic(expected = "a CRC calculator has 0, 1 or 8 tables")]
+    fn test_new_panics_on_unsupported_tables() {
+        Crc::<u32, 4>::new(CRC_32_ISCSI);
+    }
+}
@@ -0,0 +1,68 @@
+//! Cyclic redundancy checks of any width up to 64 bits.
+//!
+//! A [`CrcAlgorithm`] describes a CRC by the parameters of the Rocksoft model: the width,
+//! the generator polynomial, the initial register value, whether input bytes and the output
+//! are reflected, and the value XORed into the output. The module provides the algorithms
+//! most firmware needs, under their names in the CRC RevEng catalogue:
+//!
+//! | Constant            | Also known as             | Check value          |
+//! |---------------------|---------------------------|----------------------|
+//! | [`CRC_8_SMBUS`]     | CRC-8                     | `0xf4`               |
+//! | [`CRC_16_IBM_3740`] | CRC-16/CCITT-FALSE        | `0x29b1`             |
+//! | [`CRC_16_KERMIT`]   | CRC-16/CCITT              | `0x2189`             |
+//! | [`CRC_16_XMODEM`]   | CRC-16/ACORN              | `0x31c3`             |
+//! | [`CRC_32_ISO_HDLC`] | CRC-32, the zlib CRC      | `0xcbf43926`         |
+//! | [`CRC_32_ISCSI`]    | CRC-32C (Castagnoli)      | `0xe3069283`         |
+//! | [`CRC_64_ECMA_182`] | CRC-64/ECMA               | `0x6c40df5f0b497347` |
+//! | [`CRC_64_XZ`]       | CRC-64/GO-ECMA            | `0x995dc9bbdf1939fa` |
+//!
+//! The check value is the CRC of the ASCII bytes `123456789`.
+//!
+//! A [`Crc`] computes one algorithm in one of three modes, chosen by its number of 256-entry
+//! lookup tables:
+//!
+//! - [`BitwiseCrc`] has no table and takes eight shifts per byte;
+//! - [`TableCrc`] has one table and takes one lookup per byte;
+//! - [`Slice8Crc`] has eight tables and takes eight independent lookups per eight bytes
+//!   (slice-by-8), which is the fastest on long inputs.
+//!
+//! All modes give the same results and work without `alloc`: the tables are arrays inside the
+//! [`Crc`], so a `Slice8Crc<u64>` takes 16 KiB. A reflected algorithm keeps its register in
+//! bit-reversed order, reflecting the polynomial, the initial value and the output with
+//! [`bin_to_bit_reversal_permutation`](crate::bitline::Bitline::bin_to_bit_reversal_permutation).
+//!
+//! # Example
+//!
+//! ```
+//! use bittersweet::crc::{Slice8Crc, TableCrc, CRC_32_ISCSI, CRC_32_ISO_HDLC};
+//! let crc32 = TableCrc::new(CRC_32_ISO_HDLC);
+//! assert_eq!(crc32.checksum(b"123456789"), 0xcbf43926);
+//! let crc32c = Slice8Crc::new(CRC_32_ISCSI);
+//! let mut digest = crc32c.digest();
+//! digest.update(b"12345");
+//! digest.update(b"6789");
+//! assert_eq!(digest.finalize(), 0xe3069283);
+//! ```
+
+mod algorithm;
+mod engine;
+// re-export
+pub use algorithm::{
+    CrcAlgorithm, CRC_16_IBM_3740, CRC_16_KERMIT, CRC_16_XMODEM, CRC_32_ISCSI, CRC_32_ISO_HDLC,
+    CRC_64_ECMA_182, CRC_64_XZ, CRC_8_SMBUS,
+};
+pub use engine::{BitwiseCrc, Crc, CrcDigest, Slice8Crc, TableCrc};
+
+use crate::bitline::BitlineWord;
+
+/// A bitline integer that can hold the register of a CRC.
+///
+/// This is implemented for `u8`, `u16`, `u32` and `u64`, and is the bound of [`Crc`]. A CRC
+/// of width `w` fits in any word of at least `w` bits. Slice-by-8 shifts the whole register
+/// out in one eight-byte chunk, so the word is at most 64 bits.
+pub trait CrcWord: BitlineWord {}
+
+impl CrcWord for u8 {}
+impl CrcWord for u16 {}
+impl CrcWord for u32 {}
+impl CrcWord for u64 {}
@@ -0,0 +1,551 @@
+//! Byte, hexadecimal and base64 conversions with MSB-first bit order.
+//!
+//! Bytes follow the bit order of [`Bitline::bit_repr`](crate::bitline::Bitline::bit_repr):
+//! position 0 is the most significant bit of byte 0, position 8 the most significant bit of
+//! byte 1, and so on. For bitlines this is the big-endian byte order of the integer, see
+//! [`Bitline::to_be_bit_bytes`](crate::bitline::Bitline::to_be_bit_bytes).
+//!
+//! Bit vectors are stored in `u64` words as in [`crate::rle`]: bit `i` is bit `63 - i % 64`
+//! of word `i / 64`. A vector of `len` bits takes [`bytes_length(len)`](bytes_length)
+//! bytes, and the bits of the last byte past `len` are zero.
+//!
+//! [`encode_hex`] / [`decode_hex`] (lowercase on output, either case on input) and
+//! [`encode_base64`] / [`decode_base64`] (the standard alphabet with `=` padding) work on
+//! caller-provided buffers and do not allocate. With the `alloc` feature, [`to_hex`],
+//! [`from_hex`], [`to_base64`] and [`from_base64`] convert bit vectors directly.
+//!
+//! # Example
+//!
+//! ```
+//! use bittersweet::encoding::{decode_base64, encode_base64, encode_hex, to_be_bit_bytes};
+//! // The 12 bits 1010 1111 0001, in one word.
+//! let words = [0xaf1 << 52];
+//! let mut bytes = [0; 2];
+//! to_be_bit_bytes(&words, 12, &mut bytes);
+//! assert_eq!(bytes, [0xaf, 0x10]);
+//!
+//! let mut hex = [0; 4];
+//! assert_eq!(encode_hex(&bytes, &mut hex), "af10");
+//! let mut base64 = [0; 4];
+//! assert_eq!(encode_base64(&bytes, &mut base64), "rxA=");
+//! let mut decoded = [0; 2];
+//! decode_base64("rxA=", &mut decoded).unwrap();
+//! assert_eq!(decoded, bytes);
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+/// An error from decoding hexadecimal or base64 text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// The text does not encode exactly the expected number of bytes.
+    InvalidLength,
+    /// The text contains a character outside its alphabet.
+    InvalidCharacter,
+    /// Base64 padding is misplaced, or the unused bits of the last character are not zero.
+    InvalidPadding,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength => f.write_str("encoded text has the wrong length"),
+            DecodeError::InvalidCharacter => f.write_str("invalid character in encoded text"),
+            DecodeError::InvalidPadding => f.write_str("invalid base64 padding"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Return the number of bytes holding `bits` bits.
+///
+/// # Examples
+/// ```
+/// use bittersweet::encoding::bytes_length;
+/// assert_eq!(bytes_length(0), 0);
+/// assert_eq!(bytes_length(12), 2);
+/// assert_eq!(bytes_length(64), 8);
+/// ```
+pub const fn bytes_length(bits: usize) -> usize {
+    (bits + 7) / 8
+}
+
+/// Return the number of hexadecimal digits encoding `bytes` bytes.
+pub const fn hex_length(bytes: usize) -> usize {
+    2 * bytes
+}
+
+/// Return the number of base64 characters, padding included, encoding `bytes` bytes.
+///
+/// # Examples
+/// ```
+/// use bittersweet::encoding::base64_length;
+/// assert_eq!(base64_length(0), 0);
+/// assert_eq!(base64_length(1), 4);
+/// assert_eq!(base64_length(3), 4);
+/// assert_eq!(base64_length(16), 24);
+/// ```
+pub const fn base64_length(bytes: usize) -> usize {
+    (bytes + 2) / 3 * 4
+}
+
+/// View ASCII output as a string.
+fn ascii_str(out: &[u8]) -> &str {
+    // Only characters of the ASCII alphabets above are written, so this cannot fail.
+    core::str::from_utf8(out).unwrap_or_default()
+}
+
+/// Write `bytes` as lowercase hexadecimal digits into `out` and return them as a string.
+///
+/// # Examples
+/// ```
+/// use bittersweet::encoding::encode_hex;
+/// let mut out = [0; 6];
+/// assert_eq!(encode_hex(&[0x01, 0xab, 0xff], &mut out), "01abff");
+/// ```
+///
+/// # Panics
+///
+/// Panics if `out.len()` is not [`hex_length(bytes.len())`](hex_length).
+pub fn encode_hex<'a>(bytes: &[u8], out: &'a mut [u8]) -> &'a str {
+    assert!(
+        out.len() == hex_length(bytes.len()),
+        "output length does not match the encoded length"
+    );
+    for (byte, digits) in bytes.iter().zip(out.chunks_exact_mut(2)) {
+        digits[0] = HEX_DIGITS[(byte >> 4) as usize];
+        digits[1] = HEX_DIGITS[(byte & 0xf) as usize];
+    }
+    ascii_str(out)
+}
+
+fn hex_value(digit: u8) -> Result<u8, DecodeError> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(DecodeError::InvalidCharacter),
+    }
+}
+
+/// Decode hexadecimal digits of either case into exactly `out.len()` bytes.
+///
+/// # Examples
+/// ```
+/// use bittersweet::encoding::{decode_hex, DecodeError};
+/// let mut out = [0; 2];
+/// decode_hex("aB0f", &mut out).unwrap();
+/// assert_eq!(out, [0xab, 0x0f]);
+/// assert_eq!(decode_hex("ab0", &mut out), Err(DecodeError::InvalidLength));
+/// assert_eq!(decode_hex("ab0g", &mut out), Err(DecodeError::InvalidCharacter));
+/// ```
+///
+/// # Errors
+///
+/// Returns [`DecodeError::InvalidLength`] unless `text` has
+/// [`hex_length(out.len())`](hex_length) characters, and
+/// [`DecodeError::InvalidCharacter`] for a character that is not a hexadecimal digit.
+/// `out` is unspecified after an error.
+pub fn decode_hex(text: &str, out: &mut [u8]) -> Result<(), DecodeError> {
+    if text.len() != hex_length(out.len()) {
+        return Err(DecodeError::InvalidLength);
+    }
+    for (byte, digits) in out.iter_mut().zip(text.as_bytes().chunks_exact(2)) {
+        *byte = hex_value(digits[0])? << 4 | hex_value(digits[1])?;
+    }
+    Ok(())
+}
+
+/// Write `bytes` as padded standard base64 into `out` and return it as a string.
+///
+/// # Examples
+/// ```
+/// use bittersweet::encoding::encode_base64;
+/// let mut out = [0; 8];
+/// assert_eq!(encode_base64(b"bits", &mut out), "Yml0cw==");
+/// ```
+///
+/// # Panics
+///
+/// Panics if `out.len()` is not [`base64_length(bytes.len())`](base64_length).
+pub fn encode_base64<'a>(bytes: &[u8], out: &'a mut [u8]) -> &'a str {
+    assert!(
+        out.len() == base64_length(bytes.len()),
+        "output length does not match the encoded length"
+    );
+    for (chunk, chars) in bytes.chunks(3).zip(out.chunks_exact_mut(4)) {
+        let mut group = [0; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = (group[0] as u32) << 16 | (group[1] as u32) << 8 | group[2] as u32;
+        for (i, char) in chars.iter_mut().enumerate() {
+            *char = if i <= chunk.len() {
+                BASE64_ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize]
+            } else {
+                b'='
+            };
+        }
+    }
+    ascii_str(out)
+}
+
+fn base64_value(char: u8) -> Result<u8, DecodeError> {
+    match char {
+        b'A'..=b'Z' => Ok(char - b'A'),
+        b'a'..=b'z' => Ok(char - b'a' + 26),
+        b'0'..=b'9' => Ok(char - b'0' + 52),
+        b'+' => Ok(62),
+        b'/' => Ok(63),
+        _ => Err(DecodeError::InvalidCharacter),
+    }
+}
+
+/// Decode padded standard base64 into exactly `out.len()` bytes.
+///
+/// Only the canonical encoding is accepted: padding must match `out.len()` and the unused
+/// bits of the last character must be zero.
+///
+/// # Examples
+/// ```
+/// use bittersweet::encoding::{decode_base64, DecodeError};
+/// let mut out = [0; 4];
+/// decode_base64("Yml0cw==", &mut out).unwrap();
+/// assert_eq!(&out, b"bits");
+/// assert_eq!(decode_base64("Yml0cx==", &mut out), Err(DecodeError::InvalidPadding));
+/// assert_eq!(decode_base64("Yml0cw", &mut out), Err(DecodeError::InvalidLength));
+/// ```
+///
+/// # Errors
+///
+/// Returns [`DecodeError::InvalidLength`] unless `text` has
+/// [`base64_length(out.len())`](base64_length) characters,
+/// [`DecodeError::InvalidCharacter`] for a character outside the alphabet, and
+/// [`DecodeError::InvalidPadding`] for misplaced padding or non-zero unused bits. `out` is
+/// unspecified after an error.
+pub fn decode_base64(text: &str, out: &mut [u8]) -> Result<(), DecodeError> {
+    if text.len() != base64_length(out.len()) {
+        return Err(DecodeError::InvalidLength);
+    }
+    for (chunk, chars) in out.chunks_mut(3).zip(text.as_bytes().chunks_exact(4)) {
+        let mut bits = 0_u32;
+        for (i, &char) in chars.iter().enumerate() {
+            let value = if i <= chunk.len() && char != b'=' {
+                base64_value(char)?
+            } else if i > chunk.len() && char == b'=' {
+                0
+            } else {
+                return Err(DecodeError::InvalidPadding);
+            };
+            bits = bits << 6 | value as u32;
+        }
+        // A short chunk leaves the low bits of its last character unused.
+        if bits & (0xff_ffff >> (8 * chunk.len())) != 0 {
+            return Err(DecodeError::InvalidPadding);
+        }
+        for (i, byte) in chunk.iter_mut().enumerate() {
+            *byte = (bits >> (16 - 8 * i)) as u8;
+        }
+    }
+    Ok(())
+}
+
+/// Write the first `len` bits of `words` into `bytes`, MSB first.
+///
+/// The bits of the last byte past `len` are zero.
+///
+/// # Examples
+/// ```
+/// use bittersweet::encoding::to_be_bit_bytes;
+/// let mut bytes = [0xff; 9];
+/// to_be_bit_bytes(&[u64::MAX, u64::MAX], 66, &mut bytes);
+/// assert_eq!(bytes, [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0b1100_0000]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `words.len()` is not `(len + 63) / 64` or if `bytes.len()` is not
+/// [`bytes_length(len)`](bytes_length).
+pub fn to_be_bit_bytes(words: &[u64], len: usize, bytes: &mut [u8]) {
+    assert!(
+        words.len() == (len + 63) / 64,
+        "word count does not match the bit length"
+    );
+    assert!(
+        bytes.len() == bytes_length(len),
+        "byte count does not match the bit length"
+    );
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = (words[i / 8] >> (56 - 8 * (i % 8))) as u8;
+    }
+    if len % 8 != 0 {
+        if let Some(last) = bytes.last_mut() {
+            *last &= 0xff << (8 - len % 8);
+        }
+    }
+}
+
+/// Read `len` bits from `bytes`, MSB first, into `words`.
+///
+/// The bits of the last byte past `len` are ignored, and the bits of the last word past
+/// `len` are cleared.
+///
+/// # Examples
+/// ```
+/// use bittersweet::encoding::from_be_bit_bytes;
+/// let mut words = [0; 1];
+/// from_be_bit_bytes(&[0xaf, 0x1f], 12, &mut words);
+/// assert_eq!(words, [0xaf1 << 52]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `bytes.len()` is not [`bytes_length(len)`](bytes_length) or if `words.len()`
+/// is not `(len + 63) / 64`.
+pub fn from_be_bit_bytes(bytes: &[u8], len: usize, words: &mut [u64]) {
+    assert!(
+        bytes.len() == bytes_length(len),
+        "byte count does not match the bit length"
+    );
+    assert!(
+        words.len() == (len + 63) / 64,
+        "word count does not match the bit length"
+    );
+    for word in words.iter_mut() {
+        *word = 0;
+    }
+    for (i, &byte) in bytes.iter().enumerate() {
+        words[i / 8] |= (byte as u64) << (56 - 8 * (i % 8));
+    }
+    if len % 64 != 0 {
+        if let Some(last) = words.last_mut() {
+            *last &= u64::MAX << (64 - len % 64);
+        }
+    }
+}
+
+/// Return the first `len` bits of `words` as lowercase hexadecimal, MSB first.
+///
+/// # Examples
+/// ```
+/// use bittersweet::encoding::to_hex;
+/// assert_eq!(to_hex(&[0xaf1 << 52], 12), "af10");
+/// ```
+///
+/// # Panics
+///
+/// Panics if `words.len()` is not `(len + 63) / 64`.
+#[cfg(feature = "alloc")]
+pub fn to_hex(words: &[u64], len: usize) -> String {
+    let mut bytes = vec![0; bytes_length(len)];
+    to_be_bit_bytes(words, len, &mut bytes);
+    let mut out = vec![0; hex_length(bytes.len())];
+    String::from(encode_hex(&bytes, &mut out))
+}
+
+/// Return the `len`-bit vector encoded by hexadecimal digits, in `u64` words.
+///
+/// # Examples
+/// ```
+/// use bittersweet::encoding::from_hex;
+/// assert_eq!(from_hex("af10", 12), Ok(vec![0xaf1 << 52]));
+/// ```
+///
+/// # Errors
+///
+/// Fails as [`decode_hex`] does for [`bytes_length(len)`](bytes_length) bytes.
+#[cfg(feature = "alloc")]
+pub fn from_hex(text: &str, len: usize) -> Result<Vec<u64>, DecodeError> {
+    let mut bytes = vec![0; bytes_length(len)];
+    decode_hex(text, &mut bytes)?;
+    let mut words = vec![0; (len + 63) / 64];
+    from_be_bit_bytes(&bytes, len, &mut words);
+    Ok(words)
+}
+
+/// Return the first `len` bits of `words` as padded standard base64, MSB first.
+///
+/// # Panics
+///
+/// Panics if `words.len()` is not `(len + 63) / 64`.
+#[cfg(feature = "alloc")]
+pub fn to_base64(words: &[u64], len: usize) -> String {
+    let mut bytes = vec![0; bytes_length(len)];
+    to_be_bit_bytes(words, len, &mut bytes);
+    let mut out = vec![0; base64_length(bytes.len())];
+    String::from(encode_base64(&bytes, &mut out))
+}
+
+/// Return the `len`-bit vector encoded by padded standard base64, in `u64` words.
+///
+/// # Errors
+///
+/// Fails as [`decode_base64`] does for [`bytes_length(len)`](bytes_length) bytes.
+#[cfg(feature = "alloc")]
+pub fn from_base64(text: &str, len: usize) -> Result<Vec<u64>, DecodeError> {
+    let mut bytes = vec![0; bytes_length(len)];
+    decode_base64(text, &mut bytes)?;
+    let mut words = vec![0; (len + 63) / 64];
+    from_be_bit_bytes(&bytes, len, &mut words);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::xorshift64;
+
+    fn pseudo_random_bytes(count: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| (xorshift64(&mut state) >> 32) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn test_base64_rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for &(plain, encoded) in vectors.iter() {
+            let mut out = vec![0; base64_length(plain.len())];
+            assert_eq!(encode_base64(plain.as_bytes(), &mut out), encoded);
+            let mut decoded = vec![0; plain.len()];
+            decode_base64(encoded, &mut decoded).unwrap();
+            assert_eq!(decoded, plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_hex_and_base64_round_trip() {
+        for len in 0..40 {
+            let bytes = pseudo_random_bytes(len, 0x9e3779b97f4a7c15 + len as u64);
+            let mut hex = vec![0; hex_length(len)];
+            let text = encode_hex(&bytes, &mut hex);
+            let expected: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            assert_eq!(text, expected);
+            let mut decoded = vec![0; len];
+            decode_hex(&text.to_uppercase(), &mut decoded).unwrap();
+            assert_eq!(decoded, bytes);
+
+            let mut base64 = vec![0; base64_length(len)];
+            let text = encode_base64(&bytes, &mut base64);
+            let mut decoded = vec![0; len];
+            decode_base64(text, &mut decoded).unwrap();
+            assert_eq!(decoded, bytes);
+        }
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut out = [0; 2];
+        assert_eq!(decode_hex("", &mut out), Err(DecodeError::InvalidLength));
+        assert_eq!(
+            decode_hex("0x12", &mut out),
+            Err(DecodeError::InvalidCharacter)
+        );
+        assert_eq!(
+            decode_hex("12é", &mut out),
+            Err(DecodeError::InvalidCharacter)
+        );
+        assert_eq!(
+            decode_base64("AAAA", &mut out),
+            Err(DecodeError::InvalidPadding)
+        );
+        assert_eq!(
+            decode_base64("AA=A", &mut out),
+            Err(DecodeError::InvalidPadding)
+        );
+        assert_eq!(
+            decode_base64("AA-=", &mut out),
+            Err(DecodeError::InvalidCharacter)
+        );
+        assert_eq!(
+            decode_base64("AAB=", &mut out),
+            Err(DecodeError::InvalidPadding)
+        );
+        assert_eq!(decode_base64("AAA=", &mut out), Ok(()));
+        let mut out = [0; 3];
+        assert_eq!(
+            decode_base64("AAAAAAAA", &mut out),
+            Err(DecodeError::InvalidLength)
+        );
+        assert_eq!(decode_base64("AA==", &mut out[..1]), Ok(()));
+        assert_eq!(
+            decode_base64("AAA=", &mut out),
+            Err(DecodeError::InvalidPadding)
+        );
+        assert_eq!(
+            DecodeError::InvalidPadding.to_string(),
+            "invalid base64 padding"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "output length does not match the encoded length")]
+    fn test_encode_base64_panics_on_wrong_output_length() {
+        encode_base64(&[1, 2], &mut [0; 3]);
+    }
+
+    #[test]
+    fn test_bit_vector_bytes_match_bits() {
+        for &len in [0, 1, 7, 8, 9, 63, 64, 65, 130].iter() {
+            let mut words: Vec<u64> = pseudo_random_bytes((len + 63) / 64 * 8, len as u64 + 1)
+                .chunks(8)
+                .map(|chunk| chunk.iter().fold(0, |word, &b| word << 8 | b as u64))
+                .collect();
+            let mut bytes = vec![0; bytes_length(len)];
+            to_be_bit_bytes(&words, len, &mut bytes);
+            for i in 0..bytes.len() * 8 {
+                let bit = i < len && words[i / 64] & (1 << (63 - i % 64)) != 0;
+                assert_eq!(
+                    bytes[i / 8] & (0x80 >> (i % 8)) != 0,
+                    bit,
+                    "bit {} of {}",
+                    i,
+                    len
+                );
+            }
+            // Reading back clears the padding bits of the last word.
+            if len % 64 != 0 {
+                *words.last_mut().unwrap() &= u64::MAX << (64 - len % 64);
+            }
+            let mut decoded = vec![u64::MAX; words.len()];
+            from_be_bit_bytes(&bytes, len, &mut decoded);
+            assert_eq!(decoded, words);
+            assert_eq!(from_hex(&to_hex(&words, len), len), Ok(words.clone()));
+            assert_eq!(from_base64(&to_base64(&words, len), len), Ok(words.clone()));
+        }
+    }
+
+    #[test]
+    fn test_from_be_bit_bytes_ignores_padding() {
+        let mut words = [0; 1];
+        from_be_bit_bytes(&[0xff], 3, &mut words);
+        assert_eq!(words, [0b111 << 61]);
+        assert_eq!(from_hex("ff", 3), Ok(vec![0b111 << 61]));
+    }
+
+    #[test]
+    #[should_panic(expected = "byte count does not match the bit length")]
+    fn test_to_be_bit_bytes_panics_on_wrong_byte_count() {
+        to_be_bit_bytes(&[0], 9, &mut [0; 1]);
+    }
+}
@@ -0,0 +1,359 @@
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+
+use super::poly::{is_prime, Polynomial};
+
+/// An element of the binary field `GF(2^n)`, where `n` is the width of `W`, reduced modulo
+/// `x^n + POLY`.
+///
+/// The element is a polynomial of degree less than `n` over GF(2), with bit `k` of `W`,
+/// counted from the least significant bit, as the coefficient of `x^k`. `POLY` holds the
+/// coefficients of the modulus below `x^n`, which is left implicit so that a `u128` element
+/// can have a degree-128 modulus; only its low `n` bits are used. The elements form a field
+/// when `x^n + POLY` is irreducible, which [`Gf2n::modulus_is_irreducible`] checks.
+///
+/// Addition and subtraction are XOR. Multiplication is a carry-less product followed by
+/// reduction, and division multiplies by the inverse, so it costs about `n` products.
+///
+/// # Examples
+/// ```
+/// use bittersweet::gf2::Gf2n8;
+/// // The example from FIPS 197, section 4.2.
+/// let a = Gf2n8::new(0x57);
+/// assert_eq!(a + Gf2n8::new(0x83), Gf2n8::new(0xd4));
+/// assert_eq!(a * Gf2n8::new(0x83), Gf2n8::new(0xc1));
+/// assert_eq!(a * a.inverse().unwrap_or(Gf2n8::ZERO), Gf2n8::ONE);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Gf2n<W, const POLY: u128>(pub W);
+
+/// `GF(2^8)` modulo `x^8 + x^4 + x^3 + x + 1`, the field of AES.
+pub type Gf2n8 = Gf2n<u8, 0x1b>;
+/// `GF(2^16)` modulo `x^16 + x^12 + x^3 + x + 1`.
+pub type Gf2n16 = Gf2n<u16, 0x100b>;
+/// `GF(2^64)` modulo `x^64 + x^4 + x^3 + x + 1`.
+pub type Gf2n64 = Gf2n<u64, 0x1b>;
+/// `GF(2^128)` modulo `x^128 + x^7 + x^2 + x + 1`, the field of GHASH.
+///
+/// GHASH stores the coefficient of `x^0` in the most significant bit of its blocks, so a
+/// GHASH block converts to and from this type with `u128::reverse_bits`.
+pub type Gf2n128 = Gf2n<u128, 0x87>;
+
+impl<W, const POLY: u128> Gf2n<W, POLY> {
+    /// Create an element from its polynomial.
+    #[inline]
+    pub const fn new(value: W) -> Self {
+        Gf2n(value)
+    }
+}
+
+impl<W: Polynomial, const POLY: u128> Gf2n<W, POLY> {
+    /// The additive identity.
+    pub const ZERO: Self = Gf2n(W::ZERO);
+    /// The multiplicative identity.
+    pub const ONE: Self = Gf2n(W::ONE);
+
+    /// Return the polynomial of the element.
+    #[inline]
+    pub fn value(self) -> W {
+        self.0
+    }
+
+    /// Return the low `n` coefficients of the modulus.
+    #[inline]
+    fn poly() -> W {
+        W::from_u128(POLY)
+    }
+
+    /// Return whether the element is zero.
+    #[inline]
+    pub fn is_zero(self) -> bool {
+        self.0 == W::ZERO
+    }
+
+    /// Return the square of the element.
+    #[inline]
+    pub fn square(self) -> Self {
+        self * self
+    }
+
+    /// Return the element raised to the power `exponent`.
+    ///
+    /// `0` to the power `0` is `1`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::gf2::Gf2n16;
+    /// let a = Gf2n16::new(0x1234);
+    /// assert_eq!(a.pow(3), a * a * a);
+    /// // The multiplicative group has 2^16 - 1 elements.
+    /// assert_eq!(a.pow(0xffff), Gf2n16::ONE);
+    /// ```
+    pub fn pow(self, exponent: u128) -> Self {
+        let mut result = Self::ONE;
+        let mut base = self;
+        let mut exponent = exponent;
+        while exponent != 0 {
+            if exponent & 1 == 1 {
+                result *= base;
+            }
+            base = base.square();
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// Return the multiplicative inverse of the element, or None for zero.
+    ///
+    /// This is `self^(2^n - 2)`, computed with `n - 1` squarings and as many products.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::gf2::Gf2n128;
+    /// let h = Gf2n128::new(0x66e9_4bd4_ef8a_2c3b_884c_fa59_ca34_2b2e);
+    /// assert_eq!(h.inverse().map(|inverse| inverse * h), Some(Gf2n128::ONE));
+    /// assert_eq!(Gf2n128::ZERO.inverse(), None);
+    /// ```
+    pub fn inverse(self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut result = Self::ONE;
+        let mut power = self;
+        for _ in 1..W::length() {
+            power = power.square();
+            result *= power;
+        }
+        Some(result)
+    }
+
+    /// Return whether the modulus `x^n + POLY` is irreducible, so that the elements form a
+    /// field.
+    ///
+    /// This is the irreducibility test of [`Polynomial::is_irreducible`], with the squarings
+    /// done in `Gf2n` since the modulus does not fit in `W`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::gf2::{Gf2n, Gf2n128, Gf2n8};
+    /// assert!(Gf2n8::modulus_is_irreducible());
+    /// assert!(Gf2n128::modulus_is_irreducible());
+    /// // x^8 + 1 = (x + 1)^8
+    /// assert!(!Gf2n::<u8, 1>::modulus_is_irreducible());
+    /// ```
+    pub fn modulus_is_irreducible() -> bool {
+        let degree = W::length();
+        let x = Self::new(W::ONE << 1);
+        let mut power = x;
+        for k in 1..=degree {
+            power = power.square();
+            if k < degree && degree % k == 0 && is_prime(degree / k) {
+                let difference = (power + x).0;
+                // `gcd(modulus, difference) = gcd(difference, modulus mod difference)`
+                if difference == W::ZERO {
+                    return false;
+                }
+                let leading = (W::ONE << (degree - 1) as u32).poly_mul_mod(W::ONE << 1, difference);
+                let remainder = leading ^ Self::poly().poly_mod(difference);
+                if difference.poly_gcd(remainder) != W::ONE {
+                    return false;
+                }
+            }
+        }
+        power == x
+    }
+}
+
+impl<W: Polynomial, const POLY: u128> Add for Gf2n<W, POLY> {
+    type Output = Self;
+    // Addition and subtraction over GF(2) are both XOR.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Gf2n(self.0 ^ rhs.0)
+    }
+}
+
+impl<W: Polynomial, const POLY: u128> Sub for Gf2n<W, POLY> {
+    type Output = Self;
+    // Addition and subtraction over GF(2) are both XOR.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Gf2n(self.0 ^ rhs.0)
+    }
+}
+
+impl<W: Polynomial, const POLY: u128> Mul for Gf2n<W, POLY> {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        let (mut low, mut high) = self.0.widening_clmul(rhs.0);
+        // `x^n = POLY`, and each round lowers the degree of `high` by at least one.
+        while high != W::ZERO {
+            let (folded_low, folded_high) = high.widening_clmul(Self::poly());
+            low = low ^ folded_low;
+            high = folded_high;
+        }
+        Gf2n(low)
+    }
+}
+
+impl<W: Polynomial, const POLY: u128> Div for Gf2n<W, POLY> {
+    type Output = Self;
+    /// # Panics
+    ///
+    /// Panics if `rhs` is zero.
+    #[inline]
+    fn div(self, rhs: Self) -> Self {
+        let inverse = rhs.inverse();
+        assert!(inverse.is_some(), "division by zero");
+        self * inverse.unwrap_or(Self::ZERO)
+    }
+}
+
+impl<W: Polynomial, const POLY: u128> AddAssign for Gf2n<W, POLY> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<W: Polynomial, const POLY: u128> SubAssign for Gf2n<W, POLY> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<W: Polynomial, const POLY: u128> MulAssign for Gf2n<W, POLY> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<W: Polynomial, const POLY: u128> DivAssign for Gf2n<W, POLY> {
+    #[inline]
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::xorshift64;
+
+    fn pseudo_random<W: Polynomial>(state: &mut u64) -> W {
+        let high = xorshift64(state);
+        let low = xorshift64(state);
+        W::from_u128(u128::from(high) << 64 | u128::from(low))
+    }
+
+    /// The product of `a` and `b` modulo `x^n + poly`, one coefficient of `b` at a time.
+    fn naive_mul<W: Polynomial>(a: W, b: W, poly: W) -> W {
+        let top = W::ONE << (W::length() - 1) as u32;
+        let mut product = W::ZERO;
+        for k in (0..W::length() as u32).rev() {
+            let carry = product & top != W::ZERO;
+            product = product << 1;
+            if carry {
+                product = product ^ poly;
+            }
+            if b & (W::ONE << k) != W::ZERO {
+                product = product ^ a;
+            }
+        }
+        product
+    }
+
+    macro_rules! assert_field_axioms {
+        ($F:ty, $W:ty) => {
+            assert!(<$F>::modulus_is_irreducible());
+            let mut state = 0x9e3779b97f4a7c15;
+            for _ in 0..64 {
+                let a = Gf2n(pseudo_random::<$W>(&mut state));
+                let b = Gf2n(pseudo_random::<$W>(&mut state));
+                let c = Gf2n(pseudo_random::<$W>(&mut state));
+                let product = a * b;
+                assert_eq!(product.0, naive_mul(a.0, b.0, <$F>::poly()));
+                assert_eq!(product, b * a);
+                assert_eq!((a * b) * c, a * (b * c));
+                assert_eq!(a * (b + c), a * b + a * c);
+                assert_eq!(a - b, a + b);
+                assert_eq!(a * <$F>::ONE, a);
+                assert_eq!(a * <$F>::ZERO, <$F>::ZERO);
+                if !b.is_zero() {
+                    assert_eq!(product / b, a);
+                    assert_eq!(b.inverse().map(|inverse| inverse * b), Some(<$F>::ONE));
+                    assert_eq!(b.pow(u128::MAX >> (128 - <$W>::BITS)), <$F>::ONE);
+                }
+                assert_eq!(a.pow(5), a * a * a * a * a);
+                assert_eq!(a.pow(0), <$F>::ONE);
+            }
+        };
+    }
+
+    #[test]
+    fn test_field_axioms() {
+        assert_field_axioms!(Gf2n8, u8);
+        assert_field_axioms!(Gf2n16, u16);
+        assert_field_axioms!(Gf2n<u32, 0x8d>, u32);
+        assert_field_axioms!(Gf2n64, u64);
+        assert_field_axioms!(Gf2n128, u128);
+    }
+
+    #[test]
+    fn test_mul_matches_poly_mul_mod() {
+        for a in 0..=255_u8 {
+            for b in 0..=255_u8 {
+                let expected = u16::from(a).poly_mul_mod(u16::from(b), 0x11b);
+                assert_eq!(u16::from((Gf2n8::new(a) * Gf2n8::new(b)).0), expected);
+            }
+        }
+    }
+
+    macro_rules! assert_modulus_matches_polynomial {
+        ($W:ty, $Wide:ty, $($poly:literal),*) => {
+            $(
+                let modulus = (1 as $Wide) << <$W>::BITS | $poly;
+                assert_eq!(
+                    Gf2n::<$W, $poly>::modulus_is_irreducible(),
+                    modulus.is_irreducible()
+                );
+            )*
+        };
+    }
+
+    #[test]
+    fn test_modulus_is_irreducible_matches_polynomial() {
+        assert_modulus_matches_polynomial!(
+            u8, u16, 0x00, 0x01, 0x02, 0x03, 0x11, 0x1b, 0x1d, 0x2b, 0x4d, 0x63, 0x87, 0xe7, 0xff
+        );
+        assert_modulus_matches_polynomial!(
+            u16, u32, 0x0000, 0x002b, 0x100b, 0x1021, 0x8005, 0x8bb7, 0xffff
+        );
+        assert_modulus_matches_polynomial!(u32, u64, 0x8d, 0x04c1_1db7, 0x1edc_6f41);
+        assert_modulus_matches_polynomial!(u64, u128, 0x1b, 0x42f0_e1eb_a9ea_3693);
+        assert!(!Gf2n::<u128, 1>::modulus_is_irreducible());
+    }
+
+    #[test]
+    fn test_aes_sbox() {
+        // SubBytes is the inverse followed by an affine map.
+        let sbox = |byte: u8| {
+            let b = Gf2n8::new(byte).inverse().unwrap_or(Gf2n8::ZERO).0;
+            b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63
+        };
+        assert_eq!(sbox(0x00), 0x63);
+        assert_eq!(sbox(0x01), 0x7c);
+        assert_eq!(sbox(0x53), 0xed);
+        assert_eq!(sbox(0xff), 0x16);
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn test_div_panics_on_zero() {
+        let _ = Gf2n8::ONE / Gf2n8::ZERO;
+    }
+}
@@ -0,0 +1,53 @@
+//! Polynomial and binary field arithmetic over GF(2) on bitline integers.
+//!
+//! A `u8` through `u128` value is read as a polynomial with coefficients in GF(2): bit `k`,
+//! counted from the least significant bit, is the coefficient of `x^k`. Addition and
+//! subtraction are both XOR, and multiplication is carry-less: the partial products are
+//! combined with XOR instead of addition.
+//!
+//! In the crate's MSB-first order the leading coefficient is the first set bit, so
+//! [`bit_repr`](crate::bitline::Bitline::bit_repr) prints the coefficients from the highest
+//! degree down and [`Polynomial::poly_degree`] is `length() - 1 - first_index()`.
+//!
+//! - [`clmul`] multiplies two `u64` polynomials into their full `u128` product;
+//! - [`Polynomial`] provides the widening product, division with remainder, greatest common
+//!   divisors, products modulo a polynomial and irreducibility tests at every width;
+//! - [`Gf2n`] is an element of the field `GF(2^n)` for an `n`-bit word and a reduction
+//!   polynomial, with the arithmetic operators, [`Gf2n::pow`] and [`Gf2n::inverse`].
+//!   [`Gf2n8`], [`Gf2n16`], [`Gf2n64`] and [`Gf2n128`] name common fields;
+//! - [`LogTables`] (with `alloc`) multiplies and divides in fields of up to `2^16` elements
+//!   with logarithm and antilogarithm tables.
+//!
+//! On `x86_64`, [`clmul`] uses the `pclmulqdq` instruction when the CPU supports it, and
+//! produces exactly the same result as the portable code. The instruction is detected at run
+//! time with the `std` feature and at compile time (`-C target-feature=+pclmulqdq`) without
+//! it.
+//!
+//! # Example
+//!
+//! ```
+//! use bittersweet::gf2::{clmul, Polynomial};
+//! // (x + 1)(x + 1) = x^2 + 1
+//! assert_eq!(clmul(0b11, 0b11), 0b101);
+//! // x^8 + x^4 + x^3 + x + 1, the AES polynomial, needs 9 bits.
+//! let aes = 0x11b_u16;
+//! assert_eq!(aes.poly_degree(), Some(8));
+//! assert!(aes.is_irreducible());
+//! assert_eq!(0x57_u16.poly_mul_mod(0x83, aes), 0xc1);
+//!
+//! // The same product in GF(2^8), where the modulus x^8 + 0x1b is implicit.
+//! use bittersweet::gf2::Gf2n8;
+//! assert_eq!(Gf2n8::new(0x57) * Gf2n8::new(0x83), Gf2n8::new(0xc1));
+//! ```
+
+mod field;
+mod poly;
+#[cfg(feature = "alloc")]
+mod tables;
+#[cfg(target_arch = "x86_64")]
+mod x86;
+// re-export
+pub use field::{Gf2n, Gf2n128, Gf2n16, Gf2n64, Gf2n8};
+pub use poly::{clmul, Polynomial};
+#[cfg(feature = "alloc")]
+pub use tables::LogTables;
@@ -0,0 +1,431 @@
+use crate::bitline::BitlineWord;
+
+/// Multiply `a` and `b` as polynomials over GF(2), returning the full 128-bit product.
+///
+/// On `x86_64` this is a single `pclmulqdq` instruction when the CPU supports it; see the
+/// [module-level documentation](super).
+///
+/// # Examples
+/// ```
+/// use bittersweet::gf2::clmul;
+/// // (x^2 + x)(x + 1) = x^3 + x
+/// assert_eq!(clmul(0b110, 0b11), 0b1010);
+/// assert_eq!(clmul(1 << 63, 1 << 63), 1 << 126);
+/// ```
+#[inline]
+pub fn clmul(a: u64, b: u64) -> u128 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if let Some(product) = super::x86::clmul(a, b) {
+            return product;
+        }
+    }
+    clmul_portable(a, b)
+}
+
+/// Multiply `a` and `b` as polynomials over GF(2), one set bit of `b` at a time.
+pub(crate) fn clmul_portable(a: u64, b: u64) -> u128 {
+    let a = u128::from(a);
+    let mut b = b;
+    let mut product = 0;
+    while b != 0 {
+        product ^= a << b.trailing_zeros();
+        b &= b - 1;
+    }
+    product
+}
+
+/// A bitline integer read as a polynomial over GF(2).
+///
+/// Bit `k`, counted from the least significant bit, is the coefficient of `x^k`. This is
+/// implemented for every bitline width, `u8` through `u128`; a polynomial of degree `n` needs
+/// a width of at least `n + 1` bits.
+pub trait Polynomial: BitlineWord {
+    /// Return the carry-less product of `self` and `other` as its low and high halves.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::gf2::Polynomial;
+    /// assert_eq!(0b1000_0011_u8.widening_clmul(0b110), (0b1010, 0b11));
+    /// assert_eq!((1_u128 << 127).widening_clmul(1 << 127), (0, 1 << 126));
+    /// ```
+    fn widening_clmul(self, other: Self) -> (Self, Self);
+
+    /// Return the degree of the polynomial, or None for the zero polynomial.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::gf2::Polynomial;
+    /// assert_eq!(0b0001_0110_u8.poly_degree(), Some(4));
+    /// assert_eq!(1_u8.poly_degree(), Some(0));
+    /// assert_eq!(0_u8.poly_degree(), None);
+    /// ```
+    #[inline]
+    fn poly_degree(self) -> Option<usize> {
+        self.first_index().map(|i| Self::length() - 1 - i)
+    }
+
+    /// Return the quotient and the remainder of the division of `self` by `divisor`.
+    ///
+    /// The remainder has a smaller degree than `divisor`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is the zero polynomial.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::gf2::Polynomial;
+    /// // x^4 + x + 1 = (x^2 + x)(x^2 + x + 1) + 1
+    /// assert_eq!(0b10011_u8.poly_divmod(0b111), (0b110, 0b1));
+    /// ```
+    fn poly_divmod(self, divisor: Self) -> (Self, Self) {
+        assert!(
+            divisor != Self::as_empty(),
+            "division by the zero polynomial"
+        );
+        let degree = divisor.poly_degree().unwrap_or(0);
+        let mut quotient = Self::as_empty();
+        let mut remainder = self;
+        while let Some(top) = remainder.poly_degree().filter(|&top| top >= degree) {
+            let shift = (top - degree) as u32;
+            remainder = remainder ^ (divisor << shift);
+            quotient = quotient | (Self::ONE << shift);
+        }
+        (quotient, remainder)
+    }
+
+    /// Return the remainder of the division of `self` by `divisor`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is the zero polynomial.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::gf2::Polynomial;
+    /// assert_eq!(0b10011_u8.poly_mod(0b111), 0b1);
+    /// // x + 1 divides every polynomial with an even number of terms.
+    /// assert_eq!(0b1001_0110_u8.poly_mod(0b11), 0);
+    /// ```
+    #[inline]
+    fn poly_mod(self, divisor: Self) -> Self {
+        self.poly_divmod(divisor).1
+    }
+
+    /// Return the greatest common divisor of `self` and `other`.
+    ///
+    /// Over GF(2) the greatest common divisor is monic without normalization. It is the zero
+    /// polynomial only when both `self` and `other` are.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::gf2::Polynomial;
+    /// // (x + 1)^2 (x^2 + x + 1) and (x + 1)(x^3 + x + 1)
+    /// assert_eq!(0b11011_u16.poly_gcd(0b11101), 0b11);
+    /// assert_eq!(0b111_u8.poly_gcd(0), 0b111);
+    /// ```
+    fn poly_gcd(self, other: Self) -> Self {
+        let (mut a, mut b) = (self, other);
+        while b != Self::as_empty() {
+            let remainder = a.poly_mod(b);
+            a = b;
+            b = remainder;
+        }
+        a
+    }
+
+    /// Return the product of `self` and `other` modulo `modulus`.
+    ///
+    /// The result has a smaller degree than `modulus`, so the product never overflows the
+    /// word. This takes one shift and at most two XORs per coefficient of `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is the zero polynomial.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::gf2::Polynomial;
+    /// // x^3 = x + 1 modulo x^3 + x + 1
+    /// assert_eq!(0b100_u8.poly_mul_mod(0b10, 0b1011), 0b11);
+    /// ```
+    fn poly_mul_mod(self, other: Self, modulus: Self) -> Self {
+        let a = self.poly_mod(modulus);
+        let b = other.poly_mod(modulus);
+        let degree = modulus.poly_degree().unwrap_or(0) as u32;
+        let leading = Self::ONE << degree;
+        let mut product = Self::as_empty();
+        if let Some(top) = b.poly_degree() {
+            for k in (0..=top as u32).rev() {
+                // `product` has a smaller degree than `modulus`, so it has room to shift.
+                product = product << 1;
+                if product & leading != Self::as_empty() {
+                    product = product ^ modulus;
+                }
+                if b & (Self::ONE << k) != Self::as_empty() {
+                    product = product ^ a;
+                }
+            }
+        }
+        product
+    }
+
+    /// Return whether the polynomial is irreducible: it has a positive degree and no divisor
+    /// other than `1` and itself.
+    ///
+    /// This is Rabin's test: a polynomial `f` of degree `n` is irreducible when `x^(2^n) = x`
+    /// modulo `f`, and `x^(2^(n/q)) - x` is coprime with `f` for every prime factor `q` of
+    /// `n`. It takes `n` squarings modulo `f`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::gf2::Polynomial;
+    /// assert!(0b1011_u8.is_irreducible());
+    /// // x^4 + x^2 + 1 = (x^2 + x + 1)^2
+    /// assert!(!0b10101_u8.is_irreducible());
+    /// // x^64 + x^4 + x^3 + x + 1
+    /// assert!((1_u128 << 64 | 0b11011).is_irreducible());
+    /// ```
+    fn is_irreducible(self) -> bool {
+        let degree = match self.poly_degree() {
+            Some(degree) if degree > 0 => degree,
+            _ => return false,
+        };
+        let x = (Self::ONE << 1).poly_mod(self);
+        let mut power = x;
+        for k in 1..=degree {
+            power = power.poly_mul_mod(power, self);
+            if k < degree
+                && degree % k == 0
+                && is_prime(degree / k)
+                && (power ^ x).poly_gcd(self) != Self::ONE
+            {
+                return false;
+            }
+        }
+        power == x
+    }
+}
+
+/// Return whether `n` is prime, by trial division.
+pub(super) fn is_prime(n: usize) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0)
+}
+
+macro_rules! impl_Polynomial {
+    ($T:ty) => {
+        impl Polynomial for $T {
+            #[inline]
+            fn widening_clmul(self, other: Self) -> (Self, Self) {
+                let product = clmul(u64::from(self), u64::from(other));
+                (product as $T, (product >> <$T>::BITS) as $T)
+            }
+        }
+    };
+}
+
+impl_Polynomial!(u8);
+impl_Polynomial!(u16);
+impl_Polynomial!(u32);
+impl_Polynomial!(u64);
+
+impl Polynomial for u128 {
+    // Karatsuba: three 64×64-bit products instead of four.
+    fn widening_clmul(self, other: Self) -> (Self, Self) {
+        let (a_high, a_low) = ((self >> 64) as u64, self as u64);
+        let (b_high, b_low) = ((other >> 64) as u64, other as u64);
+        let low = clmul(a_low, b_low);
+        let high = clmul(a_high, b_high);
+        let middle = clmul(a_low ^ a_high, b_low ^ b_high) ^ low ^ high;
+        (low ^ (middle << 64), high ^ (middle >> 64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::xorshift64;
+
+    /// The carry-less product of `a` and `b` as `(low, high)`, one bit pair at a time.
+    fn naive_clmul128(a: u128, b: u128) -> (u128, u128) {
+        let (mut low, mut high) = (0, 0);
+        for i in 0..128 {
+            for j in 0..128 {
+                if a >> i & 1 == 1 && b >> j & 1 == 1 {
+                    if i + j < 128 {
+                        low ^= 1 << (i + j);
+                    } else {
+                        high ^= 1 << (i + j - 128);
+                    }
+                }
+            }
+        }
+        (low, high)
+    }
+
+    #[test]
+    fn test_clmul_matches_naive() {
+        let mut state = 0x9e3779b97f4a7c15;
+        let mut values = vec![0, 1, u64::MAX, 1 << 63];
+        for _ in 0..32 {
+            values.push(xorshift64(&mut state));
+        }
+        for &a in &values {
+            for &b in &values {
+                let expected = naive_clmul128(u128::from(a), u128::from(b)).0;
+                assert_eq!(clmul(a, b), expected);
+                assert_eq!(clmul_portable(a, b), expected);
+                assert_eq!(clmul(a, b), clmul(b, a));
+            }
+        }
+    }
+
+    macro_rules! assert_widening_clmul_matches_naive {
+        ($T:ty) => {
+            let mut state = 0xf39cc0605cedc834;
+            for _ in 0..64 {
+                let a = xorshift64(&mut state) as $T;
+                let b = xorshift64(&mut state) as $T;
+                let (low, high) = naive_clmul128(u128::from(a), u128::from(b));
+                let product = low >> <$T>::BITS;
+                assert_eq!(a.widening_clmul(b), (low as $T, product as $T));
+                assert_eq!(high, 0);
+            }
+        };
+    }
+
+    #[test]
+    fn test_widening_clmul_matches_naive() {
+        assert_widening_clmul_matches_naive!(u8);
+        assert_widening_clmul_matches_naive!(u16);
+        assert_widening_clmul_matches_naive!(u32);
+        let mut state = 0x2545f4914f6cdd1d;
+        let mut values = vec![0, 1, u128::MAX, 1 << 127, u128::from(u64::MAX)];
+        for _ in 0..16 {
+            let high = xorshift64(&mut state);
+            let low = xorshift64(&mut state);
+            values.push(u128::from(high) << 64 | u128::from(low));
+        }
+        for &a in &values {
+            for &b in &values {
+                assert_eq!(a.widening_clmul(b), naive_clmul128(a, b));
+                let (low, high) = naive_clmul128(u128::from(a as u64), u128::from(b as u64));
+                assert_eq!(
+                    (a as u64).widening_clmul(b as u64),
+                    (low as u64, (low >> 64) as u64)
+                );
+                assert_eq!(high, 0);
+            }
+        }
+    }
+
+    macro_rules! assert_division_identity {
+        ($T:ty) => {
+            let mut state = 0x9e3779b97f4a7c15;
+            for _ in 0..256 {
+                let a = xorshift64(&mut state) as $T;
+                let shift = xorshift64(&mut state) % u64::from(<$T>::BITS);
+                let b = (xorshift64(&mut state) as $T >> shift).max(1);
+                let (quotient, remainder) = a.poly_divmod(b);
+                let (low, high) = quotient.widening_clmul(b);
+                assert_eq!(low ^ remainder, a);
+                assert_eq!(high, 0);
+                assert!(remainder.poly_degree() < b.poly_degree());
+                assert_eq!(a.poly_mod(b), remainder);
+
+                let gcd = a.poly_gcd(b);
+                assert_eq!(a.poly_mod(gcd), 0);
+                assert_eq!(b.poly_mod(gcd), 0);
+                assert_eq!(gcd, b.poly_gcd(a));
+            }
+        };
+    }
+
+    #[test]
+    fn test_division_identity() {
+        assert_division_identity!(u8);
+        assert_division_identity!(u16);
+        assert_division_identity!(u32);
+        assert_division_identity!(u64);
+        assert_division_identity!(u128);
+    }
+
+    macro_rules! assert_mul_mod_matches_widening {
+        ($T:ty) => {
+            let mut state = 0x2545f4914f6cdd1d;
+            for _ in 0..256 {
+                let a = xorshift64(&mut state) as $T;
+                let b = xorshift64(&mut state) as $T;
+                let modulus = (xorshift64(&mut state) as $T).max(1);
+                let (low, high) = a.widening_clmul(b);
+                // high * x^BITS + low, reduced one half at a time
+                let shifted = (0..<$T>::BITS)
+                    .fold(high.poly_mod(modulus), |r, _| r.poly_mul_mod(0b10, modulus));
+                let expected = shifted ^ low.poly_mod(modulus);
+                assert_eq!(a.poly_mul_mod(b, modulus), expected);
+            }
+        };
+    }
+
+    #[test]
+    fn test_mul_mod_matches_widening() {
+        assert_mul_mod_matches_widening!(u8);
+        assert_mul_mod_matches_widening!(u16);
+        assert_mul_mod_matches_widening!(u32);
+        assert_mul_mod_matches_widening!(u64);
+        assert_mul_mod_matches_widening!(u128);
+    }
+
+    #[test]
+    fn test_irreducible_counts() {
+        // The number of irreducible polynomials of each degree over GF(2).
+        let counts = [0, 2, 1, 2, 3, 6, 9, 18, 30, 56, 99, 186, 335];
+        let mut found = [0; 13];
+        for p in 0..1_u16 << 13 {
+            if p.is_irreducible() {
+                found[p.poly_degree().unwrap_or(0)] += 1;
+                let by_trial = (2..p)
+                    .take_while(|d| {
+                        2 * d.poly_degree().unwrap_or(0) <= p.poly_degree().unwrap_or(0)
+                    })
+                    .all(|d| p.poly_mod(d) != 0);
+                assert!(by_trial);
+            }
+            if p < 0x100 {
+                assert_eq!((p as u8).is_irreducible(), p.is_irreducible());
+            }
+        }
+        assert_eq!(found, counts);
+    }
+
+    #[test]
+    fn test_irreducible_known() {
+        // x^31 + x^3 + 1, x^63 + x + 1 and x^127 + x + 1 are primitive trinomials.
+        assert!((1_u32 << 31 | 0b1001).is_irreducible());
+        assert!((1_u128 << 127 | 0b11).is_irreducible());
+        assert!((1_u64 << 63 | 0b11).is_irreducible());
+        // x^127 + 1 and x^8 + 1 have the root 1.
+        assert!(!(1_u128 << 127 | 1).is_irreducible());
+        assert!(!0x101_u16.is_irreducible());
+        assert!(!0_u8.is_irreducible());
+        assert!(!1_u8.is_irreducible());
+        assert!(0b10_u8.is_irreducible());
+        assert!(0b11_u8.is_irreducible());
+    }
+
+    #[test]
+    fn test_degree_and_gcd_edge_cases() {
+        assert_eq!(u128::MAX.poly_degree(), Some(127));
+        assert_eq!(0_u64.poly_gcd(0), 0);
+        assert_eq!(0_u64.poly_gcd(0b101), 0b101);
+        assert_eq!(0b1011_u8.poly_mul_mod(0b110, 1), 0);
+        assert_eq!(0b1011_u8.poly_divmod(1), (0b1011, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "division by the zero polynomial")]
+    fn test_poly_divmod_panics_on_zero() {
+        0b101_u8.poly_divmod(0);
+    }
+}
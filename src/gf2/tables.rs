@@ -0,0 +1,227 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::field::Gf2n;
+use super::poly::Polynomial;
+
+/// Logarithm and antilogarithm tables of a binary field of up to `2^16` elements.
+///
+/// Every nonzero element of `GF(2^n)` is a power `g^k` of a generator `g`, with `k` below
+/// `2^n - 1`, its logarithm. The tables turn products, quotients and inverses into
+/// additions and subtractions of logarithms and two lookups, which is how Reed–Solomon and
+/// other erasure codes usually multiply. They take `3 * 2^n` words: 768 bytes for
+/// `GF(2^8)` and 384 KiB for `GF(2^16)`.
+///
+/// # Examples
+/// ```
+/// use bittersweet::gf2::{Gf2n, LogTables};
+/// // The Reed–Solomon field of QR codes and RAID 6, modulo x^8 + x^4 + x^3 + x^2 + 1.
+/// type Gf = Gf2n<u8, 0x1d>;
+/// let tables = LogTables::<u8, 0x1d>::new();
+/// assert_eq!(tables.generator(), Gf::new(2));
+/// assert_eq!(tables.exp(8), Gf::new(0x1d));
+/// assert_eq!(tables.log(Gf::new(0x1d)), Some(8));
+/// assert_eq!(tables.mul(Gf::new(0x1d), Gf::new(0x80)), Gf::new(0x1d) * Gf::new(0x80));
+/// ```
+#[derive(Clone)]
+pub struct LogTables<W, const POLY: u128> {
+    generator: Gf2n<W, POLY>,
+    /// `exp[k]` is `g^k` for `k` below `2 * (2^n - 1)`, so that the sum of two logarithms
+    /// needs no reduction.
+    exp: Vec<Gf2n<W, POLY>>,
+    /// `log[a]` is the logarithm of the nonzero element `a`; `log[0]` is unused.
+    log: Vec<W>,
+}
+
+impl<W: Polynomial, const POLY: u128> LogTables<W, POLY> {
+    /// Return the tables of `GF(2^n)` for the smallest generator of its multiplicative group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `W` is wider than 16 bits, or if the modulus `x^n + POLY` is not
+    /// irreducible.
+    pub fn new() -> Self {
+        assert!(
+            W::length() <= 16,
+            "log tables need a field of at most 2^16 elements"
+        );
+        assert!(
+            Gf2n::<W, POLY>::modulus_is_irreducible(),
+            "the modulus is not irreducible"
+        );
+        let order = (1_usize << W::length()) - 1;
+        let generator = (2..=order)
+            .map(|g| Gf2n::<W, POLY>::new(W::from_u128(g as u128)))
+            .find(|&g| is_generator(g, order))
+            .unwrap_or(Gf2n::ONE);
+        let mut exp = Vec::with_capacity(2 * order);
+        let mut log = vec![W::ZERO; order + 1];
+        let mut power = Gf2n::<W, POLY>::ONE;
+        for k in 0..order {
+            exp.push(power);
+            log[power.0.to_u128() as usize] = W::from_u128(k as u128);
+            power *= generator;
+        }
+        exp.extend_from_within(..order);
+        LogTables {
+            generator,
+            exp,
+            log,
+        }
+    }
+
+    /// Return the number of nonzero elements, `2^n - 1`.
+    #[inline]
+    pub fn order(&self) -> usize {
+        self.log.len() - 1
+    }
+
+    /// Return the generator `g` whose powers the tables hold.
+    #[inline]
+    pub fn generator(&self) -> Gf2n<W, POLY> {
+        self.generator
+    }
+
+    /// Return `g^k`.
+    #[inline]
+    pub fn exp(&self, k: usize) -> Gf2n<W, POLY> {
+        self.exp[k % self.order()]
+    }
+
+    /// Return the logarithm of `a`: the `k` below `2^n - 1` with `g^k = a`, or None for zero.
+    #[inline]
+    pub fn log(&self, a: Gf2n<W, POLY>) -> Option<usize> {
+        if a.is_zero() {
+            None
+        } else {
+            Some(self.log[a.0.to_u128() as usize].to_u128() as usize)
+        }
+    }
+
+    /// Return the product `a * b`.
+    #[inline]
+    pub fn mul(&self, a: Gf2n<W, POLY>, b: Gf2n<W, POLY>) -> Gf2n<W, POLY> {
+        match (self.log(a), self.log(b)) {
+            (Some(i), Some(j)) => self.exp[i + j],
+            _ => Gf2n::ZERO,
+        }
+    }
+
+    /// Return the quotient `a / b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `b` is zero.
+    #[inline]
+    pub fn div(&self, a: Gf2n<W, POLY>, b: Gf2n<W, POLY>) -> Gf2n<W, POLY> {
+        let j = self.log(b);
+        assert!(j.is_some(), "division by zero");
+        match (self.log(a), j) {
+            (Some(i), Some(j)) => self.exp[i + self.order() - j],
+            _ => Gf2n::ZERO,
+        }
+    }
+
+    /// Return the multiplicative inverse of `a`, or None for zero.
+    #[inline]
+    pub fn inverse(&self, a: Gf2n<W, POLY>) -> Option<Gf2n<W, POLY>> {
+        self.log(a).map(|i| self.exp[self.order() - i])
+    }
+}
+
+impl<W: Polynomial, const POLY: u128> Default for LogTables<W, POLY> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: fmt::Debug, const POLY: u128> fmt::Debug for LogTables<W, POLY> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogTables")
+            .field("generator", &self.generator)
+            .field("order", &(self.log.len() - 1))
+            .finish()
+    }
+}
+
+/// Return whether `g` has multiplicative order `order`: `g^(order / q) != 1` for every prime
+/// factor `q` of `order`.
+fn is_generator<W: Polynomial, const POLY: u128>(g: Gf2n<W, POLY>, order: usize) -> bool {
+    let mut rest = order;
+    let mut q = 2;
+    while rest > 1 {
+        if q * q > rest {
+            q = rest;
+        }
+        if rest % q == 0 {
+            if g.pow((order / q) as u128) == Gf2n::ONE {
+                return false;
+            }
+            while rest % q == 0 {
+                rest /= q;
+            }
+        }
+        q += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gf2::{Gf2n16, Gf2n8};
+    use crate::test_util::xorshift64;
+
+    #[test]
+    fn test_log_tables_match_field() {
+        let tables = LogTables::<u8, 0x1b>::new();
+        // x is not a generator of the AES field, but x + 1 is.
+        assert_eq!(tables.generator(), Gf2n8::new(3));
+        assert_eq!(tables.order(), 255);
+        for a in 0..=255 {
+            let a = Gf2n8::new(a);
+            for b in 0..=255 {
+                let b = Gf2n8::new(b);
+                assert_eq!(tables.mul(a, b), a * b);
+                if !b.is_zero() {
+                    assert_eq!(tables.div(a, b), a / b);
+                }
+            }
+            assert_eq!(tables.inverse(a), a.inverse());
+            assert_eq!(
+                tables.log(a).map(|k| tables.exp(k)),
+                Some(a).filter(|a| !a.is_zero())
+            );
+        }
+    }
+
+    #[test]
+    fn test_log_tables_16() {
+        let tables = LogTables::<u16, 0x100b>::new();
+        let g = tables.generator();
+        assert_eq!(tables.exp(0), Gf2n16::ONE);
+        assert_eq!(tables.exp(65535), Gf2n16::ONE);
+        assert_eq!(tables.exp(1000), g.pow(1000));
+        let mut state = 0x9e3779b97f4a7c15_u64;
+        for _ in 0..1000 {
+            xorshift64(&mut state);
+            let a = Gf2n16::new(state as u16);
+            let b = Gf2n16::new((state >> 16) as u16);
+            assert_eq!(tables.mul(a, b), a * b);
+            assert_eq!(tables.inverse(a), a.inverse());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the modulus is not irreducible")]
+    fn test_log_tables_panic_on_reducible_modulus() {
+        LogTables::<u8, 0x01>::new();
+    }
+
+    #[test]
+    #[should_panic(expected = "log tables need a field of at most 2^16 elements")]
+    fn test_log_tables_panic_on_wide_field() {
+        LogTables::<u32, 0x8d>::new();
+    }
+}
@@ -0,0 +1,69 @@
+//! A `pclmulqdq` kernel for the 64×64-bit carry-less product on `x86_64`.
+//!
+//! `pclmulqdq` is detected at run time when `std` is enabled, and at compile time
+//! (`-C target-feature=+pclmulqdq`) otherwise.
+
+use core::arch::x86_64::*;
+
+/// Multiply `a` and `b` as polynomials over GF(2) with one `pclmulqdq`.
+#[inline]
+#[target_feature(enable = "pclmulqdq", enable = "sse2")]
+unsafe fn clmul_pclmulqdq(a: u64, b: u64) -> u128 {
+    let product = _mm_clmulepi64_si128(
+        _mm_cvtsi64_si128(a as i64),
+        _mm_cvtsi64_si128(b as i64),
+        0x00,
+    );
+    let low = _mm_cvtsi128_si64(product) as u64;
+    let high = _mm_cvtsi128_si64(_mm_unpackhi_epi64(product, product)) as u64;
+    (u128::from(high) << 64) | u128::from(low)
+}
+
+#[cfg(feature = "std")]
+#[inline]
+fn has_pclmulqdq() -> bool {
+    std::is_x86_feature_detected!("pclmulqdq")
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+fn has_pclmulqdq() -> bool {
+    cfg!(target_feature = "pclmulqdq")
+}
+
+/// Multiply with `pclmulqdq` if the running CPU supports it.
+#[inline]
+pub(super) fn clmul(a: u64, b: u64) -> Option<u128> {
+    if has_pclmulqdq() {
+        // SAFETY: `pclmulqdq` support was checked above, and SSE2 is part of the x86_64
+        // baseline.
+        Some(unsafe { clmul_pclmulqdq(a, b) })
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gf2::poly::clmul_portable;
+    use crate::test_util::xorshift64;
+
+    #[test]
+    fn test_pclmulqdq_matches_portable() {
+        if !is_x86_feature_detected!("pclmulqdq") {
+            return;
+        }
+        let mut state = 0x9e3779b97f4a7c15_u64;
+        let mut values = vec![0, 1, u64::MAX, 1 << 63];
+        for _ in 0..256 {
+            values.push(xorshift64(&mut state));
+        }
+        for &a in &values {
+            for &b in values.iter().take(32) {
+                // SAFETY: `pclmulqdq` support was checked above.
+                assert_eq!(unsafe { clmul_pclmulqdq(a, b) }, clmul_portable(a, b));
+            }
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use core::cmp;
+
+use crate::bitline::{Bitline, Boundary};
+use crate::grid::base::{BitGrid, Metric};
+use crate::grid::life::{next_generation, LifeRule};
+
+macro_rules! impl_BitGrid {
+    ($T:ty, $N:literal) => {
+        impl BitGrid for [$T; $N] {
+            #[inline]
+            fn first_index_2d(&self) -> Option<(usize, usize)> {
+                self.iter()
+                    .enumerate()
+                    .find_map(|(r, row)| row.first_index().map(|c| (r, c)))
+            }
+
+            #[inline]
+            fn first_bit_2d(&self) -> Self {
+                let mut grid = [0; $N];
+                if let Some(r) = self.iter().position(|row| *row != 0) {
+                    grid[r] = self[r].first_bit();
+                }
+                grid
+            }
+
+            #[inline]
+            fn num_bits_2d(&self) -> usize {
+                self.iter().map(|row| row.num_bits()).sum()
+            }
+
+            #[inline]
+            fn remove_2d(&self, other: &Self) -> Self {
+                let mut grid = *self;
+                for (row, other) in grid.iter_mut().zip(other.iter()) {
+                    *row = row.remove(*other);
+                }
+                grid
+            }
+
+            fn around_2d(&self, n: usize, metric: Metric) -> Self {
+                let reach = cmp::min(n, $N - 1);
+                let mut grid = [0; $N];
+                for (r, row) in grid.iter_mut().enumerate() {
+                    // Offsets within the same row exclude 0; other rows include the column
+                    // offset 0, so their horizontal spread is `with_around`.
+                    *row = self[r].around(n);
+                    for d in 1..=reach {
+                        let width = match metric {
+                            Metric::Chebyshev => n,
+                            Metric::Manhattan => n - d,
+                        };
+                        if r >= d {
+                            *row |= self[r - d].with_around(width);
+                        }
+                        if r + d < $N {
+                            *row |= self[r + d].with_around(width);
+                        }
+                    }
+                }
+                grid
+            }
+
+            fn with_around_2d(&self, n: usize, metric: Metric) -> Self {
+                let mut grid = self.around_2d(n, metric);
+                for (row, original) in grid.iter_mut().zip(self.iter()) {
+                    *row |= original;
+                }
+                grid
+            }
+
+            fn erode(&self, n: usize, metric: Metric) -> Self {
+                /// Keep the bits whose left and right neighbors are set, treating the bits
+                /// past either end as unset.
+                #[inline]
+                fn erode_row(row: $T) -> $T {
+                    row & (row << 1) & (row >> 1)
+                }
+
+                // Eroding by the unit ball `n` times is the same as eroding by the ball of
+                // radius `n`; after `$N / 2` rounds every cell is too close to an edge.
+                let mut grid = *self;
+                for _ in 0..cmp::min(n, $N / 2 + 1) {
+                    let rows = grid;
+                    for (r, row) in grid.iter_mut().enumerate() {
+                        let above = if r > 0 { rows[r - 1] } else { 0 };
+                        let below = if r + 1 < $N { rows[r + 1] } else { 0 };
+                        *row = match metric {
+                            Metric::Chebyshev => {
+                                let (above, below) = (erode_row(above), erode_row(below));
+                                erode_row(rows[r]) & above & below
+                            }
+                            Metric::Manhattan => erode_row(rows[r]) & above & below,
+                        };
+                    }
+                }
+                grid
+            }
+
+            fn life_step(&self, rule: LifeRule, boundary: Boundary) -> Self {
+                // Align the west and east neighbors of every cell of a row with the cell.
+                let sides = |row: $T| match boundary {
+                    Boundary::Zero => (row >> 1, row << 1),
+                    Boundary::Wrap => (row.right_rotate(1), row.left_rotate(1)),
+                };
+                let mut grid = [0; $N];
+                for (r, next) in grid.iter_mut().enumerate() {
+                    let (above, below) = match boundary {
+                        Boundary::Zero => (
+                            if r > 0 { self[r - 1] } else { 0 },
+                            if r + 1 < $N { self[r + 1] } else { 0 },
+                        ),
+                        Boundary::Wrap => (self[(r + $N - 1) % $N], self[(r + 1) % $N]),
+                    };
+                    let (above_w, above_e) = sides(above);
+                    let (w, e) = sides(self[r]);
+                    let (below_w, below_e) = sides(below);
+                    let neighbors = [above_w, above, above_e, w, e, below_w, below, below_e];
+                    *next = next_generation(self[r], neighbors, 0, rule);
+                }
+                grid
+            }
+
+            fn flood_fill(&self, seed: &Self, metric: Metric) -> Self {
+                let mut filled = [0; $N];
+                for ((cell, mask), seed) in filled.iter_mut().zip(self.iter()).zip(seed.iter()) {
+                    *cell = mask & seed;
+                }
+                loop {
+                    let mut grown = filled.with_around_2d(1, metric);
+                    for (cell, mask) in grown.iter_mut().zip(self.iter()) {
+                        *cell &= mask;
+                    }
+                    if grown == filled {
+                        return filled;
+                    }
+                    filled = grown;
+                }
+            }
+        }
+    };
+}
+
+impl_BitGrid!(u8, 8);
+impl_BitGrid!(u16, 16);
+impl_BitGrid!(u32, 32);
+impl_BitGrid!(u64, 64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pseudo_random_grid;
+
+    fn get(grid: &[u16; 16], row: usize, col: usize) -> bool {
+        grid[row] & (0x8000 >> col) != 0
+    }
+
+    fn distance(a: (usize, usize), b: (usize, usize), metric: Metric) -> usize {
+        let dr = if a.0 > b.0 { a.0 - b.0 } else { b.0 - a.0 };
+        let dc = if a.1 > b.1 { a.1 - b.1 } else { b.1 - a.1 };
+        match metric {
+            Metric::Chebyshev => cmp::max(dr, dc),
+            Metric::Manhattan => dr + dc,
+        }
+    }
+
+    /// Build a grid from a per-cell predicate.
+    fn from_fn<F: Fn(usize, usize) -> bool>(f: F) -> [u16; 16] {
+        let mut grid = [0; 16];
+        for (r, row) in grid.iter_mut().enumerate() {
+            for c in 0..16 {
+                if f(r, c) {
+                    *row |= 0x8000 >> c;
+                }
+            }
+        }
+        grid
+    }
+
+    fn cells() -> impl Iterator<Item = (usize, usize)> {
+        (0..16).flat_map(|r| (0..16).map(move |c| (r, c)))
+    }
+
+    fn naive_around(grid: &[u16; 16], n: usize, metric: Metric) -> [u16; 16] {
+        from_fn(|r, c| {
+            cells().any(|s| {
+                let d = distance(s, (r, c), metric);
+                get(grid, s.0, s.1) && 1 <= d && d <= n && s != (r, c)
+            })
+        })
+    }
+
+    fn naive_erode(grid: &[u16; 16], n: usize, metric: Metric) -> [u16; 16] {
+        from_fn(|r, c| {
+            let (r, c) = (r as isize, c as isize);
+            let n = n as isize;
+            (-n..=n).all(|dr| {
+                (-n..=n).all(|dc| {
+                    let inside = match metric {
+                        Metric::Chebyshev => true,
+                        Metric::Manhattan => dr.abs() + dc.abs() <= n,
+                    };
+                    let (sr, sc) = (r + dr, c + dc);
+                    !inside
+                        || ((0..16).contains(&sr)
+                            && (0..16).contains(&sc)
+                            && get(grid, sr as usize, sc as usize))
+                })
+            })
+        })
+    }
+
+    #[test]
+    fn test_around_2d_matches_naive() {
+        let mut state = 0x9e3779b97f4a7c15;
+        for _ in 0..8 {
+            let grid = pseudo_random_grid::<u16, 16>(&mut state, 3);
+            for &metric in [Metric::Chebyshev, Metric::Manhattan].iter() {
+                for &n in [0, 1, 2, 3, 7, 15, 16, 100].iter() {
+                    assert_eq!(
+                        grid.around_2d(n, metric),
+                        naive_around(&grid, n, metric),
+                        "n = {}, {:?}",
+                        n,
+                        metric
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_with_around_2d_includes_self() {
+        let mut state = 42;
+        for _ in 0..8 {
+            let grid = pseudo_random_grid::<u16, 16>(&mut state, 3);
+            for &metric in [Metric::Chebyshev, Metric::Manhattan].iter() {
+                assert_eq!(grid.with_around_2d(0, metric), grid);
+                let around = grid.around_2d(2, metric);
+                let with_around = grid.with_around_2d(2, metric);
+                for r in 0..16 {
+                    assert_eq!(with_around[r], around[r] | grid[r]);
+                }
+                assert_eq!(grid.dilate(2, metric), with_around);
+            }
+        }
+    }
+
+    #[test]
+    fn test_erode_matches_naive() {
+        let mut state = 7;
+        for _ in 0..8 {
+            // Dense grids, so that erosion leaves something behind.
+            let grid = pseudo_random_grid::<u16, 16>(&mut state, 3).map(|row| !row);
+            for &metric in [Metric::Chebyshev, Metric::Manhattan].iter() {
+                for &n in [0, 1, 2, 8, 100].iter() {
+                    assert_eq!(
+                        grid.erode(n, metric),
+                        naive_erode(&grid, n, metric),
+                        "n = {}, {:?}",
+                        n,
+                        metric
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_erode_full_grid() {
+        let full = [u64::MAX; 64];
+        let eroded = full.erode(3, Metric::Chebyshev);
+        assert_eq!(eroded[2], 0);
+        assert_eq!(eroded[3], u64::MAX >> 6 << 3);
+        assert_eq!(full.erode(32, Metric::Chebyshev), [0; 64]);
+        assert_eq!([u8::MAX; 8].erode(3, Metric::Manhattan)[3], 0b00011000);
+    }
+
+    #[test]
+    fn test_flood_fill_matches_naive() {
+        let mut state = 0x0123456789abcdef;
+        for _ in 0..8 {
+            let mask = pseudo_random_grid::<u16, 16>(&mut state, 2).map(|row| !row);
+            let seed = from_fn(|r, c| (r, c) == (8, 8));
+            for &metric in [Metric::Chebyshev, Metric::Manhattan].iter() {
+                // Grow the seed one cell at a time until nothing changes.
+                let mut expected = from_fn(|r, c| get(&seed, r, c) && get(&mask, r, c));
+                loop {
+                    let grown = from_fn(|r, c| {
+                        get(&mask, r, c)
+                            && cells().any(|s| {
+                                get(&expected, s.0, s.1) && distance(s, (r, c), metric) <= 1
+                            })
+                    });
+                    if grown == expected {
+                        break;
+                    }
+                    expected = grown;
+                }
+                assert_eq!(mask.flood_fill(&seed, metric), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flood_fill_spiral() {
+        // A path that winds through the whole grid needs one round per cell.
+        let mut mask = [0_u32; 32];
+        for (r, row) in mask.iter_mut().enumerate() {
+            *row = match r % 4 {
+                0 | 2 => u32::MAX,
+                1 => 1,
+                _ => 1 << 31,
+            };
+        }
+        let mut seed = [0_u32; 32];
+        seed[0] = 1 << 31;
+        assert_eq!(mask.flood_fill(&seed, Metric::Manhattan), mask);
+        assert_eq!(mask.flood_fill(&[0; 32], Metric::Manhattan), [0; 32]);
+    }
+}
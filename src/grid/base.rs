@@ -0,0 +1,264 @@
+use crate::bitline::Boundary;
+use crate::grid::components::Components;
+use crate::grid::life::LifeRule;
+
+/// A distance between two cells of a grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// The larger of the row and column distances. Cells at distance 1 form the
+    /// 8-neighborhood (orthogonal and diagonal neighbors).
+    Chebyshev,
+    /// The sum of the row and column distances. Cells at distance 1 form the
+    /// 4-neighborhood (orthogonal neighbors only).
+    Manhattan,
+}
+
+/// Neighborhood operations over a square bit grid.
+///
+/// Cell `(row, col)` is bit `col` (MSB-first) of row `row`. Cells outside the grid count as
+/// unset. See the [module-level documentation](super) for the layout.
+pub trait BitGrid: Sized {
+    /// Return the first set cell in row-major order as `(row, col)`.
+    /// If no cell is set, return None.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::grid::BitGrid;
+    /// let grid: [u8; 8] = [0, 0, 0b00100100, 0b10000000, 0, 0, 0, 0];
+    /// assert_eq!(grid.first_index_2d(), Some((2, 2)));
+    /// assert_eq!([0_u8; 8].first_index_2d(), None);
+    /// ```
+    fn first_index_2d(&self) -> Option<(usize, usize)>;
+
+    /// Return the grid with only its first set cell in row-major order.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::grid::BitGrid;
+    /// let grid: [u8; 8] = [0, 0, 0b00100100, 0b10000000, 0, 0, 0, 0];
+    /// assert_eq!(grid.first_bit_2d(), [0, 0, 0b00100000, 0, 0, 0, 0, 0]);
+    /// ```
+    fn first_bit_2d(&self) -> Self;
+
+    /// Return the number of set cells.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::grid::BitGrid;
+    /// let grid: [u8; 8] = [0, 0, 0b00100100, 0b10000000, 0, 0, 0, 0];
+    /// assert_eq!(grid.num_bits_2d(), 3);
+    /// ```
+    fn num_bits_2d(&self) -> usize;
+
+    /// Return the set cells of `self` that are not set in `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::grid::BitGrid;
+    /// let grid: [u8; 8] = [0b11000000, 0b10000000, 0, 0, 0, 0, 0, 0];
+    /// let other: [u8; 8] = [0b01000000, 0b11000000, 0, 0, 0, 0, 0, 0];
+    /// assert_eq!(grid.remove_2d(&other), [0b10000000, 0, 0, 0, 0, 0, 0, 0]);
+    /// ```
+    fn remove_2d(&self, other: &Self) -> Self;
+
+    /// Return all cells within distance `n` of a set cell, excluding the offset of 0.
+    ///
+    /// This is the two-dimensional counterpart of
+    /// [`Bitline::around`](crate::bitline::Bitline::around): a set cell appears in the result
+    /// only when another set cell lies within distance `n` of it. `around_2d(0, _)` is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::grid::{BitGrid, Metric};
+    /// let grid: [u8; 8] = [0, 0, 0, 0b00010000, 0, 0, 0, 0];
+    /// assert_eq!(
+    ///     grid.around_2d(1, Metric::Chebyshev),
+    ///     [0, 0, 0b00111000, 0b00101000, 0b00111000, 0, 0, 0]
+    /// );
+    /// assert_eq!(
+    ///     grid.around_2d(2, Metric::Manhattan),
+    ///     [0, 0b00010000, 0b00111000, 0b01101100, 0b00111000, 0b00010000, 0, 0]
+    /// );
+    /// ```
+    fn around_2d(&self, n: usize, metric: Metric) -> Self;
+
+    /// Return all cells within distance `n` of a set cell, including the set cells themselves.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::grid::{BitGrid, Metric};
+    /// let grid: [u8; 8] = [0b10000000, 0, 0, 0, 0, 0, 0, 0];
+    /// assert_eq!(
+    ///     grid.with_around_2d(1, Metric::Chebyshev),
+    ///     [0b11000000, 0b11000000, 0, 0, 0, 0, 0, 0]
+    /// );
+    /// assert_eq!(
+    ///     grid.with_around_2d(1, Metric::Manhattan),
+    ///     [0b11000000, 0b10000000, 0, 0, 0, 0, 0, 0]
+    /// );
+    /// ```
+    fn with_around_2d(&self, n: usize, metric: Metric) -> Self;
+
+    /// Return the dilation of the grid by the ball of radius `n`.
+    ///
+    /// This is the same as [`with_around_2d`](BitGrid::with_around_2d) and is provided under
+    /// its morphological name.
+    fn dilate(&self, n: usize, metric: Metric) -> Self {
+        self.with_around_2d(n, metric)
+    }
+
+    /// Return the erosion of the grid by the ball of radius `n`.
+    ///
+    /// A cell stays set only when every cell within distance `n` of it is set. Because cells
+    /// outside the grid count as unset, cells closer than `n + 1` to an edge are always
+    /// cleared.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::grid::{BitGrid, Metric};
+    /// let grid: [u8; 8] = [0, 0b01110000, 0b01111000, 0b01110000, 0, 0, 0, 0];
+    /// assert_eq!(
+    ///     grid.erode(1, Metric::Chebyshev),
+    ///     [0, 0, 0b00100000, 0, 0, 0, 0, 0]
+    /// );
+    /// assert_eq!(
+    ///     grid.erode(1, Metric::Manhattan),
+    ///     [0, 0, 0b00110000, 0, 0, 0, 0, 0]
+    /// );
+    /// assert_eq!([0xff_u8; 8].erode(1, Metric::Manhattan)[0], 0);
+    /// ```
+    fn erode(&self, n: usize, metric: Metric) -> Self;
+
+    /// Return the cells of `self` connected to `seed` within `self`.
+    ///
+    /// `self` is the mask of cells the fill may enter, and two cells are connected when they
+    /// are at distance 1 under `metric`. Seed cells outside the mask are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::grid::{BitGrid, Metric};
+    /// let mask: [u8; 8] = [
+    ///     0b11000000, //
+    ///     0b01000000, //
+    ///     0b00100011, //
+    ///     0b00000011, //
+    ///     0, 0, 0, 0,
+    /// ];
+    /// let seed: [u8; 8] = [0b10000000, 0, 0, 0, 0, 0, 0, 0];
+    /// assert_eq!(
+    ///     mask.flood_fill(&seed, Metric::Chebyshev),
+    ///     [0b11000000, 0b01000000, 0b00100000, 0, 0, 0, 0, 0]
+    /// );
+    /// assert_eq!(
+    ///     mask.flood_fill(&seed, Metric::Manhattan),
+    ///     [0b11000000, 0b01000000, 0, 0, 0, 0, 0, 0]
+    /// );
+    /// ```
+    fn flood_fill(&self, seed: &Self, metric: Metric) -> Self;
+
+    /// Return the next generation of the Life-like cellular automaton `rule`.
+    ///
+    /// Every cell counts its live neighbors in the 8-neighborhood; the counts of all cells
+    /// are accumulated at once with bitwise full adders. With [`Boundary::Zero`] the cells
+    /// outside the grid are dead, and with [`Boundary::Wrap`] the grid is a torus whose
+    /// opposite edges are neighbors.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::bitline::Boundary;
+    /// use bittersweet::grid::{BitGrid, LifeRule};
+    /// // A blinker oscillates between a horizontal and a vertical bar.
+    /// let horizontal: [u8; 8] = [0, 0b01110000, 0, 0, 0, 0, 0, 0];
+    /// let vertical: [u8; 8] = [0b00100000, 0b00100000, 0b00100000, 0, 0, 0, 0, 0];
+    /// assert_eq!(horizontal.life_step(LifeRule::CONWAY, Boundary::Zero), vertical);
+    /// assert_eq!(vertical.life_step(LifeRule::CONWAY, Boundary::Zero), horizontal);
+    /// // On a torus the top edge touches the bottom one.
+    /// let top: [u8; 8] = [0b01110000, 0, 0, 0, 0, 0, 0, 0];
+    /// assert_eq!(
+    ///     top.life_step(LifeRule::CONWAY, Boundary::Wrap),
+    ///     [0b00100000, 0b00100000, 0, 0, 0, 0, 0, 0b00100000]
+    /// );
+    /// ```
+    fn life_step(&self, rule: LifeRule, boundary: Boundary) -> Self;
+
+    /// Return an iterator over the connected components of the grid.
+    ///
+    /// Two set cells are connected when they are at distance 1 under `metric`, so
+    /// [`Metric::Manhattan`] gives 4-connected and [`Metric::Chebyshev`] 8-connected
+    /// components. Each component is yielded as a mask, ordered by its first cell in
+    /// row-major order.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::grid::{BitGrid, Metric};
+    /// let grid: [u8; 8] = [
+    ///     0b11000000, //
+    ///     0b00100001, //
+    ///     0b00000001, //
+    ///     0, 0, 0, 0, 0,
+    /// ];
+    /// let components: Vec<[u8; 8]> = grid.components(Metric::Chebyshev).collect();
+    /// assert_eq!(
+    ///     components,
+    ///     vec![
+    ///         [0b11000000, 0b00100000, 0, 0, 0, 0, 0, 0],
+    ///         [0, 0b00000001, 0b00000001, 0, 0, 0, 0, 0],
+    ///     ]
+    /// );
+    /// assert_eq!(grid.components(Metric::Manhattan).count(), 3);
+    /// ```
+    fn components(&self, metric: Metric) -> Components<Self>
+    where
+        Self: Clone,
+    {
+        Components::new(self.clone(), metric)
+    }
+
+    /// Return the number of connected components of the grid.
+    ///
+    /// See [`components`](BitGrid::components) for the connectivity under each metric.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::grid::{BitGrid, Metric};
+    /// let grid: [u8; 8] = [0b10100000, 0b01000000, 0, 0, 0, 0, 0, 0];
+    /// assert_eq!(grid.count_components(Metric::Chebyshev), 1);
+    /// assert_eq!(grid.count_components(Metric::Manhattan), 3);
+    /// ```
+    fn count_components(&self, metric: Metric) -> usize
+    where
+        Self: Clone,
+    {
+        self.components(metric).count()
+    }
+
+    /// Return the connected component with the most cells, or `None` for an empty grid.
+    ///
+    /// When several components have the same size, the first one in the order of
+    /// [`components`](BitGrid::components) is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::grid::{BitGrid, Metric};
+    /// let grid: [u8; 8] = [0b10011000, 0b00011000, 0, 0, 0, 0, 0, 0];
+    /// assert_eq!(
+    ///     grid.largest_component(Metric::Manhattan),
+    ///     Some([0b00011000, 0b00011000, 0, 0, 0, 0, 0, 0])
+    /// );
+    /// assert_eq!([0_u8; 8].largest_component(Metric::Manhattan), None);
+    /// ```
+    fn largest_component(&self, metric: Metric) -> Option<Self>
+    where
+        Self: Clone,
+    {
+        let mut largest: Option<(usize, Self)> = None;
+        for component in self.components(metric) {
+            let size = component.num_bits_2d();
+            match largest {
+                Some((largest_size, _)) if largest_size >= size => {}
+                _ => largest = Some((size, component)),
+            }
+        }
+        largest.map(|(_, component)| component)
+    }
+}
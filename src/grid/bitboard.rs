@@ -0,0 +1,151 @@
+use crate::bitboard::Bitboard8x8;
+use crate::bitline::{Bitline, Boundary};
+use crate::grid::base::{BitGrid, Metric};
+use crate::grid::life::LifeRule;
+
+/// Grow the board by one cell under `metric`, using the directional shifts.
+#[inline]
+fn dilate_once(board: Bitboard8x8, metric: Metric) -> Bitboard8x8 {
+    let row = board | board.shift_e() | board.shift_w();
+    match metric {
+        Metric::Chebyshev => row | row.shift_n() | row.shift_s(),
+        Metric::Manhattan => row | board.shift_n() | board.shift_s(),
+    }
+}
+
+/// Shrink the board by one cell under `metric`, using the directional shifts. The shifts
+/// fill the edges with unset cells, so every edge cell is cleared.
+#[inline]
+fn erode_once(board: Bitboard8x8, metric: Metric) -> Bitboard8x8 {
+    let row = board & board.shift_e() & board.shift_w();
+    match metric {
+        Metric::Chebyshev => row & row.shift_n() & row.shift_s(),
+        Metric::Manhattan => row & board.shift_n() & board.shift_s(),
+    }
+}
+
+/// The packed 8×8 board, with the same results as the `[u8; 8]` grid of its rows.
+///
+/// Distance-1 steps (erosion and flood fill) use the directional shifts of
+/// [`Bitboard8x8`] directly instead of going through the rows.
+impl BitGrid for Bitboard8x8 {
+    #[inline]
+    fn first_index_2d(&self) -> Option<(usize, usize)> {
+        self.bits().first_index().map(|i| (i / 8, i % 8))
+    }
+
+    #[inline]
+    fn first_bit_2d(&self) -> Self {
+        Bitboard8x8(self.bits().first_bit())
+    }
+
+    #[inline]
+    fn num_bits_2d(&self) -> usize {
+        self.count() as usize
+    }
+
+    #[inline]
+    fn remove_2d(&self, other: &Self) -> Self {
+        self.difference(*other)
+    }
+
+    #[inline]
+    fn around_2d(&self, n: usize, metric: Metric) -> Self {
+        Bitboard8x8::from_rows(self.to_rows().around_2d(n, metric))
+    }
+
+    #[inline]
+    fn with_around_2d(&self, n: usize, metric: Metric) -> Self {
+        if n == 1 {
+            return dilate_once(*self, metric);
+        }
+        Bitboard8x8::from_rows(self.to_rows().with_around_2d(n, metric))
+    }
+
+    fn erode(&self, n: usize, metric: Metric) -> Self {
+        let mut board = *self;
+        for _ in 0..n {
+            if board.is_empty() {
+                break;
+            }
+            board = erode_once(board, metric);
+        }
+        board
+    }
+
+    #[inline]
+    fn life_step(&self, rule: LifeRule, boundary: Boundary) -> Self {
+        Bitboard8x8::from_rows(self.to_rows().life_step(rule, boundary))
+    }
+
+    fn flood_fill(&self, seed: &Self, metric: Metric) -> Self {
+        let mut filled = *self & *seed;
+        loop {
+            let grown = dilate_once(filled, metric) & *self;
+            if grown == filled {
+                return filled;
+            }
+            filled = grown;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pseudo_random_boards;
+
+    #[test]
+    fn test_matches_rows() {
+        for board in pseudo_random_boards(64, Bitboard8x8::from_rows) {
+            let rows = board.to_rows();
+            assert_eq!(
+                board.first_index_2d(),
+                rows.first_index_2d(),
+                "{:016x}",
+                board.0
+            );
+            assert_eq!(board.first_bit_2d().to_rows(), rows.first_bit_2d());
+            assert_eq!(board.num_bits_2d(), rows.num_bits_2d());
+            for &metric in [Metric::Chebyshev, Metric::Manhattan].iter() {
+                for n in 0..=8 {
+                    assert_eq!(
+                        board.with_around_2d(n, metric).to_rows(),
+                        rows.with_around_2d(n, metric)
+                    );
+                    assert_eq!(board.erode(n, metric).to_rows(), rows.erode(n, metric));
+                }
+                let boundary = match metric {
+                    Metric::Chebyshev => Boundary::Zero,
+                    Metric::Manhattan => Boundary::Wrap,
+                };
+                assert_eq!(
+                    board.life_step(LifeRule::CONWAY, boundary).to_rows(),
+                    rows.life_step(LifeRule::CONWAY, boundary)
+                );
+                let seed = board.first_bit_2d();
+                assert_eq!(
+                    board.flood_fill(&seed, metric).to_rows(),
+                    rows.flood_fill(&seed.to_rows(), metric)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_components_match_rows() {
+        for board in pseudo_random_boards(64, Bitboard8x8::from_rows) {
+            let rows = board.to_rows();
+            for &metric in [Metric::Chebyshev, Metric::Manhattan].iter() {
+                let components = board.components(metric);
+                assert!(components
+                    .map(|component| component.to_rows())
+                    .eq(rows.components(metric)));
+                assert_eq!(
+                    board.largest_component(metric).map(|c| c.to_rows()),
+                    rows.largest_component(metric)
+                );
+            }
+        }
+    }
+}
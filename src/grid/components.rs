@@ -0,0 +1,148 @@
+use core::iter::FusedIterator;
+
+use crate::grid::base::{BitGrid, Metric};
+
+/// An iterator over the connected components of a grid.
+///
+/// This struct is created by [`BitGrid::components`]. Each step takes the first remaining
+/// set cell as a seed, flood-fills its component and removes it from the remaining cells.
+#[derive(Clone, Debug)]
+pub struct Components<G> {
+    remaining: G,
+    metric: Metric,
+}
+
+impl<G> Components<G> {
+    pub(crate) fn new(grid: G, metric: Metric) -> Self {
+        Components {
+            remaining: grid,
+            metric,
+        }
+    }
+}
+
+impl<G: BitGrid> Iterator for Components<G> {
+    type Item = G;
+
+    fn next(&mut self) -> Option<G> {
+        self.remaining.first_index_2d()?;
+        let seed = self.remaining.first_bit_2d();
+        let component = self.remaining.flood_fill(&seed, self.metric);
+        self.remaining = self.remaining.remove_2d(&component);
+        Some(component)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let cells = self.remaining.num_bits_2d();
+        // At least one component remains while any cell does.
+        (usize::from(cells != 0), Some(cells))
+    }
+}
+
+impl<G: BitGrid> FusedIterator for Components<G> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pseudo_random_grid;
+
+    /// Label every set cell with a depth-first search; labels follow row-major order of the
+    /// first cell of each component.
+    fn naive_labels(grid: &[u32; 32], metric: Metric) -> Vec<Vec<(usize, usize)>> {
+        let get = |r: usize, c: usize| grid[r] & (1 << (31 - c)) != 0;
+        let mut seen = [[false; 32]; 32];
+        let mut components = Vec::new();
+        for r in 0..32 {
+            for c in 0..32 {
+                if !get(r, c) || seen[r][c] {
+                    continue;
+                }
+                let mut cells = Vec::new();
+                let mut stack = vec![(r, c)];
+                seen[r][c] = true;
+                while let Some((r, c)) = stack.pop() {
+                    cells.push((r, c));
+                    for dr in -1_isize..=1 {
+                        for dc in -1_isize..=1 {
+                            if metric == Metric::Manhattan && dr != 0 && dc != 0 {
+                                continue;
+                            }
+                            let (nr, nc) = (r as isize + dr, c as isize + dc);
+                            if !(0..32).contains(&nr) || !(0..32).contains(&nc) {
+                                continue;
+                            }
+                            let (nr, nc) = (nr as usize, nc as usize);
+                            if get(nr, nc) && !seen[nr][nc] {
+                                seen[nr][nc] = true;
+                                stack.push((nr, nc));
+                            }
+                        }
+                    }
+                }
+                cells.sort_unstable();
+                components.push(cells);
+            }
+        }
+        components
+    }
+
+    fn cells_of(grid: &[u32; 32]) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+        for (r, row) in grid.iter().enumerate() {
+            for c in 0..32 {
+                if row & (1 << (31 - c)) != 0 {
+                    cells.push((r, c));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn test_components_match_naive() {
+        let mut state = 0x9e3779b97f4a7c15;
+        for _ in 0..16 {
+            let grid = pseudo_random_grid::<u32, 32>(&mut state, 2);
+            for &metric in [Metric::Chebyshev, Metric::Manhattan].iter() {
+                let components: Vec<_> = grid.components(metric).map(|c| cells_of(&c)).collect();
+                assert_eq!(components, naive_labels(&grid, metric));
+                assert_eq!(grid.count_components(metric), components.len());
+                let largest = components.iter().map(|c| c.len()).max();
+                assert_eq!(
+                    grid.largest_component(metric).map(|c| c.num_bits_2d()),
+                    largest
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_checkerboard() {
+        let mut grid = [0_u64; 64];
+        for (r, row) in grid.iter_mut().enumerate() {
+            *row = if r % 2 == 0 {
+                0xaaaa_aaaa_aaaa_aaaa
+            } else {
+                0x5555_5555_5555_5555
+            };
+        }
+        assert_eq!(grid.count_components(Metric::Chebyshev), 1);
+        assert_eq!(grid.count_components(Metric::Manhattan), 64 * 32);
+        assert_eq!(
+            grid.largest_component(Metric::Manhattan),
+            Some(grid.first_bit_2d())
+        );
+    }
+
+    #[test]
+    fn test_empty_and_full() {
+        assert_eq!([0_u16; 16].components(Metric::Chebyshev).next(), None);
+        assert_eq!([0_u16; 16].count_components(Metric::Manhattan), 0);
+        let full = [u16::MAX; 16];
+        let mut components = full.components(Metric::Manhattan);
+        assert_eq!(components.size_hint(), (1, Some(256)));
+        assert_eq!(components.next(), Some(full));
+        assert_eq!(components.next(), None);
+        assert_eq!(components.next(), None);
+    }
+}
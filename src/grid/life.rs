@@ -0,0 +1,263 @@
+use core::ops::{BitAnd, BitOr, BitXor, Not};
+
+/// A Life-like cellular automaton rule in birth/survival form.
+///
+/// Bit `k` of the birth mask says whether a dead cell with exactly `k` live neighbors
+/// (in the 8-neighborhood) comes alive; bit `k` of the survival mask says whether a live cell
+/// with `k` live neighbors stays alive. Only bits `0` to `8` are meaningful.
+///
+/// # Examples
+/// ```
+/// use bittersweet::grid::LifeRule;
+/// assert_eq!(LifeRule::new(&[3], &[2, 3]), LifeRule::CONWAY);
+/// assert!(LifeRule::CONWAY.is_born(3));
+/// assert!(!LifeRule::CONWAY.survives(4));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LifeRule {
+    birth: u16,
+    survival: u16,
+}
+
+impl LifeRule {
+    /// Conway's Game of Life, B3/S23.
+    pub const CONWAY: Self = LifeRule::from_masks(1 << 3, 1 << 2 | 1 << 3);
+    /// HighLife, B36/S23.
+    pub const HIGHLIFE: Self = LifeRule::from_masks(1 << 3 | 1 << 6, 1 << 2 | 1 << 3);
+    /// Seeds, B2/S: every live cell dies in the next generation.
+    pub const SEEDS: Self = LifeRule::from_masks(1 << 2, 0);
+    /// Day & Night, B3678/S34678.
+    pub const DAY_AND_NIGHT: Self = LifeRule::from_masks(
+        1 << 3 | 1 << 6 | 1 << 7 | 1 << 8,
+        1 << 3 | 1 << 4 | 1 << 6 | 1 << 7 | 1 << 8,
+    );
+
+    /// Create a rule from the neighbor counts that cause a birth and those that allow a
+    /// live cell to survive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a count is greater than 8.
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        let mask = |counts: &[u8]| {
+            counts.iter().fold(0_u16, |mask, &count| {
+                assert!(count <= 8, "neighbor count out of range");
+                mask | 1 << count
+            })
+        };
+        LifeRule::from_masks(mask(birth), mask(survival))
+    }
+
+    /// Create a rule from birth and survival masks, where bit `k` stands for `k` live
+    /// neighbors. Bits above 8 are ignored.
+    #[inline]
+    pub const fn from_masks(birth: u16, survival: u16) -> Self {
+        LifeRule {
+            birth: birth & 0x1ff,
+            survival: survival & 0x1ff,
+        }
+    }
+
+    /// Return the birth mask.
+    #[inline]
+    pub const fn birth(self) -> u16 {
+        self.birth
+    }
+
+    /// Return the survival mask.
+    #[inline]
+    pub const fn survival(self) -> u16 {
+        self.survival
+    }
+
+    /// Return whether a dead cell with `neighbors` live neighbors comes alive.
+    #[inline]
+    pub const fn is_born(self, neighbors: usize) -> bool {
+        neighbors <= 8 && (self.birth >> neighbors) & 1 == 1
+    }
+
+    /// Return whether a live cell with `neighbors` live neighbors stays alive.
+    #[inline]
+    pub const fn survives(self, neighbors: usize) -> bool {
+        neighbors <= 8 && (self.survival >> neighbors) & 1 == 1
+    }
+}
+
+/// Add three bit vectors lane by lane, returning the sum and carry vectors.
+#[inline]
+fn full_add<T>(a: T, b: T, c: T) -> (T, T)
+where
+    T: Copy + BitAnd<Output = T> + BitOr<Output = T> + BitXor<Output = T>,
+{
+    let ab = a ^ b;
+    (ab ^ c, (a & b) | (ab & c))
+}
+
+/// Apply `rule` to every lane of `alive`, given the eight neighbor vectors aligned with it.
+///
+/// The neighbor counts are accumulated into four bit planes (1, 2, 4 and 8) with a tree of
+/// full adders, so every lane is updated at once.
+#[inline]
+pub(crate) fn next_generation<T>(alive: T, neighbors: [T; 8], zero: T, rule: LifeRule) -> T
+where
+    T: Copy + BitAnd<Output = T> + BitOr<Output = T> + BitXor<Output = T> + Not<Output = T>,
+{
+    let [n0, n1, n2, n3, n4, n5, n6, n7] = neighbors;
+    let (s0, c0) = full_add(n0, n1, n2);
+    let (s1, c1) = full_add(n3, n4, n5);
+    let (s2, c2) = full_add(n6, n7, zero);
+    let (ones, c3) = full_add(s0, s1, s2);
+    let (t, c4) = full_add(c0, c1, c2);
+    let (twos, c5) = full_add(t, c3, zero);
+    let (fours, eights) = full_add(c4, c5, zero);
+
+    let mut next = zero;
+    for count in 0..=8 {
+        let born = rule.is_born(count);
+        let survives = rule.survives(count);
+        if !born && !survives {
+            continue;
+        }
+        let plane = |plane: T, bit: usize| if count & bit != 0 { plane } else { !plane };
+        let matches = plane(ones, 1) & plane(twos, 2) & plane(fours, 4) & plane(eights, 8);
+        next = next
+            | match (born, survives) {
+                (true, true) => matches,
+                (true, false) => matches & !alive,
+                _ => matches & alive,
+            };
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitline::Boundary;
+    use crate::grid::BitGrid;
+    use crate::test_util::pseudo_random_grid;
+
+    fn naive_life_step(grid: &[u64; 64], rule: LifeRule, boundary: Boundary) -> [u64; 64] {
+        let get = |r: isize, c: isize| -> bool {
+            let (r, c) = match boundary {
+                Boundary::Zero if !(0..64).contains(&r) || !(0..64).contains(&c) => return false,
+                _ => (r.rem_euclid(64) as usize, c.rem_euclid(64) as usize),
+            };
+            grid[r] & (1 << (63 - c)) != 0
+        };
+        let mut next = [0; 64];
+        for (r, row) in next.iter_mut().enumerate() {
+            for c in 0..64 {
+                let (r, c) = (r as isize, c as isize);
+                let mut neighbors = 0;
+                for dr in -1..=1 {
+                    for dc in -1..=1 {
+                        if (dr, dc) != (0, 0) && get(r + dr, c + dc) {
+                            neighbors += 1;
+                        }
+                    }
+                }
+                let alive = if get(r, c) {
+                    rule.survives(neighbors)
+                } else {
+                    rule.is_born(neighbors)
+                };
+                if alive {
+                    *row |= 1 << (63 - c);
+                }
+            }
+        }
+        next
+    }
+
+    #[test]
+    fn test_life_step_matches_naive() {
+        let mut state = 0x9e3779b97f4a7c15;
+        let rules = [
+            LifeRule::CONWAY,
+            LifeRule::HIGHLIFE,
+            LifeRule::SEEDS,
+            LifeRule::DAY_AND_NIGHT,
+            LifeRule::from_masks(0x1ff, 0x1ff),
+            LifeRule::from_masks(1, 1 << 8),
+        ];
+        for &rule in rules.iter() {
+            let mut grid = pseudo_random_grid::<u64, 64>(&mut state, 1);
+            for _ in 0..4 {
+                for &boundary in [Boundary::Zero, Boundary::Wrap].iter() {
+                    assert_eq!(
+                        grid.life_step(rule, boundary),
+                        naive_life_step(&grid, rule, boundary),
+                        "{:?} {:?}",
+                        rule,
+                        boundary
+                    );
+                }
+                grid = grid.life_step(rule, Boundary::Wrap);
+            }
+        }
+    }
+
+    #[test]
+    fn test_glider_on_torus() {
+        // A glider moves one cell diagonally every 4 generations, so on an 8×8 torus it
+        // returns to where it started after 32.
+        let glider: [u8; 8] = [0b01000000, 0b00100000, 0b11100000, 0, 0, 0, 0, 0];
+        let mut grid = glider;
+        for generation in 1..=32 {
+            grid = grid.life_step(LifeRule::CONWAY, Boundary::Wrap);
+            assert_eq!(grid.num_bits_2d(), 5);
+            if generation == 4 {
+                let moved: Vec<u8> = glider.iter().map(|row| row >> 1).collect();
+                assert_eq!(grid[1..], moved[..7]);
+            }
+        }
+        assert_eq!(grid, glider);
+    }
+
+    #[test]
+    fn test_still_life_and_edges() {
+        let block: [u16; 16] = [
+            0b11 << 14,
+            0b11 << 14,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+        ];
+        assert_eq!(block.life_step(LifeRule::CONWAY, Boundary::Zero), block);
+        // With a zero boundary the full grid keeps only its corners.
+        let full = [u32::MAX; 32];
+        let next = full.life_step(LifeRule::CONWAY, Boundary::Zero);
+        assert_eq!(next[0], 1 << 31 | 1);
+        assert_eq!(next[1], 0);
+        assert_eq!(next[31], 1 << 31 | 1);
+        assert_eq!(full.life_step(LifeRule::CONWAY, Boundary::Wrap), [0; 32]);
+    }
+
+    #[test]
+    fn test_rule() {
+        assert_eq!(LifeRule::new(&[3, 6], &[2, 3]), LifeRule::HIGHLIFE);
+        assert_eq!(LifeRule::from_masks(0xffff, 0).birth(), 0x1ff);
+        assert!(LifeRule::DAY_AND_NIGHT.survives(8));
+        assert!(!LifeRule::DAY_AND_NIGHT.survives(5));
+        assert!(!LifeRule::CONWAY.is_born(9));
+        assert_eq!(LifeRule::SEEDS.survival(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "neighbor count out of range")]
+    fn test_rule_out_of_range() {
+        LifeRule::new(&[9], &[]);
+    }
+}
@@ -0,0 +1,27 @@
+//! Two-dimensional neighborhood operations for square bit grids.
+//!
+//! A grid is one of the square row arrays of [`crate::matrix`]: `[u8; 8]`, `[u16; 16]`,
+//! `[u32; 32]` or `[u64; 64]`, or the packed [`Bitboard8x8`](crate::bitboard::Bitboard8x8).
+//! Element `i` is row `i` and, within a row, the MSB is column 0, following the same
+//! MSB-first convention as [`crate::bitline`].
+//!
+//! [`BitGrid`] extends the one-dimensional [`Bitline::around`](crate::bitline::Bitline::around)
+//! family to two dimensions. Distances are measured with a [`Metric`]: [`Metric::Chebyshev`]
+//! for the 8-neighborhood and [`Metric::Manhattan`] for the 4-neighborhood. Cells outside the
+//! grid are treated as unset; nothing wraps around an edge.
+//!
+//! [`BitGrid::components`] splits a grid into its 4- or 8-connected regions, and
+//! [`BitGrid::life_step`] advances a Life-like cellular automaton described by a
+//! [`LifeRule`] by one generation.
+//!
+//! See also: [`crate::bitboard`] for directional shifts on 8×8 and 16×16 boards.
+
+mod arrays;
+mod base;
+mod bitboard;
+mod components;
+mod life;
+// re-export
+pub use base::{BitGrid, Metric};
+pub use components::Components;
+pub use life::LifeRule;
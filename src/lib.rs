@@ -1,8 +1,30 @@
 // #![no_std]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod bitboard;
 pub mod bitline;
+pub mod bitplane;
+#[cfg(feature = "alloc")]
+pub mod bloom;
+pub mod codes;
+#[cfg(feature = "alloc")]
+pub mod compressed;
+pub mod crc;
+pub mod encoding;
+pub mod gf2;
+pub mod grid;
 pub mod matrix;
+#[cfg(feature = "alloc")]
+pub mod rle;
+pub mod search;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod stream;
+#[cfg(test)]
+pub(crate) mod test_util;
 
 #[cfg(test)]
 mod tests {
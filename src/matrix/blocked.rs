@@ -0,0 +1,222 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp;
+
+use super::transpose64x64_in_place;
+
+/// Return the number of `u64` words needed to store one row of `cols` bits.
+///
+/// This is the row stride used by [`transpose_blocked`] for both its input and its output.
+///
+/// # Examples
+/// ```
+/// use bittersweet::matrix::row_words;
+/// assert_eq!(row_words(0), 0);
+/// assert_eq!(row_words(1), 1);
+/// assert_eq!(row_words(64), 1);
+/// assert_eq!(row_words(65), 2);
+/// ```
+#[inline]
+pub fn row_words(cols: usize) -> usize {
+    (cols + 63) / 64
+}
+
+/// Transpose a `rows × cols` bit matrix stored row-major in `u64` words.
+///
+/// Row `i` of `input` occupies the [`row_words(cols)`](row_words) words starting at
+/// `input[i * row_words(cols)]`. Within a row, the MSB of the first word is column 0,
+/// following the [module-level convention](super). Bits past `cols` in the last word of
+/// each input row are ignored.
+///
+/// The transposed `cols × rows` matrix is written to `output` with the same layout, so each
+/// output row occupies `row_words(rows)` words. Every output word is overwritten, and bits past
+/// `rows` in the last word of each output row are cleared.
+///
+/// The matrix is processed in 64×64 tiles, each transposed with
+/// [`transpose64x64_in_place`](super::transpose64x64_in_place), which keeps
+/// the working set of every step within a single tile.
+///
+/// # Examples
+/// ```
+/// use bittersweet::matrix::transpose_blocked;
+/// // A 2×3 matrix:
+/// // 1 0 1
+/// // 0 1 1
+/// let input = [0b101 << 61, 0b011 << 61];
+/// let mut output = [0; 3];
+/// transpose_blocked(&input, 2, 3, &mut output);
+/// assert_eq!(output, [0b10 << 62, 0b01 << 62, 0b11 << 62]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `input.len()` is not `rows * row_words(cols)` or if `output.len()` is not
+/// `cols * row_words(rows)`.
+pub fn transpose_blocked(input: &[u64], rows: usize, cols: usize, output: &mut [u64]) {
+    let in_stride = row_words(cols);
+    let out_stride = row_words(rows);
+    assert!(
+        input.len() == rows * in_stride,
+        "input length does not match the matrix shape"
+    );
+    assert!(
+        output.len() == cols * out_stride,
+        "output length does not match the transposed matrix shape"
+    );
+
+    for block_row in 0..out_stride {
+        let row_base = block_row * 64;
+        let height = cmp::min(64, rows - row_base);
+        for block_col in 0..in_stride {
+            let col_base = block_col * 64;
+            let width = cmp::min(64, cols - col_base);
+
+            let mut tile = [0_u64; 64];
+            for (r, word) in tile.iter_mut().take(height).enumerate() {
+                *word = input[(row_base + r) * in_stride + block_col];
+            }
+            transpose64x64_in_place(&mut tile);
+            for (c, word) in tile.iter().take(width).enumerate() {
+                output[(col_base + c) * out_stride + block_row] = *word;
+            }
+        }
+    }
+}
+
+/// Transpose a `rows × cols` bit matrix into a newly allocated buffer.
+///
+/// This is a convenience wrapper around [`transpose_blocked`]; see it for the layout of
+/// `input` and of the returned matrix.
+///
+/// # Examples
+/// ```
+/// use bittersweet::matrix::transpose_blocked_vec;
+/// let input = [0b101 << 61, 0b011 << 61];
+/// assert_eq!(
+///     transpose_blocked_vec(&input, 2, 3),
+///     vec![0b10 << 62, 0b01 << 62, 0b11 << 62]
+/// );
+/// ```
+///
+/// # Panics
+///
+/// Panics if `input.len()` is not `rows * row_words(cols)`.
+pub fn transpose_blocked_vec(input: &[u64], rows: usize, cols: usize) -> Vec<u64> {
+    let mut output = vec![0; cols * row_words(rows)];
+    transpose_blocked(input, rows, cols, &mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::xorshift64;
+
+    fn get(matrix: &[u64], stride: usize, row: usize, col: usize) -> bool {
+        matrix[row * stride + col / 64] & (1 << (63 - col % 64)) != 0
+    }
+
+    fn naive_transpose(input: &[u64], rows: usize, cols: usize) -> Vec<u64> {
+        let in_stride = row_words(cols);
+        let out_stride = row_words(rows);
+        let mut output = vec![0; cols * out_stride];
+        for r in 0..rows {
+            for c in 0..cols {
+                if get(input, in_stride, r, c) {
+                    output[c * out_stride + r / 64] |= 1 << (63 - r % 64);
+                }
+            }
+        }
+        output
+    }
+
+    fn pseudo_random_matrix(rows: usize, cols: usize, seed: u64) -> Vec<u64> {
+        let stride = row_words(cols);
+        let mut state = seed;
+        let mut matrix = Vec::with_capacity(rows * stride);
+        for _ in 0..rows * stride {
+            matrix.push(xorshift64(&mut state));
+        }
+        // keep the padding bits clear so the matrix is canonical
+        if cols % 64 != 0 {
+            let mask = !0_u64 << (64 - cols % 64);
+            for r in 0..rows {
+                matrix[r * stride + stride - 1] &= mask;
+            }
+        }
+        matrix
+    }
+
+    #[test]
+    fn test_row_words() {
+        assert_eq!(row_words(0), 0);
+        assert_eq!(row_words(63), 1);
+        assert_eq!(row_words(64), 1);
+        assert_eq!(row_words(128), 2);
+        assert_eq!(row_words(129), 3);
+    }
+
+    #[test]
+    fn test_transpose_blocked_matches_naive() {
+        let shapes = [
+            (1, 1),
+            (1, 64),
+            (64, 1),
+            (3, 130),
+            (64, 64),
+            (65, 129),
+            (200, 64),
+            (64, 200),
+            (127, 191),
+        ];
+        for (i, &(rows, cols)) in shapes.iter().enumerate() {
+            let input = pseudo_random_matrix(rows, cols, 0x9e3779b97f4a7c15 + i as u64);
+            assert_eq!(
+                transpose_blocked_vec(&input, rows, cols),
+                naive_transpose(&input, rows, cols),
+                "shape {}x{}",
+                rows,
+                cols
+            );
+        }
+    }
+
+    #[test]
+    fn test_transpose_blocked_is_involution() {
+        let (rows, cols) = (150, 70);
+        let input = pseudo_random_matrix(rows, cols, 42);
+        let transposed = transpose_blocked_vec(&input, rows, cols);
+        assert_eq!(transpose_blocked_vec(&transposed, cols, rows), input);
+    }
+
+    #[test]
+    fn test_transpose_blocked_ignores_input_padding() {
+        // Column 3 is out of range; its bits must not leak into the output.
+        let input = [0b1111 << 60, 0b0001 << 60];
+        let mut output = [u64::MAX; 3];
+        transpose_blocked(&input, 2, 3, &mut output);
+        assert_eq!(output, [0b10 << 62, 0b10 << 62, 0b10 << 62]);
+    }
+
+    #[test]
+    fn test_transpose_blocked_empty() {
+        let mut output: [u64; 0] = [];
+        transpose_blocked(&[], 0, 0, &mut output);
+        transpose_blocked(&[], 0, 10, &mut output);
+        assert!(transpose_blocked_vec(&[], 10, 0).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "input length does not match")]
+    fn test_transpose_blocked_panics_on_short_input() {
+        let mut output = [0; 64];
+        transpose_blocked(&[0; 1], 2, 64, &mut output);
+    }
+
+    #[test]
+    #[should_panic(expected = "output length does not match")]
+    fn test_transpose_blocked_panics_on_short_output() {
+        let mut output = [0; 63];
+        transpose_blocked(&[0; 2], 2, 64, &mut output);
+    }
+}
@@ -0,0 +1,382 @@
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::bitline::Bitline;
+
+/// The two PBM (portable bitmap) encodings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PbmFormat {
+    /// `P1`: one `0` or `1` character per pixel.
+    Ascii,
+    /// `P4`: rows packed MSB-first into bytes, the same layout as the matrix rows.
+    Binary,
+}
+
+/// An error from [`read_pbm`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PbmError {
+    /// The input does not start with a `P1` or `P4` header with a width and height.
+    InvalidHeader,
+    /// The image width is not the row width, or its height is not the number of rows.
+    DimensionMismatch,
+    /// The input ends before the last pixel.
+    UnexpectedEnd,
+    /// A `P1` pixel is neither `0` nor `1`.
+    InvalidPixel,
+}
+
+impl fmt::Display for PbmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PbmError::InvalidHeader => f.write_str("invalid PBM header"),
+            PbmError::DimensionMismatch => {
+                f.write_str("PBM dimensions do not match the bit matrix")
+            }
+            PbmError::UnexpectedEnd => f.write_str("unexpected end of PBM data"),
+            PbmError::InvalidPixel => f.write_str("invalid pixel in PBM data"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PbmError {}
+
+/// The widest row any `Bitline` integer has, in bytes.
+const MAX_ROW_BYTES: usize = 16;
+
+/// Pixels per line of `P1` output, keeping lines within the 70 characters PBM allows.
+#[cfg(feature = "alloc")]
+const ASCII_LINE_PIXELS: usize = 64;
+
+/// Bytes per line of XBM output.
+#[cfg(feature = "alloc")]
+const XBM_LINE_BYTES: usize = 12;
+
+/// Append `rows` to `out` as a PBM image, one row per matrix row.
+///
+/// The image is `T::length()` pixels wide and `rows.len()` pixels high. Set bits are black
+/// pixels, and column 0 (the MSB of each row) is the left edge, so the image shows the
+/// matrix as the module documentation draws it. Bitboards can be written through their
+/// `to_rows`.
+///
+/// # Examples
+///
+/// ```
+/// use bittersweet::matrix::{write_pbm, PbmFormat};
+/// let mut out = Vec::new();
+/// write_pbm(&mut out, &[0b1000_0001_u8, 0b0100_0010], PbmFormat::Binary);
+/// assert_eq!(out, b"P4\n8 2\n\x81\x42");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn write_pbm<T: Bitline>(out: &mut Vec<u8>, rows: &[T], format: PbmFormat) {
+    let width = T::length();
+    let magic = match format {
+        PbmFormat::Ascii => "P1",
+        PbmFormat::Binary => "P4",
+    };
+    out.extend_from_slice(alloc::format!("{}\n{} {}\n", magic, width, rows.len()).as_bytes());
+    match format {
+        PbmFormat::Ascii => {
+            for row in rows {
+                for column in 0..width {
+                    out.push(if row.access(column) { b'1' } else { b'0' });
+                    if (column + 1) % ASCII_LINE_PIXELS == 0 || column + 1 == width {
+                        out.push(b'\n');
+                    }
+                }
+            }
+        }
+        PbmFormat::Binary => {
+            let mut bytes = [0; MAX_ROW_BYTES];
+            let bytes = &mut bytes[..T::bytes_length()];
+            for row in rows {
+                row.to_be_bit_bytes(bytes);
+                out.extend_from_slice(bytes);
+            }
+        }
+    }
+}
+
+/// Read a PBM image into `rows`.
+///
+/// Both `P1` and `P4` images are accepted, with comments in the header. The image must be
+/// exactly `T::length()` pixels wide and `rows.len()` pixels high; anything after the last
+/// pixel is ignored.
+///
+/// # Errors
+///
+/// Returns [`PbmError::InvalidHeader`] for a missing or malformed header,
+/// [`PbmError::DimensionMismatch`] when the image does not have the shape of `rows`,
+/// [`PbmError::UnexpectedEnd`] when pixels are missing and [`PbmError::InvalidPixel`] for
+/// a `P1` pixel other than `0` or `1`. `rows` may be partly overwritten on error.
+///
+/// # Examples
+///
+/// ```
+/// use bittersweet::matrix::read_pbm;
+/// let mut rows = [0_u8; 2];
+/// read_pbm(b"P1\n# two rows\n8 2\n10000001\n01000010\n", &mut rows).unwrap();
+/// assert_eq!(rows, [0b1000_0001, 0b0100_0010]);
+/// ```
+pub fn read_pbm<T: Bitline>(bytes: &[u8], rows: &mut [T]) -> Result<(), PbmError> {
+    let format = match bytes {
+        [b'P', b'1', ..] => PbmFormat::Ascii,
+        [b'P', b'4', ..] => PbmFormat::Binary,
+        _ => return Err(PbmError::InvalidHeader),
+    };
+    if !bytes.get(2).map_or(false, is_separator) {
+        return Err(PbmError::InvalidHeader);
+    }
+    let mut position = 2;
+    let width = read_header_number(bytes, &mut position)?;
+    let height = read_header_number(bytes, &mut position)?;
+    if width != T::length() || height != rows.len() {
+        return Err(PbmError::DimensionMismatch);
+    }
+    match format {
+        PbmFormat::Ascii => {
+            let mut row_bytes = [0; MAX_ROW_BYTES];
+            let row_bytes = &mut row_bytes[..T::bytes_length()];
+            for row in rows.iter_mut() {
+                row_bytes.fill(0);
+                for column in 0..width {
+                    skip_whitespace_and_comments(bytes, &mut position);
+                    match bytes.get(position) {
+                        Some(b'0') => {}
+                        Some(b'1') => row_bytes[column / 8] |= 0x80 >> (column % 8),
+                        Some(_) => return Err(PbmError::InvalidPixel),
+                        None => return Err(PbmError::UnexpectedEnd),
+                    }
+                    position += 1;
+                }
+                *row = T::from_be_bit_bytes(row_bytes);
+            }
+        }
+        PbmFormat::Binary => {
+            // A single whitespace byte separates the height from the raster.
+            if !bytes[position].is_ascii_whitespace() {
+                return Err(PbmError::InvalidHeader);
+            }
+            position += 1;
+            let row_bytes = T::bytes_length();
+            let raster = bytes.get(position..).unwrap_or(&[]);
+            if raster.len() < row_bytes * height {
+                return Err(PbmError::UnexpectedEnd);
+            }
+            for (row, chunk) in rows.iter_mut().zip(raster.chunks_exact(row_bytes)) {
+                *row = T::from_be_bit_bytes(chunk);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Render `rows` as an XBM (X bitmap) C source fragment named `name`.
+///
+/// The result declares `<name>_width`, `<name>_height` and the `<name>_bits` array. XBM
+/// packs each row's pixels least significant bit first, so every byte of the row is
+/// bit-reversed relative to [`Bitline::to_be_bit_bytes`]; the image itself shows column 0
+/// on the left, as PBM does.
+///
+/// # Examples
+///
+/// ```
+/// use bittersweet::matrix::to_xbm;
+/// assert_eq!(
+///     to_xbm(&[0b1100_0000_u8], "dot"),
+///     "#define dot_width 8\n#define dot_height 1\nstatic unsigned char dot_bits[] = {\n   0x03};\n"
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn to_xbm<T: Bitline>(rows: &[T], name: &str) -> String {
+    use core::fmt::Write;
+
+    let mut out = String::new();
+    // Writing into a `String` cannot fail.
+    let _ = write!(
+        out,
+        "#define {0}_width {1}\n#define {0}_height {2}\nstatic unsigned char {0}_bits[] = {{",
+        name,
+        T::length(),
+        rows.len()
+    );
+    let mut bytes = [0; MAX_ROW_BYTES];
+    let bytes = &mut bytes[..T::bytes_length()];
+    let mut count = 0;
+    for row in rows {
+        row.to_be_bit_bytes(bytes);
+        for byte in bytes.iter() {
+            out.push_str(if count == 0 {
+                "\n   "
+            } else if count % XBM_LINE_BYTES == 0 {
+                ",\n   "
+            } else {
+                ", "
+            });
+            let _ = write!(out, "0x{:02x}", byte.reverse_bits());
+            count += 1;
+        }
+    }
+    out.push_str("};\n");
+    out
+}
+
+/// Read a decimal header field, skipping the whitespace and comments before it.
+fn read_header_number(bytes: &[u8], position: &mut usize) -> Result<usize, PbmError> {
+    skip_whitespace_and_comments(bytes, position);
+    let start = *position;
+    let mut value: usize = 0;
+    while let Some(digit) = bytes.get(*position).filter(|byte| byte.is_ascii_digit()) {
+        value = value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(usize::from(digit - b'0')))
+            .ok_or(PbmError::InvalidHeader)?;
+        *position += 1;
+    }
+    if *position == start || !bytes.get(*position).map_or(false, is_separator) {
+        return Err(PbmError::InvalidHeader);
+    }
+    Ok(value)
+}
+
+/// Return whether `byte` may end a header field: whitespace or the start of a comment.
+fn is_separator(byte: &u8) -> bool {
+    byte.is_ascii_whitespace() || *byte == b'#'
+}
+
+/// Advance past whitespace and `#` comments, which run to the end of the line.
+fn skip_whitespace_and_comments(bytes: &[u8], position: &mut usize) {
+    while let Some(&byte) = bytes.get(*position) {
+        if byte == b'#' {
+            while bytes.get(*position).map_or(false, |&byte| byte != b'\n') {
+                *position += 1;
+            }
+        } else if byte.is_ascii_whitespace() {
+            *position += 1;
+        } else {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::xorshift64;
+    use alloc::vec;
+
+    macro_rules! assert_pbm_roundtrip {
+        ($T:ty, $N:literal) => {
+            let mut state = 0x2545_f491_4f6c_dd1d_u64;
+            let mut rows = [0 as $T; $N];
+            for row in rows.iter_mut() {
+                *row = (u128::from(xorshift64(&mut state)) << 64
+                    | u128::from(xorshift64(&mut state))) as $T;
+            }
+            for format in [PbmFormat::Ascii, PbmFormat::Binary] {
+                let mut out = Vec::new();
+                write_pbm(&mut out, &rows, format);
+                let mut decoded = [0 as $T; $N];
+                assert_eq!(read_pbm(&out, &mut decoded), Ok(()));
+                assert_eq!(decoded, rows);
+            }
+        };
+    }
+
+    #[test]
+    fn test_pbm_roundtrip() {
+        assert_pbm_roundtrip!(u8, 8);
+        assert_pbm_roundtrip!(u16, 16);
+        assert_pbm_roundtrip!(u32, 32);
+        assert_pbm_roundtrip!(u64, 64);
+        assert_pbm_roundtrip!(u128, 128);
+        assert_pbm_roundtrip!(u64, 3);
+    }
+
+    #[test]
+    fn test_write_pbm() {
+        let rows = [0b1010_0000_0000_0001_u16, 0x00ff];
+        let mut out = Vec::new();
+        write_pbm(&mut out, &rows, PbmFormat::Ascii);
+        assert_eq!(out, b"P1\n16 2\n1010000000000001\n0000000011111111\n");
+        let mut out = Vec::new();
+        write_pbm(&mut out, &rows, PbmFormat::Binary);
+        assert_eq!(out, b"P4\n16 2\n\xa0\x01\x00\xff");
+
+        let mut out = Vec::new();
+        write_pbm(&mut out, &[u128::MAX], PbmFormat::Ascii);
+        let lines: Vec<&[u8]> = out.split(|&byte| byte == b'\n').collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines.iter().all(|line| line.len() <= 70));
+    }
+
+    #[test]
+    fn test_read_pbm_layouts() {
+        let mut rows = [0_u8; 2];
+        let ascii = b"P1 # comment\n 8\n# another\n2 1 0 1 0 0 0 0 1\n0\n0 0 0 1 1 1 1";
+        assert_eq!(read_pbm(ascii, &mut rows), Ok(()));
+        assert_eq!(rows, [0b1010_0001, 0b0000_1111]);
+
+        let mut rows = [0_u8; 2];
+        assert_eq!(read_pbm(b"P4 8 2\t\x81\x0a trailing", &mut rows), Ok(()));
+        assert_eq!(rows, [0x81, 0x0a]);
+    }
+
+    #[test]
+    fn test_read_pbm_errors() {
+        let mut rows = [0_u8; 2];
+        for input in [
+            &b""[..],
+            b"P2\n8 2\n",
+            b"P1",
+            b"P18 2\n",
+            b"P1\n8x 2\n",
+            b"P1\n8 2",
+            b"P1\n99999999999999999999999 2\n",
+        ] {
+            assert_eq!(read_pbm(input, &mut rows), Err(PbmError::InvalidHeader));
+        }
+        assert_eq!(
+            read_pbm(b"P1\n16 2\n", &mut rows),
+            Err(PbmError::DimensionMismatch)
+        );
+        assert_eq!(
+            read_pbm(b"P4\n8 3\n\0\0\0", &mut rows),
+            Err(PbmError::DimensionMismatch)
+        );
+        assert_eq!(
+            read_pbm(b"P1\n8 2\n00000000\n0000000", &mut rows),
+            Err(PbmError::UnexpectedEnd)
+        );
+        assert_eq!(
+            read_pbm(b"P4\n8 2\n\0", &mut rows),
+            Err(PbmError::UnexpectedEnd)
+        );
+        assert_eq!(
+            read_pbm(b"P1\n8 2\n00000002\n", &mut rows),
+            Err(PbmError::InvalidPixel)
+        );
+        assert_eq!(
+            PbmError::DimensionMismatch.to_string(),
+            "PBM dimensions do not match the bit matrix"
+        );
+    }
+
+    #[test]
+    fn test_to_xbm() {
+        let rows = vec![0x8001_u16; 7];
+        let xbm = to_xbm(&rows, "grid");
+        assert_eq!(
+            xbm,
+            "#define grid_width 16\n\
+             #define grid_height 7\n\
+             static unsigned char grid_bits[] = {\n   \
+             0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80,\n   \
+             0x01, 0x80};\n"
+        );
+    }
+}
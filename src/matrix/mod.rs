@@ -16,8 +16,69 @@
 //! For an 8×8 matrix, `row[0] = 0b10000000u8` means only column 0 of row 0 is set.
 //! After transposition, only row 0 of column 0 is set, so `result[0] = 0b10000000u8`.
 //!
+//! # Rotations and reflections
+//!
+//! Row 0 is the top of the matrix and column 0 is its left edge. Besides transposition
+//! (reflection across the main diagonal), every square size provides `flip_vertical*`,
+//! `flip_horizontal*`, `flip_anti_diagonal*`, `rotate90_cw_*`, `rotate90_ccw_*` and
+//! `rotate180_*`, together with `*8x8_u64` counterparts for the packed 8×8 form.
+//!
+//! # Compile-time and in-place transposition
+//!
+//! The by-value transposes are `const fn`, so constant tables can be transposed at compile
+//! time. Each array transpose also has a `*_in_place` variant taking `&mut [T; N]`, which
+//! avoids copying the matrix on and off the stack. The in-place variants cannot be `const fn`
+//! because mutable references in constant functions require a newer Rust than the crate's
+//! minimum supported version.
+//!
+//! On `x86_64`, [`transpose64x64_in_place`] and [`transpose128x128_in_place`] use SSE2, or
+//! AVX2 when the CPU supports it, and produce exactly the same result as the portable
+//! `const fn` transposes. AVX2 is detected at run time with the `std` feature and at compile
+//! time (`-C target-feature=+avx2`) without it. These kernels are not truly in place: they
+//! collect the columns into a stack buffer the size of the matrix and copy it back at the end.
+//!
+//! # Matrix–vector products
+//!
+//! `mat_vec_gf2_*` and `vec_mat_gf2_*` multiply a square matrix by a column or row vector over
+//! GF(2) (AND for products, XOR for sums); the `*_bool_*` variants use the boolean semiring
+//! (AND and OR). Vectors use the same MSB-first indexing as rows. With the `alloc` feature,
+//! [`CompiledMatrix64`] precomputes lookup tables for applying one 64×64 matrix many times.
+//!
+//! # Images
+//!
+//! [`read_pbm`] reads `P1` (ASCII) and `P4` (binary) PBM images into row arrays, and with the
+//! `alloc` feature [`write_pbm`] writes them and [`to_xbm`] renders XBM source. `P4` packs
+//! each row MSB-first, exactly as the rows are stored, so a matrix can be inspected in any
+//! image viewer with column 0 on the left.
+//!
+//! # Large matrices
+//!
+//! With the `alloc` feature, [`transpose_blocked`] transposes matrices of arbitrary
+//! size stored row-major in `u64` words by tiling them into 64×64 blocks.
+//!
 //! See also: [`crate::bitline`] for bit-manipulation predicates over the same integer types.
 
+#[cfg(feature = "alloc")]
+mod blocked;
+mod image;
+mod product;
+#[cfg(target_arch = "x86_64")]
+mod x86;
+#[cfg(feature = "alloc")]
+pub use blocked::{row_words, transpose_blocked, transpose_blocked_vec};
+pub use image::{read_pbm, PbmError, PbmFormat};
+#[cfg(feature = "alloc")]
+pub use image::{to_xbm, write_pbm};
+#[cfg(feature = "alloc")]
+pub use product::CompiledMatrix64;
+pub use product::{
+    mat_vec_bool_128x128, mat_vec_bool_16x16, mat_vec_bool_32x32, mat_vec_bool_64x64,
+    mat_vec_bool_8x8, mat_vec_gf2_128x128, mat_vec_gf2_16x16, mat_vec_gf2_32x32, mat_vec_gf2_64x64,
+    mat_vec_gf2_8x8, vec_mat_bool_128x128, vec_mat_bool_16x16, vec_mat_bool_32x32,
+    vec_mat_bool_64x64, vec_mat_bool_8x8, vec_mat_gf2_128x128, vec_mat_gf2_16x16,
+    vec_mat_gf2_32x32, vec_mat_gf2_64x64, vec_mat_gf2_8x8,
+};
+
 /// Transpose an 8×8 bit matrix packed into a single `u64`.
 ///
 /// The matrix is packed in big-endian byte order: the most-significant byte is row 0,
@@ -27,7 +88,7 @@
 /// from a `[u8; 8]` array. The two functions operate on the same logical bit matrix;
 /// to convert between representations use `u64::from_be_bytes` / `u64::to_be_bytes`.
 #[inline]
-pub fn transpose8x8_u64(n: u64) -> u64 {
+pub const fn transpose8x8_u64(n: u64) -> u64 {
     let m = (n ^ (n >> 7)) & 0x00aa00aa00aa00aa;
     let n = n ^ m ^ (m << 7);
     let m = (n ^ (n >> 14)) & 0x0000cccc0000cccc;
@@ -37,127 +98,343 @@ pub fn transpose8x8_u64(n: u64) -> u64 {
     n ^ m ^ (m << 28)
 }
 
+/// Swap the off-diagonal blocks of a square bit matrix in place, halving the block size
+/// each round until single bits have been exchanged.
+///
+/// `$a` may be an array or a mutable reference to one, so the same kernel backs both the
+/// `const fn` by-value transposes and the `*_in_place` variants.
+macro_rules! transpose_kernel {
+    ($a:ident, $T:ty, $N:literal) => {
+        let mut j: usize = $N / 2;
+        let mut k;
+        let mut m: $T = <$T>::MAX >> ($N / 2);
+        let mut t: $T;
+        while j != 0 {
+            k = 0;
+            while k < $N {
+                t = ($a[k] ^ ($a[k | j] >> j)) & m;
+                $a[k] ^= t;
+                $a[k | j] ^= t << j;
+                k = ((k | j) + 1) & !j;
+            }
+            j >>= 1;
+            m ^= m << j;
+        }
+    };
+}
+
 /// Transpose an 8×8 bit matrix represented as an array of 8 bytes.
 ///
 /// Element `a[i]` is row `i`. Within each byte, MSB = column 0.
 ///
+/// This is a `const fn`, so lookup tables can be transposed at compile time.
 /// See the [module-level documentation](self) for the full bit representation convention.
-/// For a packed `u64` alternative see [`transpose8x8_u64`].
-pub fn transpose8x8(mut a: [u8; 8]) -> [u8; 8] {
-    let mut j: usize = 4;
-    let mut k;
-    let mut m: u8 = 0x0F;
-    let mut t: u8;
-    while j != 0 {
-        k = 0;
-        while k < 8 {
-            t = (a[k] ^ (a[k | j] >> j)) & m;
-            a[k] ^= t;
-            a[k | j] ^= t << j;
-            k = ((k | j) + 1) & !j;
-        }
-        j >>= 1;
-        m ^= m << j;
-    }
+/// For a packed `u64` alternative see [`transpose8x8_u64`], and to transpose without
+/// copying the array see [`transpose8x8_in_place`].
+pub const fn transpose8x8(mut a: [u8; 8]) -> [u8; 8] {
+    transpose_kernel!(a, u8, 8);
     a
 }
 
+/// Transpose an 8×8 bit matrix represented as an array of 8 bytes, in place.
+///
+/// This is the in-place counterpart of [`transpose8x8`].
+///
+/// # Examples
+/// ```
+/// use bittersweet::matrix::{transpose8x8, transpose8x8_in_place};
+/// let a = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0];
+/// let mut b = a;
+/// transpose8x8_in_place(&mut b);
+/// assert_eq!(b, transpose8x8(a));
+/// ```
+pub fn transpose8x8_in_place(a: &mut [u8; 8]) {
+    transpose_kernel!(a, u8, 8);
+}
+
 /// Transpose a 16×16 bit matrix represented as an array of 16 `u16` values.
 ///
 /// Element `a[i]` is row `i`. Within each `u16`, MSB = column 0.
 ///
+/// This is a `const fn`, so lookup tables can be transposed at compile time.
 /// See the [module-level documentation](self) for the full bit representation convention.
-pub fn transpose16x16(mut a: [u16; 16]) -> [u16; 16] {
-    let mut j: usize = 8;
-    let mut k;
-    let mut m: u16 = 0x00FF;
-    let mut t: u16;
-    while j != 0 {
-        k = 0;
-        while k < 16 {
-            t = (a[k] ^ (a[k | j] >> j)) & m;
-            a[k] ^= t;
-            a[k | j] ^= t << j;
-            k = ((k | j) + 1) & !j;
-        }
-        j >>= 1;
-        m ^= m << j;
-    }
+pub const fn transpose16x16(mut a: [u16; 16]) -> [u16; 16] {
+    transpose_kernel!(a, u16, 16);
     a
 }
 
+/// Transpose a 16×16 bit matrix represented as an array of 16 `u16` values, in place.
+///
+/// This is the in-place counterpart of [`transpose16x16`].
+pub fn transpose16x16_in_place(a: &mut [u16; 16]) {
+    transpose_kernel!(a, u16, 16);
+}
+
 /// Transpose a 32×32 bit matrix represented as an array of 32 `u32` values.
 ///
 /// Element `a[i]` is row `i`. Within each `u32`, MSB = column 0.
 ///
+/// This is a `const fn`, so lookup tables can be transposed at compile time.
 /// See the [module-level documentation](self) for the full bit representation convention.
-pub fn transpose32x32(mut a: [u32; 32]) -> [u32; 32] {
-    let mut j: usize = 16;
-    let mut k;
-    let mut m: u32 = 0x0000FFFF;
-    let mut t: u32;
-    while j != 0 {
-        k = 0;
-        while k < 32 {
-            t = (a[k] ^ (a[k | j] >> j)) & m;
-            a[k] ^= t;
-            a[k | j] ^= t << j;
-            k = ((k | j) + 1) & !j;
-        }
-        j >>= 1;
-        m ^= m << j;
-    }
+pub const fn transpose32x32(mut a: [u32; 32]) -> [u32; 32] {
+    transpose_kernel!(a, u32, 32);
     a
 }
 
+/// Transpose a 32×32 bit matrix represented as an array of 32 `u32` values, in place.
+///
+/// This is the in-place counterpart of [`transpose32x32`].
+pub fn transpose32x32_in_place(a: &mut [u32; 32]) {
+    transpose_kernel!(a, u32, 32);
+}
+
 /// Transpose a 64×64 bit matrix represented as an array of 64 `u64` values.
 ///
 /// Element `a[i]` is row `i`. Within each `u64`, MSB = column 0.
 ///
+/// This is a `const fn`, so lookup tables can be transposed at compile time.
 /// See the [module-level documentation](self) for the full bit representation convention.
-pub fn transpose64x64(mut a: [u64; 64]) -> [u64; 64] {
-    let mut j: usize = 32;
-    let mut k;
-    let mut m: u64 = 0x00000000FFFFFFFF;
-    let mut t: u64;
-    while j != 0 {
-        k = 0;
-        while k < 64 {
-            t = (a[k] ^ (a[k | j] >> j)) & m;
-            a[k] ^= t;
-            a[k | j] ^= t << j;
-            k = ((k | j) + 1) & !j;
-        }
-        j >>= 1;
-        m ^= m << j;
-    }
+pub const fn transpose64x64(mut a: [u64; 64]) -> [u64; 64] {
+    transpose_kernel!(a, u64, 64);
     a
 }
 
+/// Transpose a 64×64 bit matrix represented as an array of 64 `u64` values, in place.
+///
+/// This is the in-place counterpart of [`transpose64x64`]. On `x86_64` it runs an SSE2 or
+/// AVX2 kernel, falling back to the portable algorithm of [`transpose64x64`] elsewhere.
+///
+/// The `x86_64` kernel builds the result in a stack buffer and copies it back into `a`, so it
+/// still performs one full-matrix copy; the portable path swaps bits within `a` directly.
+pub fn transpose64x64_in_place(a: &mut [u64; 64]) {
+    #[cfg(target_arch = "x86_64")]
+    x86::transpose64x64_in_place(a);
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        transpose_kernel!(a, u64, 64);
+    }
+}
+
 /// Transpose a 128×128 bit matrix represented as an array of 128 `u128` values.
 ///
 /// Element `a[i]` is row `i`. Within each `u128`, MSB = column 0.
 ///
+/// This is a `const fn`, so lookup tables can be transposed at compile time.
 /// See the [module-level documentation](self) for the full bit representation convention.
-pub fn transpose128x128(mut a: [u128; 128]) -> [u128; 128] {
-    let mut j: usize = 64;
-    let mut k;
-    let mut m: u128 = 0x0000000000000000FFFFFFFFFFFFFFFF;
-    let mut t: u128;
-    while j != 0 {
-        k = 0;
-        while k < 128 {
-            t = (a[k] ^ (a[k | j] >> j)) & m;
-            a[k] ^= t;
-            a[k | j] ^= t << j;
-            k = ((k | j) + 1) & !j;
-        }
-        j >>= 1;
-        m ^= m << j;
-    }
+/// The array is 2 KiB; prefer [`transpose128x128_in_place`] at run time to avoid copying it.
+pub const fn transpose128x128(mut a: [u128; 128]) -> [u128; 128] {
+    transpose_kernel!(a, u128, 128);
     a
 }
 
+/// Transpose a 128×128 bit matrix represented as an array of 128 `u128` values, in place.
+///
+/// This is the in-place counterpart of [`transpose128x128`]. On `x86_64` it runs an SSE2 or
+/// AVX2 kernel, falling back to the portable algorithm of [`transpose128x128`] elsewhere.
+///
+/// The `x86_64` kernel builds the result in a stack buffer and copies it back into `a`, so it
+/// still performs one full-matrix copy; the portable path swaps bits within `a` directly.
+pub fn transpose128x128_in_place(a: &mut [u128; 128]) {
+    #[cfg(target_arch = "x86_64")]
+    x86::transpose128x128_in_place(a);
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        transpose_kernel!(a, u128, 128);
+    }
+}
+
+macro_rules! impl_square_symmetries {
+    (
+        $T:ty, $N:literal, $size:literal, $transpose:ident,
+        $flip_vertical:ident, $flip_horizontal:ident, $flip_anti_diagonal:ident,
+        $rotate90_cw:ident, $rotate90_ccw:ident, $rotate180:ident
+    ) => {
+        #[doc = concat!("Flip a ", $size, " bit matrix upside down (reverse the row order).")]
+        ///
+        /// Row `i` of the result is row `N - 1 - i` of `a`.
+        /// See the [module-level documentation](self) for the orientation convention.
+        pub const fn $flip_vertical(mut a: [$T; $N]) -> [$T; $N] {
+            let mut i = 0;
+            while i < $N / 2 {
+                let t = a[i];
+                a[i] = a[$N - 1 - i];
+                a[$N - 1 - i] = t;
+                i += 1;
+            }
+            a
+        }
+
+        #[doc = concat!("Mirror a ", $size, " bit matrix left to right (reverse every row).")]
+        ///
+        /// Column `j` of the result is column `N - 1 - j` of `a`.
+        /// See the [module-level documentation](self) for the orientation convention.
+        pub const fn $flip_horizontal(mut a: [$T; $N]) -> [$T; $N] {
+            let mut i = 0;
+            while i < $N {
+                a[i] = a[i].reverse_bits();
+                i += 1;
+            }
+            a
+        }
+
+        #[doc = concat!("Reflect a ", $size, " bit matrix across its anti-diagonal.")]
+        ///
+        /// The bit at `(row, col)` moves to `(N - 1 - col, N - 1 - row)`. Reflecting across the
+        #[doc = concat!("main diagonal is [`", stringify!($transpose), "`].")]
+        pub const fn $flip_anti_diagonal(a: [$T; $N]) -> [$T; $N] {
+            $rotate180($transpose(a))
+        }
+
+        #[doc = concat!("Rotate a ", $size, " bit matrix by 90° clockwise.")]
+        ///
+        /// The bit at `(row, col)` moves to `(col, N - 1 - row)`, so row 0 becomes the
+        /// rightmost column.
+        pub const fn $rotate90_cw(a: [$T; $N]) -> [$T; $N] {
+            $flip_horizontal($transpose(a))
+        }
+
+        #[doc = concat!("Rotate a ", $size, " bit matrix by 90° counterclockwise.")]
+        ///
+        /// The bit at `(row, col)` moves to `(N - 1 - col, row)`, so row 0 becomes the
+        /// leftmost column.
+        pub const fn $rotate90_ccw(a: [$T; $N]) -> [$T; $N] {
+            $flip_vertical($transpose(a))
+        }
+
+        #[doc = concat!("Rotate a ", $size, " bit matrix by 180°.")]
+        ///
+        /// The bit at `(row, col)` moves to `(N - 1 - row, N - 1 - col)`.
+        pub const fn $rotate180(a: [$T; $N]) -> [$T; $N] {
+            $flip_horizontal($flip_vertical(a))
+        }
+    };
+}
+
+impl_square_symmetries!(
+    u8,
+    8,
+    "8×8",
+    transpose8x8,
+    flip_vertical8x8,
+    flip_horizontal8x8,
+    flip_anti_diagonal8x8,
+    rotate90_cw_8x8,
+    rotate90_ccw_8x8,
+    rotate180_8x8
+);
+impl_square_symmetries!(
+    u16,
+    16,
+    "16×16",
+    transpose16x16,
+    flip_vertical16x16,
+    flip_horizontal16x16,
+    flip_anti_diagonal16x16,
+    rotate90_cw_16x16,
+    rotate90_ccw_16x16,
+    rotate180_16x16
+);
+impl_square_symmetries!(
+    u32,
+    32,
+    "32×32",
+    transpose32x32,
+    flip_vertical32x32,
+    flip_horizontal32x32,
+    flip_anti_diagonal32x32,
+    rotate90_cw_32x32,
+    rotate90_ccw_32x32,
+    rotate180_32x32
+);
+impl_square_symmetries!(
+    u64,
+    64,
+    "64×64",
+    transpose64x64,
+    flip_vertical64x64,
+    flip_horizontal64x64,
+    flip_anti_diagonal64x64,
+    rotate90_cw_64x64,
+    rotate90_ccw_64x64,
+    rotate180_64x64
+);
+impl_square_symmetries!(
+    u128,
+    128,
+    "128×128",
+    transpose128x128,
+    flip_vertical128x128,
+    flip_horizontal128x128,
+    flip_anti_diagonal128x128,
+    rotate90_cw_128x128,
+    rotate90_ccw_128x128,
+    rotate180_128x128
+);
+
+/// Flip an 8×8 bit matrix packed into a `u64` upside down (reverse the row order).
+///
+/// This is the packed counterpart of [`flip_vertical8x8`]; rows are bytes, so it is a byte swap.
+#[inline]
+pub const fn flip_vertical8x8_u64(n: u64) -> u64 {
+    n.swap_bytes()
+}
+
+/// Mirror an 8×8 bit matrix packed into a `u64` left to right (reverse every row).
+///
+/// This is the packed counterpart of [`flip_horizontal8x8`].
+#[inline]
+pub const fn flip_horizontal8x8_u64(n: u64) -> u64 {
+    n.reverse_bits().swap_bytes()
+}
+
+/// Reflect an 8×8 bit matrix packed into a `u64` across its anti-diagonal.
+///
+/// This is the packed counterpart of [`flip_anti_diagonal8x8`]. Reflecting across the
+/// main diagonal is [`transpose8x8_u64`].
+#[inline]
+pub const fn flip_anti_diagonal8x8_u64(n: u64) -> u64 {
+    rotate180_8x8_u64(transpose8x8_u64(n))
+}
+
+/// Rotate an 8×8 bit matrix packed into a `u64` by 90° clockwise.
+///
+/// This is the packed counterpart of [`rotate90_cw_8x8`].
+///
+/// # Examples
+/// ```
+/// use bittersweet::matrix::rotate90_cw_8x8_u64;
+/// // Row 0 becomes the rightmost column.
+/// assert_eq!(rotate90_cw_8x8_u64(0xff00000000000000), 0x0101010101010101);
+/// ```
+#[inline]
+pub const fn rotate90_cw_8x8_u64(n: u64) -> u64 {
+    flip_horizontal8x8_u64(transpose8x8_u64(n))
+}
+
+/// Rotate an 8×8 bit matrix packed into a `u64` by 90° counterclockwise.
+///
+/// This is the packed counterpart of [`rotate90_ccw_8x8`].
+///
+/// # Examples
+/// ```
+/// use bittersweet::matrix::rotate90_ccw_8x8_u64;
+/// // Row 0 becomes the leftmost column.
+/// assert_eq!(rotate90_ccw_8x8_u64(0xff00000000000000), 0x8080808080808080);
+/// ```
+#[inline]
+pub const fn rotate90_ccw_8x8_u64(n: u64) -> u64 {
+    flip_vertical8x8_u64(transpose8x8_u64(n))
+}
+
+/// Rotate an 8×8 bit matrix packed into a `u64` by 180°.
+///
+/// This is the packed counterpart of [`rotate180_8x8`]; reversing all 64 bits reverses both
+/// the row order and every row.
+#[inline]
+pub const fn rotate180_8x8_u64(n: u64) -> u64 {
+    n.reverse_bits()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -466,4 +743,303 @@ mod tests {
         assert_ne!(transpose128x128(a), a);
         assert_eq!(transpose128x128(transpose128x128(a)), a);
     }
+
+    #[test]
+    fn test_transpose_const_evaluation() {
+        const PACKED: u64 = transpose8x8_u64(0x8040201008040201);
+        const ROWS: [u8; 8] = transpose8x8([0xff, 0, 0, 0, 0, 0, 0, 0]);
+        const WIDE: [u128; 128] = transpose128x128([u128::MAX; 128]);
+        assert_eq!(PACKED, 0x8040201008040201);
+        assert_eq!(ROWS, [0x80; 8]);
+        assert_eq!(WIDE, [u128::MAX; 128]);
+    }
+
+    #[test]
+    fn test_transpose_in_place_matches_by_value() {
+        macro_rules! assert_in_place_matches {
+            ($T:ty, $N:literal, $by_value:ident, $in_place:ident) => {{
+                let mut a = [0 as $T; $N];
+                for (i, row) in a.iter_mut().enumerate() {
+                    *row = (0x0123456789abcdef_fedcba9876543210_u128 as $T)
+                        .rotate_left(i as u32)
+                        .wrapping_mul(i as $T | 1);
+                }
+                let mut b = a;
+                $in_place(&mut b);
+                assert_eq!(b, $by_value(a));
+                $in_place(&mut b);
+                assert_eq!(b, a);
+            }};
+        }
+
+        assert_in_place_matches!(u8, 8, transpose8x8, transpose8x8_in_place);
+        assert_in_place_matches!(u16, 16, transpose16x16, transpose16x16_in_place);
+        assert_in_place_matches!(u32, 32, transpose32x32, transpose32x32_in_place);
+        assert_in_place_matches!(u64, 64, transpose64x64, transpose64x64_in_place);
+        assert_in_place_matches!(u128, 128, transpose128x128, transpose128x128_in_place);
+    }
+
+    #[test]
+    fn test_symmetries_8x8() {
+        // An "F" glyph: asymmetric under every non-trivial rotation and reflection.
+        let glyph: [u8; 8] = [
+            0b11111100, //
+            0b11000000, //
+            0b11000000, //
+            0b11111000, //
+            0b11000000, //
+            0b11000000, //
+            0b11000000, //
+            0b00000000, //
+        ];
+        assert_eq!(
+            rotate90_cw_8x8(glyph),
+            [
+                0b01111111, //
+                0b01111111, //
+                0b00001001, //
+                0b00001001, //
+                0b00001001, //
+                0b00000001, //
+                0b00000000, //
+                0b00000000, //
+            ]
+        );
+        assert_eq!(
+            rotate90_ccw_8x8(glyph),
+            [
+                0b00000000, //
+                0b00000000, //
+                0b10000000, //
+                0b10010000, //
+                0b10010000, //
+                0b10010000, //
+                0b11111110, //
+                0b11111110, //
+            ]
+        );
+        assert_eq!(
+            flip_horizontal8x8(glyph),
+            [
+                0b00111111, //
+                0b00000011, //
+                0b00000011, //
+                0b00011111, //
+                0b00000011, //
+                0b00000011, //
+                0b00000011, //
+                0b00000000, //
+            ]
+        );
+        assert_eq!(flip_vertical8x8(glyph)[0], 0b00000000);
+        assert_eq!(flip_vertical8x8(glyph)[7], 0b11111100);
+    }
+
+    #[test]
+    fn test_symmetries_match_bitwise_definition() {
+        macro_rules! assert_symmetries_match_definition {
+            ($T:ty, $N:literal, $flip_vertical:ident, $flip_horizontal:ident,
+             $flip_anti_diagonal:ident, $rotate90_cw:ident, $rotate90_ccw:ident,
+             $rotate180:ident) => {{
+                let mut a = [0 as $T; $N];
+                for (i, row) in a.iter_mut().enumerate() {
+                    *row = (0x9e3779b97f4a7c15_f39cc0605cedc834_u128 as $T)
+                        .rotate_left(3 * i as u32)
+                        .wrapping_mul(i as $T | 1);
+                }
+                let get = |m: &[$T; $N], row: usize, col: usize| (m[row] >> ($N - 1 - col)) & 1;
+                let n = $N - 1;
+                let (fv, fh, fa) = (
+                    $flip_vertical(a),
+                    $flip_horizontal(a),
+                    $flip_anti_diagonal(a),
+                );
+                let (cw, ccw, r180) = ($rotate90_cw(a), $rotate90_ccw(a), $rotate180(a));
+                for row in 0..$N {
+                    for col in 0..$N {
+                        let bit = get(&a, row, col);
+                        assert_eq!(get(&fv, n - row, col), bit);
+                        assert_eq!(get(&fh, row, n - col), bit);
+                        assert_eq!(get(&fa, n - col, n - row), bit);
+                        assert_eq!(get(&cw, col, n - row), bit);
+                        assert_eq!(get(&ccw, n - col, row), bit);
+                        assert_eq!(get(&r180, n - row, n - col), bit);
+                    }
+                }
+            }};
+        }
+
+        assert_symmetries_match_definition!(
+            u8,
+            8,
+            flip_vertical8x8,
+            flip_horizontal8x8,
+            flip_anti_diagonal8x8,
+            rotate90_cw_8x8,
+            rotate90_ccw_8x8,
+            rotate180_8x8
+        );
+        assert_symmetries_match_definition!(
+            u16,
+            16,
+            flip_vertical16x16,
+            flip_horizontal16x16,
+            flip_anti_diagonal16x16,
+            rotate90_cw_16x16,
+            rotate90_ccw_16x16,
+            rotate180_16x16
+        );
+        assert_symmetries_match_definition!(
+            u32,
+            32,
+            flip_vertical32x32,
+            flip_horizontal32x32,
+            flip_anti_diagonal32x32,
+            rotate90_cw_32x32,
+            rotate90_ccw_32x32,
+            rotate180_32x32
+        );
+        assert_symmetries_match_definition!(
+            u64,
+            64,
+            flip_vertical64x64,
+            flip_horizontal64x64,
+            flip_anti_diagonal64x64,
+            rotate90_cw_64x64,
+            rotate90_ccw_64x64,
+            rotate180_64x64
+        );
+        assert_symmetries_match_definition!(
+            u128,
+            128,
+            flip_vertical128x128,
+            flip_horizontal128x128,
+            flip_anti_diagonal128x128,
+            rotate90_cw_128x128,
+            rotate90_ccw_128x128,
+            rotate180_128x128
+        );
+    }
+
+    #[test]
+    fn test_symmetry_group_identities() {
+        macro_rules! assert_group_identities {
+            ($T:ty, $N:literal, $transpose:ident, $flip_vertical:ident, $flip_horizontal:ident,
+             $flip_anti_diagonal:ident, $rotate90_cw:ident, $rotate90_ccw:ident,
+             $rotate180:ident) => {{
+                let mut a = [0 as $T; $N];
+                for (i, row) in a.iter_mut().enumerate() {
+                    *row = (0x0123456789abcdef_fedcba9876543210_u128 as $T)
+                        .rotate_left(i as u32)
+                        .wrapping_mul(i as $T | 1);
+                }
+                // four quarter turns are the identity
+                assert_eq!($rotate90_cw($rotate90_cw($rotate90_cw($rotate90_cw(a)))), a);
+                assert_eq!(
+                    $rotate90_ccw($rotate90_ccw($rotate90_ccw($rotate90_ccw(a)))),
+                    a
+                );
+                // clockwise and counterclockwise cancel out
+                assert_eq!($rotate90_ccw($rotate90_cw(a)), a);
+                assert_eq!($rotate90_cw($rotate90_ccw(a)), a);
+                // two quarter turns are a half turn
+                assert_eq!($rotate90_cw($rotate90_cw(a)), $rotate180(a));
+                assert_eq!($rotate90_ccw($rotate90_ccw(a)), $rotate180(a));
+                // reflections are involutions
+                assert_eq!($flip_vertical($flip_vertical(a)), a);
+                assert_eq!($flip_horizontal($flip_horizontal(a)), a);
+                assert_eq!($flip_anti_diagonal($flip_anti_diagonal(a)), a);
+                assert_eq!($rotate180($rotate180(a)), a);
+                // composing two reflections gives a rotation
+                assert_eq!($flip_horizontal($flip_vertical(a)), $rotate180(a));
+                assert_eq!($flip_anti_diagonal($transpose(a)), $rotate180(a));
+                assert_eq!($flip_horizontal($transpose(a)), $rotate90_cw(a));
+                assert_eq!($flip_vertical($transpose(a)), $rotate90_ccw(a));
+            }};
+        }
+
+        assert_group_identities!(
+            u8,
+            8,
+            transpose8x8,
+            flip_vertical8x8,
+            flip_horizontal8x8,
+            flip_anti_diagonal8x8,
+            rotate90_cw_8x8,
+            rotate90_ccw_8x8,
+            rotate180_8x8
+        );
+        assert_group_identities!(
+            u16,
+            16,
+            transpose16x16,
+            flip_vertical16x16,
+            flip_horizontal16x16,
+            flip_anti_diagonal16x16,
+            rotate90_cw_16x16,
+            rotate90_ccw_16x16,
+            rotate180_16x16
+        );
+        assert_group_identities!(
+            u32,
+            32,
+            transpose32x32,
+            flip_vertical32x32,
+            flip_horizontal32x32,
+            flip_anti_diagonal32x32,
+            rotate90_cw_32x32,
+            rotate90_ccw_32x32,
+            rotate180_32x32
+        );
+        assert_group_identities!(
+            u64,
+            64,
+            transpose64x64,
+            flip_vertical64x64,
+            flip_horizontal64x64,
+            flip_anti_diagonal64x64,
+            rotate90_cw_64x64,
+            rotate90_ccw_64x64,
+            rotate180_64x64
+        );
+        assert_group_identities!(
+            u128,
+            128,
+            transpose128x128,
+            flip_vertical128x128,
+            flip_horizontal128x128,
+            flip_anti_diagonal128x128,
+            rotate90_cw_128x128,
+            rotate90_ccw_128x128,
+            rotate180_128x128
+        );
+    }
+
+    #[test]
+    fn test_symmetries_8x8_u64_match_arrays() {
+        let cases = [
+            0x123456789abcdef0_u64,
+            0xfcc0c0f8c0c0c000,
+            0x8040201008040201,
+            1,
+        ];
+        for &n in cases.iter() {
+            let rows = n.to_be_bytes();
+            let check = |packed: u64, array: [u8; 8]| {
+                assert_eq!(packed, u64::from_be_bytes(array));
+            };
+            check(flip_vertical8x8_u64(n), flip_vertical8x8(rows));
+            check(flip_horizontal8x8_u64(n), flip_horizontal8x8(rows));
+            check(flip_anti_diagonal8x8_u64(n), flip_anti_diagonal8x8(rows));
+            check(rotate90_cw_8x8_u64(n), rotate90_cw_8x8(rows));
+            check(rotate90_ccw_8x8_u64(n), rotate90_ccw_8x8(rows));
+            check(rotate180_8x8_u64(n), rotate180_8x8(rows));
+            assert_eq!(
+                rotate90_cw_8x8_u64(rotate90_cw_8x8_u64(rotate90_cw_8x8_u64(
+                    rotate90_cw_8x8_u64(n)
+                ))),
+                n
+            );
+        }
+    }
 }
@@ -0,0 +1,338 @@
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::vec;
+
+#[cfg(feature = "alloc")]
+use super::transpose64x64_in_place;
+
+macro_rules! impl_mat_vec {
+    (
+        $T:ty, $N:literal, $size:literal,
+        $mat_vec_gf2:ident, $vec_mat_gf2:ident, $mat_vec_bool:ident, $vec_mat_bool:ident
+    ) => {
+        #[doc = concat!("Multiply a ", $size, " bit matrix by a column vector over GF(2).")]
+        ///
+        /// Bit `i` of the result (MSB-first) is the parity of `a[i] & v`, i.e. the XOR of the
+        /// products of row `i` with `v`. Bit `j` of `v` (MSB-first) pairs with column `j`.
+        pub const fn $mat_vec_gf2(a: &[$T; $N], v: $T) -> $T {
+            let mut y: $T = 0;
+            let mut i = 0;
+            while i < $N {
+                y |= (((a[i] & v).count_ones() & 1) as $T) << ($N - 1 - i);
+                i += 1;
+            }
+            y
+        }
+
+        #[doc = concat!("Multiply a row vector by a ", $size, " bit matrix over GF(2).")]
+        ///
+        /// The result is the XOR of every row `a[i]` for which bit `i` of `v` (MSB-first)
+        /// is set.
+        pub const fn $vec_mat_gf2(v: $T, a: &[$T; $N]) -> $T {
+            let mut y: $T = 0;
+            let mut i = 0;
+            while i < $N {
+                let select = ((v >> ($N - 1 - i)) & 1).wrapping_neg();
+                y ^= a[i] & select;
+                i += 1;
+            }
+            y
+        }
+
+        #[doc = concat!("Multiply a ", $size, " bit matrix by a column vector over the boolean semiring.")]
+        ///
+        /// Bit `i` of the result (MSB-first) is set when row `i` overlaps `v`, i.e. the OR of
+        /// the ANDs of row `i` with `v`.
+        pub const fn $mat_vec_bool(a: &[$T; $N], v: $T) -> $T {
+            let mut y: $T = 0;
+            let mut i = 0;
+            while i < $N {
+                y |= ((a[i] & v != 0) as $T) << ($N - 1 - i);
+                i += 1;
+            }
+            y
+        }
+
+        #[doc = concat!("Multiply a row vector by a ", $size, " bit matrix over the boolean semiring.")]
+        ///
+        /// The result is the OR of every row `a[i]` for which bit `i` of `v` (MSB-first) is set.
+        pub const fn $vec_mat_bool(v: $T, a: &[$T; $N]) -> $T {
+            let mut y: $T = 0;
+            let mut i = 0;
+            while i < $N {
+                let select = ((v >> ($N - 1 - i)) & 1).wrapping_neg();
+                y |= a[i] & select;
+                i += 1;
+            }
+            y
+        }
+    };
+}
+
+impl_mat_vec!(
+    u8,
+    8,
+    "8×8",
+    mat_vec_gf2_8x8,
+    vec_mat_gf2_8x8,
+    mat_vec_bool_8x8,
+    vec_mat_bool_8x8
+);
+impl_mat_vec!(
+    u16,
+    16,
+    "16×16",
+    mat_vec_gf2_16x16,
+    vec_mat_gf2_16x16,
+    mat_vec_bool_16x16,
+    vec_mat_bool_16x16
+);
+impl_mat_vec!(
+    u32,
+    32,
+    "32×32",
+    mat_vec_gf2_32x32,
+    vec_mat_gf2_32x32,
+    mat_vec_bool_32x32,
+    vec_mat_bool_32x32
+);
+impl_mat_vec!(
+    u64,
+    64,
+    "64×64",
+    mat_vec_gf2_64x64,
+    vec_mat_gf2_64x64,
+    mat_vec_bool_64x64,
+    vec_mat_bool_64x64
+);
+impl_mat_vec!(
+    u128,
+    128,
+    "128×128",
+    mat_vec_gf2_128x128,
+    vec_mat_gf2_128x128,
+    mat_vec_bool_128x128,
+    vec_mat_bool_128x128
+);
+
+/// A 64×64 bit matrix precompiled for fast repeated GF(2) matrix–vector products.
+///
+/// The product `A·v` is the XOR of the columns of `A` selected by the bits of `v`. The compiled
+/// form stores, for each of the 8 bytes of `v`, a 256-entry table with the XOR of every subset
+/// of the 8 columns that byte selects. Applying the matrix then takes 8 table lookups instead
+/// of 64 row products. The tables occupy 16 KiB on the heap.
+///
+/// To compute `v·A` instead, compile the transpose of `A`.
+///
+/// # Examples
+/// ```
+/// use bittersweet::matrix::{mat_vec_gf2_64x64, CompiledMatrix64};
+/// let mut a = [0_u64; 64];
+/// for (i, row) in a.iter_mut().enumerate() {
+///     *row = 0x0123456789abcdef_u64.rotate_left(i as u32);
+/// }
+/// let compiled = CompiledMatrix64::new(&a);
+/// let v = 0xdeadbeef_cafef00d;
+/// assert_eq!(compiled.mat_vec(v), mat_vec_gf2_64x64(&a, v));
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompiledMatrix64 {
+    tables: Box<[[u64; 256]]>,
+}
+
+#[cfg(feature = "alloc")]
+impl CompiledMatrix64 {
+    /// Compile the matrix whose row `i` is `a[i]`.
+    pub fn new(a: &[u64; 64]) -> Self {
+        let mut columns = *a;
+        transpose64x64_in_place(&mut columns);
+        let mut tables = vec![[0_u64; 256]; 8].into_boxed_slice();
+        for (table, columns) in tables.iter_mut().zip(columns.chunks(8)) {
+            for byte in 1..256_usize {
+                // Add the column of the lowest selected bit to the table entry without it.
+                let lowest = byte.trailing_zeros() as usize;
+                table[byte] = table[byte & (byte - 1)] ^ columns[7 - lowest];
+            }
+        }
+        CompiledMatrix64 { tables }
+    }
+
+    /// Multiply the compiled matrix by the column vector `v` over GF(2).
+    ///
+    /// This returns the same value as [`mat_vec_gf2_64x64`] applied to the original matrix.
+    #[inline]
+    pub fn mat_vec(&self, v: u64) -> u64 {
+        self.tables
+            .iter()
+            .zip(v.to_be_bytes().iter())
+            .fold(0, |y, (table, byte)| y ^ table[*byte as usize])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::{transpose16x16, transpose64x64, transpose8x8};
+    use crate::test_util::xorshift64;
+
+    fn naive_mat_vec(a: &[u64], v: u64, or: bool) -> u64 {
+        let n = a.len();
+        let mut y = 0;
+        for (i, row) in a.iter().enumerate() {
+            let mut acc = false;
+            for j in 0..n {
+                let product = (row >> (n - 1 - j)) & (v >> (n - 1 - j)) & 1 == 1;
+                acc = if or { acc | product } else { acc ^ product };
+            }
+            y |= (acc as u64) << (n - 1 - i);
+        }
+        y
+    }
+
+    #[test]
+    fn test_mat_vec_identity() {
+        let mut identity = [0_u64; 64];
+        for (i, row) in identity.iter_mut().enumerate() {
+            *row = 1 << (63 - i);
+        }
+        for &v in [0, 1, u64::MAX, 0x0123456789abcdef].iter() {
+            assert_eq!(mat_vec_gf2_64x64(&identity, v), v);
+            assert_eq!(vec_mat_gf2_64x64(v, &identity), v);
+            assert_eq!(mat_vec_bool_64x64(&identity, v), v);
+            assert_eq!(vec_mat_bool_64x64(v, &identity), v);
+        }
+    }
+
+    #[test]
+    fn test_mat_vec_8x8() {
+        let a: [u8; 8] = [
+            0b11000000, //
+            0b01100000, //
+            0b00110000, //
+            0b00011000, //
+            0b00001100, //
+            0b00000110, //
+            0b00000011, //
+            0b10000001, //
+        ];
+        // v selects columns 0 and 1
+        let v = 0b11000000;
+        assert_eq!(mat_vec_gf2_8x8(&a, v), 0b01000001);
+        assert_eq!(mat_vec_bool_8x8(&a, v), 0b11000001);
+        // v selects rows 0 and 1
+        assert_eq!(vec_mat_gf2_8x8(v, &a), 0b10100000);
+        assert_eq!(vec_mat_bool_8x8(v, &a), 0b11100000);
+    }
+
+    #[test]
+    fn test_mat_vec_matches_naive() {
+        let mut state = 0x9e3779b97f4a7c15;
+        for _ in 0..32 {
+            let mut a = [0_u64; 64];
+            for row in a.iter_mut() {
+                *row = xorshift64(&mut state);
+            }
+            let v = xorshift64(&mut state);
+            assert_eq!(mat_vec_gf2_64x64(&a, v), naive_mat_vec(&a, v, false));
+            assert_eq!(mat_vec_bool_64x64(&a, v), naive_mat_vec(&a, v, true));
+
+            let a16: Vec<u64> = a[..16].iter().map(|row| *row as u16 as u64).collect();
+            let mut rows16 = [0_u16; 16];
+            for (dst, row) in rows16.iter_mut().zip(a16.iter()) {
+                *dst = *row as u16;
+            }
+            let v16 = v as u16;
+            assert_eq!(
+                mat_vec_gf2_16x16(&rows16, v16) as u64,
+                naive_mat_vec(&a16, v16 as u64, false)
+            );
+            assert_eq!(
+                mat_vec_bool_16x16(&rows16, v16) as u64,
+                naive_mat_vec(&a16, v16 as u64, true)
+            );
+        }
+    }
+
+    #[test]
+    fn test_vec_mat_is_mat_vec_of_transpose() {
+        let mut state = 0xf39cc0605cedc834;
+        for _ in 0..32 {
+            let mut a = [0_u64; 64];
+            for row in a.iter_mut() {
+                *row = xorshift64(&mut state);
+            }
+            let v = xorshift64(&mut state);
+            let t = transpose64x64(a);
+            assert_eq!(vec_mat_gf2_64x64(v, &a), mat_vec_gf2_64x64(&t, v));
+            assert_eq!(vec_mat_bool_64x64(v, &a), mat_vec_bool_64x64(&t, v));
+
+            let a8 = v.to_be_bytes();
+            let v8 = (v >> 8) as u8;
+            assert_eq!(
+                vec_mat_gf2_8x8(v8, &a8),
+                mat_vec_gf2_8x8(&transpose8x8(a8), v8)
+            );
+            let mut a16 = [0_u16; 16];
+            for row in a16.iter_mut() {
+                *row = xorshift64(&mut state) as u16;
+            }
+            let v16 = v as u16;
+            assert_eq!(
+                vec_mat_bool_16x16(v16, &a16),
+                mat_vec_bool_16x16(&transpose16x16(a16), v16)
+            );
+        }
+    }
+
+    #[test]
+    fn test_mat_vec_gf2_is_linear() {
+        let mut state = 0x2545f4914f6cdd1d;
+        let mut a = [0_u128; 128];
+        for row in a.iter_mut() {
+            *row = (xorshift64(&mut state) as u128) << 64 | xorshift64(&mut state) as u128;
+        }
+        for _ in 0..32 {
+            let u = (xorshift64(&mut state) as u128) << 64;
+            let v = xorshift64(&mut state) as u128;
+            assert_eq!(
+                mat_vec_gf2_128x128(&a, u ^ v),
+                mat_vec_gf2_128x128(&a, u) ^ mat_vec_gf2_128x128(&a, v)
+            );
+            assert_eq!(
+                vec_mat_gf2_128x128(u ^ v, &a),
+                vec_mat_gf2_128x128(u, &a) ^ vec_mat_gf2_128x128(v, &a)
+            );
+        }
+        let b = [0xa5a5a5a5_u32; 32];
+        assert_eq!(mat_vec_gf2_32x32(&b, u32::MAX), 0);
+        assert_eq!(mat_vec_bool_32x32(&b, u32::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn test_compiled_matrix_matches_mat_vec() {
+        let mut state = 0x9e3779b97f4a7c15;
+        let mut a = [0_u64; 64];
+        for row in a.iter_mut() {
+            *row = xorshift64(&mut state);
+        }
+        let compiled = CompiledMatrix64::new(&a);
+        assert_eq!(compiled.mat_vec(0), 0);
+        for _ in 0..256 {
+            let v = xorshift64(&mut state);
+            assert_eq!(compiled.mat_vec(v), mat_vec_gf2_64x64(&a, v));
+        }
+        for j in 0..64 {
+            // A single bit selects a single column.
+            assert_eq!(
+                compiled.mat_vec(1 << (63 - j)),
+                mat_vec_gf2_64x64(&a, 1 << (63 - j))
+            );
+        }
+
+        let transposed = CompiledMatrix64::new(&transpose64x64(a));
+        let v = xorshift64(&mut state);
+        assert_eq!(transposed.mat_vec(v), vec_mat_gf2_64x64(v, &a));
+    }
+}
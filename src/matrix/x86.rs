@@ -0,0 +1,329 @@
+//! SSE2 and AVX2 kernels for the 64×64 and 128×128 transposes on `x86_64`.
+//!
+//! Both kernels gather one byte from each of 16 (SSE2) or 32 (AVX2) rows into a vector,
+//! then repeatedly use `movemask` to collect the most significant bit of every byte, which is
+//! one column of those rows, and shift every byte left by one to expose the next column.
+//!
+//! The kernels read every row before any column is complete, so they write the result to a
+//! stack buffer of the same size and copy it over the input at the end.
+//!
+//! SSE2 is part of the `x86_64` baseline. AVX2 is detected at run time when `std` is enabled,
+//! and at compile time (`-C target-feature=+avx2`) otherwise.
+
+use core::arch::x86_64::*;
+
+/// Transpose the 16 rows `rows[0..16]` of 64 bits each.
+///
+/// Returns the 64 columns as 16-bit values, with row 0 at the most significant bit.
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn columns16x64_sse2(rows: &[u64]) -> [u16; 64] {
+    // Lane `i` of every vector holds a byte of row `15 - i`, so that `movemask`, which puts
+    // lane 15 at bit 15, yields row 0 at the most significant bit.
+    let mut x = [_mm_setzero_si128(); 8];
+    for (k, v) in x.iter_mut().enumerate() {
+        *v = _mm_set_epi64x(rows[14 - 2 * k] as i64, rows[15 - 2 * k] as i64);
+    }
+
+    // Transpose the 16×8 byte matrix so that vector `m` holds byte `m` (little-endian) of every
+    // row: interleave the two rows of each vector, then transpose the resulting 8×8 matrix of
+    // 16-bit pairs.
+    let mut z = [_mm_setzero_si128(); 8];
+    for k in 0..8 {
+        z[k] = _mm_unpacklo_epi8(x[k], _mm_srli_si128(x[k], 8));
+    }
+    let mut w = [_mm_setzero_si128(); 8];
+    for k in 0..4 {
+        w[2 * k] = _mm_unpacklo_epi16(z[2 * k], z[2 * k + 1]);
+        w[2 * k + 1] = _mm_unpackhi_epi16(z[2 * k], z[2 * k + 1]);
+    }
+    let mut p = [_mm_setzero_si128(); 8];
+    for h in 0..2 {
+        let (a, b, c, d) = (w[4 * h], w[4 * h + 1], w[4 * h + 2], w[4 * h + 3]);
+        p[4 * h] = _mm_unpacklo_epi32(a, c);
+        p[4 * h + 1] = _mm_unpackhi_epi32(a, c);
+        p[4 * h + 2] = _mm_unpacklo_epi32(b, d);
+        p[4 * h + 3] = _mm_unpackhi_epi32(b, d);
+    }
+    let mut bytes = [_mm_setzero_si128(); 8];
+    for j in 0..4 {
+        bytes[2 * j] = _mm_unpacklo_epi64(p[j], p[j + 4]);
+        bytes[2 * j + 1] = _mm_unpackhi_epi64(p[j], p[j + 4]);
+    }
+
+    let mut columns = [0_u16; 64];
+    for (m, v) in bytes.iter().enumerate() {
+        // Little-endian byte `m` holds columns `8 * (7 - m)` to `8 * (7 - m) + 7`, MSB first.
+        let mut v = *v;
+        for s in 0..8 {
+            columns[8 * (7 - m) + s] = _mm_movemask_epi8(v) as u16;
+            v = _mm_add_epi8(v, v);
+        }
+    }
+    columns
+}
+
+/// Transpose the 32 rows `rows[0..32]` of 64 bits each.
+///
+/// Returns the 64 columns as 32-bit values, with row 0 at the most significant bit.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn columns32x64_avx2(rows: &[u64]) -> [u32; 64] {
+    // The upper 128-bit lane carries rows 0..16 and the lower lane rows 16..32, each in the
+    // reversed order used by `columns16x64_sse2`, so a 32-bit `movemask` puts row 0 at bit 31.
+    // AVX2 unpacks work within each 128-bit lane, so the byte transpose below is the SSE2
+    // network applied to both lanes at once.
+    let mut x = [_mm256_setzero_si256(); 8];
+    for (k, v) in x.iter_mut().enumerate() {
+        *v = _mm256_set_epi64x(
+            rows[14 - 2 * k] as i64,
+            rows[15 - 2 * k] as i64,
+            rows[30 - 2 * k] as i64,
+            rows[31 - 2 * k] as i64,
+        );
+    }
+
+    let mut z = [_mm256_setzero_si256(); 8];
+    for k in 0..8 {
+        z[k] = _mm256_unpacklo_epi8(x[k], _mm256_srli_si256(x[k], 8));
+    }
+    let mut w = [_mm256_setzero_si256(); 8];
+    for k in 0..4 {
+        w[2 * k] = _mm256_unpacklo_epi16(z[2 * k], z[2 * k + 1]);
+        w[2 * k + 1] = _mm256_unpackhi_epi16(z[2 * k], z[2 * k + 1]);
+    }
+    let mut p = [_mm256_setzero_si256(); 8];
+    for h in 0..2 {
+        let (a, b, c, d) = (w[4 * h], w[4 * h + 1], w[4 * h + 2], w[4 * h + 3]);
+        p[4 * h] = _mm256_unpacklo_epi32(a, c);
+        p[4 * h + 1] = _mm256_unpackhi_epi32(a, c);
+        p[4 * h + 2] = _mm256_unpacklo_epi32(b, d);
+        p[4 * h + 3] = _mm256_unpackhi_epi32(b, d);
+    }
+    let mut bytes = [_mm256_setzero_si256(); 8];
+    for j in 0..4 {
+        bytes[2 * j] = _mm256_unpacklo_epi64(p[j], p[j + 4]);
+        bytes[2 * j + 1] = _mm256_unpackhi_epi64(p[j], p[j + 4]);
+    }
+
+    let mut columns = [0_u32; 64];
+    for (m, v) in bytes.iter().enumerate() {
+        let mut v = *v;
+        for s in 0..8 {
+            columns[8 * (7 - m) + s] = _mm256_movemask_epi8(v) as u32;
+            v = _mm256_add_epi8(v, v);
+        }
+    }
+    columns
+}
+
+#[target_feature(enable = "sse2")]
+unsafe fn transpose64x64_sse2(a: &mut [u64; 64]) {
+    let mut out = [0_u64; 64];
+    for g in 0..4 {
+        let columns = columns16x64_sse2(&a[16 * g..]);
+        for (o, c) in out.iter_mut().zip(columns.iter()) {
+            *o |= (*c as u64) << (48 - 16 * g);
+        }
+    }
+    *a = out;
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn transpose64x64_avx2(a: &mut [u64; 64]) {
+    let mut out = [0_u64; 64];
+    for g in 0..2 {
+        let columns = columns32x64_avx2(&a[32 * g..]);
+        for (o, c) in out.iter_mut().zip(columns.iter()) {
+            *o |= (*c as u64) << (32 - 32 * g);
+        }
+    }
+    *a = out;
+}
+
+/// Split every 128-bit row into its high half (columns 0..64) and low half (columns 64..128).
+#[inline(always)]
+fn split_rows<const N: usize>(rows: &[u128]) -> ([u64; N], [u64; N]) {
+    let mut high = [0_u64; N];
+    let mut low = [0_u64; N];
+    for (i, row) in rows.iter().take(N).enumerate() {
+        high[i] = (row >> 64) as u64;
+        low[i] = *row as u64;
+    }
+    (high, low)
+}
+
+/// Assemble 128-bit rows from `(high, low)` word pairs.
+#[inline(always)]
+fn join_rows(a: &mut [u128; 128], words: &[[u64; 2]; 128]) {
+    for (row, [high, low]) in a.iter_mut().zip(words.iter()) {
+        *row = (*high as u128) << 64 | *low as u128;
+    }
+}
+
+#[target_feature(enable = "sse2")]
+unsafe fn transpose128x128_sse2(a: &mut [u128; 128]) {
+    // Row `r` of the result is kept as two `u64` words to avoid 128-bit shifts.
+    let mut out = [[0_u64; 2]; 128];
+    for g in 0..8 {
+        let (high, low) = split_rows::<16>(&a[16 * g..]);
+        let shift = 48 - 16 * (g % 4);
+        for (h, half) in [high, low].iter().enumerate() {
+            let columns = columns16x64_sse2(half);
+            for (o, c) in out[64 * h..].iter_mut().zip(columns.iter()) {
+                o[g / 4] |= (*c as u64) << shift;
+            }
+        }
+    }
+    join_rows(a, &out);
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn transpose128x128_avx2(a: &mut [u128; 128]) {
+    let mut out = [[0_u64; 2]; 128];
+    for g in 0..4 {
+        let (high, low) = split_rows::<32>(&a[32 * g..]);
+        let shift = 32 - 32 * (g % 2);
+        for (h, half) in [high, low].iter().enumerate() {
+            let columns = columns32x64_avx2(half);
+            for (o, c) in out[64 * h..].iter_mut().zip(columns.iter()) {
+                o[g / 2] |= (*c as u64) << shift;
+            }
+        }
+    }
+    join_rows(a, &out);
+}
+
+#[cfg(feature = "std")]
+#[inline]
+fn has_avx2() -> bool {
+    std::is_x86_feature_detected!("avx2")
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+fn has_avx2() -> bool {
+    cfg!(target_feature = "avx2")
+}
+
+/// Transpose with the widest kernel the running CPU supports.
+pub(super) fn transpose64x64_in_place(a: &mut [u64; 64]) {
+    if has_avx2() {
+        // SAFETY: AVX2 support was checked above.
+        unsafe { transpose64x64_avx2(a) }
+    } else {
+        // SAFETY: SSE2 is part of the x86_64 baseline.
+        unsafe { transpose64x64_sse2(a) }
+    }
+}
+
+/// Transpose with the widest kernel the running CPU supports.
+pub(super) fn transpose128x128_in_place(a: &mut [u128; 128]) {
+    if has_avx2() {
+        // SAFETY: AVX2 support was checked above.
+        unsafe { transpose128x128_avx2(a) }
+    } else {
+        // SAFETY: SSE2 is part of the x86_64 baseline.
+        unsafe { transpose128x128_sse2(a) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::{transpose128x128, transpose64x64};
+    use crate::test_util::xorshift64;
+
+    fn samples64() -> Vec<[u64; 64]> {
+        let mut state = 0x9e3779b97f4a7c15;
+        let mut samples = vec![[0; 64], [u64::MAX; 64]];
+        let mut identity = [0; 64];
+        for (i, row) in identity.iter_mut().enumerate() {
+            *row = 1 << (63 - i);
+        }
+        samples.push(identity);
+        for _ in 0..16 {
+            let mut a = [0; 64];
+            for row in a.iter_mut() {
+                *row = xorshift64(&mut state);
+            }
+            samples.push(a);
+        }
+        samples
+    }
+
+    fn samples128() -> Vec<[u128; 128]> {
+        let mut state = 0xf39cc0605cedc834;
+        let mut samples = vec![[0; 128], [u128::MAX; 128]];
+        let mut identity = [0; 128];
+        for (i, row) in identity.iter_mut().enumerate() {
+            *row = 1 << (127 - i);
+        }
+        samples.push(identity);
+        for _ in 0..16 {
+            let mut a = [0; 128];
+            for row in a.iter_mut() {
+                let hi = xorshift64(&mut state) as u128;
+                *row = (hi << 64) | xorshift64(&mut state) as u128;
+            }
+            samples.push(a);
+        }
+        samples
+    }
+
+    #[test]
+    fn test_transpose64x64_sse2_matches_portable() {
+        for a in samples64() {
+            let mut b = a;
+            unsafe { transpose64x64_sse2(&mut b) };
+            assert_eq!(b, transpose64x64(a));
+        }
+    }
+
+    #[test]
+    fn test_transpose64x64_avx2_matches_portable() {
+        if !is_x86_feature_detected!("avx2") {
+            return;
+        }
+        for a in samples64() {
+            let mut b = a;
+            unsafe { transpose64x64_avx2(&mut b) };
+            assert_eq!(b, transpose64x64(a));
+        }
+    }
+
+    #[test]
+    fn test_transpose128x128_sse2_matches_portable() {
+        for a in samples128() {
+            let mut b = a;
+            unsafe { transpose128x128_sse2(&mut b) };
+            assert_eq!(b, transpose128x128(a));
+        }
+    }
+
+    #[test]
+    fn test_transpose128x128_avx2_matches_portable() {
+        if !is_x86_feature_detected!("avx2") {
+            return;
+        }
+        for a in samples128() {
+            let mut b = a;
+            unsafe { transpose128x128_avx2(&mut b) };
+            assert_eq!(b, transpose128x128(a));
+        }
+    }
+
+    #[test]
+    fn test_dispatch_matches_portable() {
+        for a in samples64() {
+            let mut b = a;
+            transpose64x64_in_place(&mut b);
+            assert_eq!(b, transpose64x64(a));
+        }
+        for a in samples128() {
+            let mut b = a;
+            transpose128x128_in_place(&mut b);
+            assert_eq!(b, transpose128x128(a));
+        }
+    }
+}
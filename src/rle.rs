@@ -0,0 +1,286 @@
+//! Run-length encoding of bit sequences of any length.
+//!
+//! Runs follow the convention of [`Bitline::to_rle`](crate::bitline::Bitline::to_rle): the
+//! lengths of the maximal runs of equal bits, MSB-first, alternating between zeros and
+//! ones and starting with a (possibly empty) run of zeros.
+//!
+//! Bit sequences are stored in `u64` words, MSB-first: bit `i` is bit `63 - i % 64` of
+//! word `i / 64`, the same layout as [`crate::bitplane`] and
+//! [`transpose_blocked`](crate::matrix::transpose_blocked).
+//!
+//! [`encode_runs`] serializes a run list compactly as unsigned LEB128 varints (7 bits per
+//! byte, low bits first, high bit set on every byte but the last), and [`decode_runs`]
+//! reads it back.
+//!
+//! # Example
+//!
+//! ```
+//! use bittersweet::rle::{decode_runs, encode_runs, from_rle, to_rle};
+//! // 100 zeros, then 200 ones, then 12 zeros.
+//! let (words, len) = from_rle(&[100, 200, 12]);
+//! assert_eq!((words.len(), len), (5, 312));
+//! let runs = to_rle(&words, len);
+//! assert_eq!(runs, vec![100, 200, 12]);
+//!
+//! let bytes = encode_runs(&runs);
+//! assert_eq!(bytes, vec![100, 0xc8, 0x01, 12]);
+//! assert_eq!(decode_runs(&bytes), Ok(runs));
+//! ```
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp;
+
+use crate::stream::StreamError;
+
+/// Return the run-length encoding of the first `len` bits of `words`.
+///
+/// Bits of the last word past `len` are ignored. An empty sequence has no runs.
+///
+/// # Examples
+/// ```
+/// use bittersweet::rle::to_rle;
+/// assert_eq!(to_rle(&[0b0011 << 60], 4), vec![2, 2]);
+/// assert_eq!(to_rle(&[u64::MAX, 1 << 63], 65), vec![0, 65]);
+/// assert!(to_rle(&[], 0).is_empty());
+/// ```
+///
+/// # Panics
+///
+/// Panics if `words.len()` is not `(len + 63) / 64`.
+pub fn to_rle(words: &[u64], len: usize) -> Vec<usize> {
+    assert!(
+        words.len() == (len + 63) / 64,
+        "word count does not match the bit length"
+    );
+    let mut runs = Vec::new();
+    let mut ones = false;
+    let mut run = 0;
+    let mut position = 0;
+    while position < len {
+        let word = words[position / 64] << (position % 64);
+        let available = cmp::min(64 - position % 64, len - position);
+        let same = if ones {
+            word.leading_ones()
+        } else {
+            word.leading_zeros()
+        };
+        let same = cmp::min(same as usize, available);
+        run += same;
+        position += same;
+        if same < available {
+            // The bit at `position` starts the next run.
+            runs.push(run);
+            run = 0;
+            ones = !ones;
+        }
+    }
+    if run > 0 {
+        runs.push(run);
+    }
+    runs
+}
+
+/// Return the bit sequence described by alternating run lengths starting with zeros,
+/// together with its length in bits.
+///
+/// This is the inverse of [`to_rle`]. The bits of the last word past the end are zero.
+///
+/// # Examples
+/// ```
+/// use bittersweet::rle::from_rle;
+/// assert_eq!(from_rle(&[2, 2]), (vec![0b0011 << 60], 4));
+/// assert_eq!(from_rle(&[]), (vec![], 0));
+/// ```
+///
+/// # Panics
+///
+/// Panics if the total length overflows `usize`.
+pub fn from_rle(runs: &[usize]) -> (Vec<u64>, usize) {
+    let len = runs
+        .iter()
+        .try_fold(0_usize, |total, run| total.checked_add(*run));
+    assert!(len.is_some(), "total run length overflows");
+    let len = len.unwrap_or(0);
+    let mut words = vec![0_u64; (len + 63) / 64];
+    let mut position = 0;
+    for (i, &run) in runs.iter().enumerate() {
+        if i % 2 == 1 {
+            fill_ones(&mut words, position, position + run);
+        }
+        position += run;
+    }
+    (words, len)
+}
+
+/// Set bits `begin..end` of `words`, a word at a time.
+fn fill_ones(words: &mut [u64], begin: usize, end: usize) {
+    let mut position = begin;
+    while position < end {
+        let offset = position % 64;
+        let width = cmp::min(64 - offset, end - position);
+        words[position / 64] |= (u64::MAX >> (64 - width)) << (64 - offset - width);
+        position += width;
+    }
+}
+
+/// Serialize run lengths as unsigned LEB128 varints.
+///
+/// # Examples
+/// ```
+/// use bittersweet::rle::encode_runs;
+/// assert_eq!(encode_runs(&[0, 127, 128, 300]), vec![0, 127, 0x80, 0x01, 0xac, 0x02]);
+/// ```
+pub fn encode_runs(runs: &[usize]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(runs.len());
+    for &run in runs {
+        let mut value = run as u64;
+        while value >= 0x80 {
+            bytes.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        bytes.push(value as u8);
+    }
+    bytes
+}
+
+/// Deserialize run lengths written by [`encode_runs`].
+///
+/// Returns [`StreamError::UnexpectedEnd`] if the bytes end inside a varint and
+/// [`StreamError::InvalidCode`] if a varint does not fit in a `usize`.
+///
+/// # Examples
+/// ```
+/// use bittersweet::rle::decode_runs;
+/// use bittersweet::stream::StreamError;
+/// assert_eq!(decode_runs(&[0, 0xac, 0x02]), Ok(vec![0, 300]));
+/// assert_eq!(decode_runs(&[0xac]), Err(StreamError::UnexpectedEnd));
+/// ```
+pub fn decode_runs(bytes: &[u8]) -> Result<Vec<usize>, StreamError> {
+    let mut runs = Vec::new();
+    let mut value = 0_u64;
+    let mut shift = 0;
+    for &byte in bytes {
+        let bits = (byte & 0x7f) as u64;
+        if shift >= 64 || (bits << shift) >> shift != bits {
+            return Err(StreamError::InvalidCode);
+        }
+        value |= bits << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if value > usize::MAX as u64 {
+                return Err(StreamError::InvalidCode);
+            }
+            runs.push(value as usize);
+            value = 0;
+            shift = 0;
+        }
+    }
+    if shift != 0 {
+        return Err(StreamError::UnexpectedEnd);
+    }
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::xorshift64;
+
+    fn naive_bits(words: &[u64], len: usize) -> Vec<bool> {
+        (0..len)
+            .map(|i| words[i / 64] & (1 << (63 - i % 64)) != 0)
+            .collect()
+    }
+
+    fn naive_rle(bits: &[bool]) -> Vec<usize> {
+        let mut runs = Vec::new();
+        let mut current = false;
+        let mut run = 0;
+        for &bit in bits {
+            if bit != current {
+                runs.push(run);
+                run = 0;
+                current = bit;
+            }
+            run += 1;
+        }
+        if run > 0 {
+            runs.push(run);
+        }
+        runs
+    }
+
+    fn pseudo_random_words(count: usize, seed: u64) -> Vec<u64> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| {
+                xorshift64(&mut state);
+                // thinned so that runs span word boundaries
+                if state % 3 == 0 {
+                    0
+                } else if state % 3 == 1 {
+                    u64::MAX
+                } else {
+                    state & (state >> 11)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_to_rle_matches_naive() {
+        for (i, &len) in [1, 5, 63, 64, 65, 128, 200, 1000].iter().enumerate() {
+            let mut words = pseudo_random_words((len + 63) / 64, 0x9e3779b97f4a7c15 + i as u64);
+            let expected = naive_rle(&naive_bits(&words, len));
+            assert_eq!(to_rle(&words, len), expected, "length {}", len);
+            // Padding bits are ignored on input and cleared on output.
+            if len % 64 != 0 {
+                let mask = u64::MAX << (64 - len % 64);
+                *words.last_mut().unwrap() |= !mask;
+                assert_eq!(to_rle(&words, len), expected, "length {}", len);
+                *words.last_mut().unwrap() &= mask;
+            }
+            assert_eq!(from_rle(&expected), (words, len));
+        }
+    }
+
+    #[test]
+    fn test_rle_edge_cases() {
+        assert!(to_rle(&[], 0).is_empty());
+        assert_eq!(from_rle(&[]), (vec![], 0));
+        assert_eq!(from_rle(&[0]), (vec![], 0));
+        assert_eq!(to_rle(&[u64::MAX; 3], 192), vec![0, 192]);
+        assert_eq!(to_rle(&[0; 3], 130), vec![130]);
+        assert_eq!(from_rle(&[0, 0, 3, 0, 0, 1]), (vec![1 << 60], 4));
+        assert_eq!(from_rle(&[1, 127]), (vec![u64::MAX >> 1, u64::MAX], 128));
+    }
+
+    #[test]
+    #[should_panic(expected = "word count does not match the bit length")]
+    fn test_to_rle_panics_on_wrong_word_count() {
+        to_rle(&[0, 0], 64);
+    }
+
+    #[test]
+    fn test_encode_decode_runs() {
+        let runs = vec![0, 1, 127, 128, 16383, 16384, usize::MAX];
+        let bytes = encode_runs(&runs);
+        assert_eq!(decode_runs(&bytes), Ok(runs));
+        assert!(encode_runs(&[]).is_empty());
+        assert_eq!(decode_runs(&[]), Ok(vec![]));
+        assert_eq!(encode_runs(&[16384]), vec![0x80, 0x80, 0x01]);
+    }
+
+    #[test]
+    fn test_decode_runs_errors() {
+        assert_eq!(decode_runs(&[5, 0x80]), Err(StreamError::UnexpectedEnd));
+        // Eleven continuation groups exceed 64 bits.
+        let mut bytes = vec![0xff; 10];
+        bytes.push(0x01);
+        assert_eq!(decode_runs(&bytes), Err(StreamError::InvalidCode));
+        let mut bytes = vec![0x80; 10];
+        bytes.push(0x00);
+        assert_eq!(decode_runs(&bytes), Err(StreamError::InvalidCode));
+    }
+}
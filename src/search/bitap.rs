@@ -0,0 +1,211 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::slice;
+
+use super::{assert_pattern_fits, bit, match_masks, Match};
+use crate::bitline::BitlineWord;
+
+/// An approximate matcher that finds substrings within `max_errors` edits of a pattern of up
+/// to `T::length()` bytes, using the Wu–Manber extension of the Bitap (Shift-And) algorithm.
+///
+/// The matcher keeps one state word per error count `d` from 0 to `max_errors`; bit `i` of
+/// state `d` is set while some substring ending at the current byte is within `d` edits of the
+/// first `i + 1` bytes of the pattern. Each text byte therefore costs `O(max_errors)` word
+/// operations.
+///
+/// # Examples
+/// ```
+/// use bittersweet::search::{Bitap, Match};
+/// let matcher = Bitap::<u64>::new(b"needle", 1);
+/// let matches: Vec<Match> = matcher.find_iter(b"haystack nedle needles").collect();
+/// assert_eq!(
+///     matches,
+///     vec![
+///         Match { end: 14, distance: 1 },
+///         Match { end: 20, distance: 1 },
+///         Match { end: 21, distance: 0 },
+///         Match { end: 22, distance: 1 },
+///     ]
+/// );
+/// ```
+#[derive(Clone)]
+pub struct Bitap<T> {
+    /// Bit `i` of `masks[c]` is set when `pattern[i] == c`.
+    masks: [T; 256],
+    len: usize,
+    max_errors: usize,
+}
+
+impl<T: BitlineWord> Bitap<T> {
+    /// Return a matcher for substrings within `max_errors` edits of `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is empty or longer than `T::length()` bytes.
+    pub fn new(pattern: &[u8], max_errors: usize) -> Self {
+        assert_pattern_fits::<T>(pattern);
+        Bitap {
+            masks: match_masks(pattern),
+            len: pattern.len(),
+            max_errors,
+        }
+    }
+
+    /// Return the length of the pattern.
+    pub fn pattern_len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the largest number of edits a match may have.
+    pub fn max_errors(&self) -> usize {
+        self.max_errors
+    }
+
+    /// Return the first match in `text`.
+    pub fn find(&self, text: &[u8]) -> Option<Match> {
+        self.find_iter(text).next()
+    }
+
+    /// Return an iterator over every end position in `text` where a substring is within
+    /// `max_errors` edits of the pattern, with the smallest such number of edits.
+    pub fn find_iter<'a>(&'a self, text: &'a [u8]) -> BitapMatches<'a, T> {
+        // With `d` errors to spend, the first `d` pattern bytes can all be deleted.
+        let states = (0..=self.max_errors.min(self.len))
+            .map(|errors| T::by_range(T::length() - errors, T::length()))
+            .collect();
+        BitapMatches {
+            matcher: self,
+            text: text.iter(),
+            position: 0,
+            states,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Bitap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bitap")
+            .field("len", &self.len)
+            .field("max_errors", &self.max_errors)
+            .finish()
+    }
+}
+
+/// An iterator over approximate matches, returned by [`Bitap::find_iter`].
+#[derive(Clone, Debug)]
+pub struct BitapMatches<'a, T> {
+    matcher: &'a Bitap<T>,
+    text: slice::Iter<'a, u8>,
+    position: usize,
+    /// `states[d]` is the state for `d` errors. Deleting the whole pattern is always a match,
+    /// so error counts past the pattern length are left out.
+    states: Vec<T>,
+}
+
+impl<'a, T: BitlineWord> Iterator for BitapMatches<'a, T> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        let last = bit::<T>(self.matcher.len - 1);
+        for &byte in &mut self.text {
+            let mask = self.matcher.masks[usize::from(byte)];
+            // `previous` is the state for one error less, before and after this byte.
+            let mut previous_old = T::as_empty();
+            let mut previous_new = T::as_empty();
+            let mut distance = None;
+            for (errors, state) in self.states.iter_mut().enumerate() {
+                let old = *state;
+                let mut new = ((old << 1) | T::ONE) & mask;
+                if errors > 0 {
+                    // Insertion, substitution and deletion.
+                    new = new | previous_old | (previous_old << 1) | (previous_new << 1) | T::ONE;
+                }
+                *state = new;
+                if distance.is_none() && new & last != T::as_empty() {
+                    distance = Some(errors);
+                }
+                previous_old = old;
+                previous_new = new;
+            }
+            self.position += 1;
+            if let Some(distance) = distance {
+                return Some(Match {
+                    end: self.position,
+                    distance,
+                });
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T: BitlineWord> FusedIterator for BitapMatches<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::reference::{random_text, search_distances};
+    use super::*;
+
+    fn reference_matches(pattern: &[u8], text: &[u8], max_errors: usize) -> Vec<Match> {
+        search_distances(pattern, text)
+            .into_iter()
+            .enumerate()
+            .skip(1)
+            .filter(|&(_, distance)| distance <= max_errors)
+            .map(|(end, distance)| Match { end, distance })
+            .collect()
+    }
+
+    macro_rules! assert_matches_reference {
+        ($T:ty) => {
+            let mut state = 0x2545_f491_4f6c_dd1d_u64;
+            for len in 1..=<$T>::BITS as usize {
+                for &max_errors in &[0, 1, 2, 5] {
+                    let text = random_text(&mut state, 300, 4);
+                    let start = (state % 150) as usize;
+                    let mut pattern = text[start..start + len].to_vec();
+                    pattern[len / 2] = b'z';
+                    let matches: Vec<Match> = Bitap::<$T>::new(&pattern, max_errors)
+                        .find_iter(&text)
+                        .collect();
+                    assert_eq!(matches, reference_matches(&pattern, &text, max_errors));
+                }
+            }
+        };
+    }
+
+    #[test]
+    fn test_bitap_matches_reference() {
+        assert_matches_reference!(u8);
+        assert_matches_reference!(u16);
+        assert_matches_reference!(u32);
+        assert_matches_reference!(u64);
+        assert_matches_reference!(u128);
+    }
+
+    #[test]
+    fn test_bitap_edge_cases() {
+        let matcher = Bitap::<u8>::new(b"ab", 3);
+        assert_eq!(matcher.find(b""), None);
+        assert_eq!(
+            matcher.find_iter(b"xx").collect::<Vec<_>>(),
+            reference_matches(b"ab", b"xx", 3)
+        );
+        let exact = Bitap::<u16>::new(b"abc", 0);
+        assert_eq!(
+            exact.find_iter(b"abcabc").collect::<Vec<_>>(),
+            vec![
+                Match {
+                    end: 3,
+                    distance: 0
+                },
+                Match {
+                    end: 6,
+                    distance: 0
+                }
+            ]
+        );
+        assert_eq!((exact.pattern_len(), exact.max_errors()), (3, 0));
+    }
+}
@@ -0,0 +1,317 @@
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::{assert_pattern_fits, match_masks};
+use crate::bitline::BitlineWord;
+
+/// Return the length of the longest common subsequence of a pattern of `pattern_len` symbols
+/// and a text given by the match masks of its symbols.
+///
+/// Bit `i` of each mask, counted from the least significant bit, is set when pattern symbol
+/// `i` equals the text symbol, so the caller can use any alphabet. This is the bit-parallel
+/// algorithm of Allison and Dix in Hyyrö's formulation: each text symbol costs an AND, an
+/// addition and an OR.
+///
+/// # Panics
+///
+/// Panics if `pattern_len` is larger than `T::length()`.
+///
+/// # Examples
+/// ```
+/// use bittersweet::search::lcs_length;
+/// let pattern = ["let", "x", "=", "1", ";"];
+/// let text = ["let", "y", "=", "1", ";"];
+/// let masks = text.iter().map(|token| {
+///     pattern
+///         .iter()
+///         .enumerate()
+///         .filter(|(_, symbol)| *symbol == token)
+///         .fold(0_u8, |mask, (i, _)| mask | 1 << i)
+/// });
+/// assert_eq!(lcs_length(pattern.len(), masks), 4);
+/// ```
+pub fn lcs_length<T: BitlineWord, I: IntoIterator<Item = T>>(
+    pattern_len: usize,
+    masks: I,
+) -> usize {
+    assert!(
+        pattern_len <= T::length(),
+        "the pattern is longer than the word"
+    );
+    let mut state = T::as_full();
+    for mask in masks {
+        let matched = state & mask;
+        // `matched` is a subset of `state`, so `state - matched` is `state & !matched`.
+        state = state.wrapping_add(matched) | (state & !matched);
+    }
+    let low = T::by_range(T::length() - pattern_len, T::length());
+    pattern_len - (state & low).num_bits()
+}
+
+/// Return the length of the longest common subsequence of a pattern of `pattern_len` symbols
+/// and a text given by the multi-word match masks of its symbols.
+///
+/// This is [`lcs_length`] for patterns of any length: bit `i` of a mask is bit `i % 64` of
+/// its word `i / 64`, and each mask has `(pattern_len + 63) / 64` words.
+///
+/// # Panics
+///
+/// Panics if a mask has the wrong number of words.
+///
+/// # Examples
+/// ```
+/// use bittersweet::search::lcs_length_words;
+/// let pattern = [1_u32; 100];
+/// let ones = [!0_u64, (1 << 36) - 1];
+/// let zeros = [0_u64; 2];
+/// let masks = [&ones[..], &zeros, &ones];
+/// assert_eq!(lcs_length_words(pattern.len(), masks), 2);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn lcs_length_words<'a, I: IntoIterator<Item = &'a [u64]>>(
+    pattern_len: usize,
+    masks: I,
+) -> usize {
+    let words = (pattern_len + 63) / 64;
+    let mut state = vec![!0_u64; words];
+    for mask in masks {
+        assert!(
+            mask.len() == words,
+            "the mask does not have one bit per pattern symbol"
+        );
+        let mut carry = false;
+        for (word, &mask) in state.iter_mut().zip(mask) {
+            let matched = *word & mask;
+            let (sum, overflow) = word.overflowing_add(matched);
+            let (sum, carried) = sum.overflowing_add(u64::from(carry));
+            carry = overflow || carried;
+            *word = sum | (*word & !matched);
+        }
+    }
+    let ones: usize = state
+        .iter()
+        .enumerate()
+        .map(|(i, &word)| {
+            let bits = (pattern_len - 64 * i).min(64);
+            (word & (!0 >> (64 - bits))).count_ones() as usize
+        })
+        .sum();
+    pattern_len - ones
+}
+
+/// A longest common subsequence calculator for byte patterns of up to `T::length()` bytes.
+///
+/// # Examples
+/// ```
+/// use bittersweet::search::Lcs;
+/// let lcs = Lcs::<u16>::new(b"AGGTAB");
+/// assert_eq!(lcs.length(b"GXTXAYB"), 4);
+/// ```
+#[derive(Clone)]
+pub struct Lcs<T> {
+    masks: [T; 256],
+    len: usize,
+}
+
+impl<T: BitlineWord> Lcs<T> {
+    /// Return a calculator for `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is empty or longer than `T::length()` bytes.
+    pub fn new(pattern: &[u8]) -> Self {
+        assert_pattern_fits::<T>(pattern);
+        Lcs {
+            masks: match_masks(pattern),
+            len: pattern.len(),
+        }
+    }
+
+    /// Return the length of the pattern.
+    pub fn pattern_len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the length of the longest common subsequence of the pattern and `text`.
+    pub fn length(&self, text: &[u8]) -> usize {
+        lcs_length(
+            self.len,
+            text.iter().map(|&byte| self.masks[usize::from(byte)]),
+        )
+    }
+}
+
+impl<T> fmt::Debug for Lcs<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lcs").field("len", &self.len).finish()
+    }
+}
+
+/// The match-mask table of a pattern over any ordered alphabet, for longest common
+/// subsequences of token sequences.
+///
+/// The table holds one multi-word mask per distinct pattern symbol, sorted by symbol;
+/// symbols missing from the pattern have an all-zero mask. Patterns of up to 64 symbols are
+/// computed in a single `u64` word.
+///
+/// # Examples
+/// ```
+/// use bittersweet::search::MatchMasks;
+/// let old = ["fn", "main", "(", ")", "{", "}"];
+/// let new = ["pub", "fn", "main", "(", ")", "{", "run", "(", ")", "}"];
+/// let masks = MatchMasks::new(&old);
+/// assert_eq!(masks.mask(&"("), &[0b100]);
+/// assert_eq!(masks.lcs_length(&new), 6);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct MatchMasks<A> {
+    symbols: Vec<A>,
+    /// The mask of `symbols[s]` is the `words` words starting at `s * words`, followed by
+    /// one all-zero mask shared by every symbol missing from the pattern.
+    masks: Vec<u64>,
+    words: usize,
+    len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<A: Ord + Clone> MatchMasks<A> {
+    /// Return the match-mask table of `pattern`.
+    pub fn new(pattern: &[A]) -> Self {
+        let mut symbols = pattern.to_vec();
+        symbols.sort_unstable();
+        symbols.dedup();
+        let words = (pattern.len() + 63) / 64;
+        let mut masks = vec![0; (symbols.len() + 1) * words];
+        for (i, symbol) in pattern.iter().enumerate() {
+            if let Ok(s) = symbols.binary_search(symbol) {
+                masks[s * words + i / 64] |= 1 << (i % 64);
+            }
+        }
+        MatchMasks {
+            symbols,
+            masks,
+            words,
+            len: pattern.len(),
+        }
+    }
+
+    /// Return the length of the pattern.
+    pub fn pattern_len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the mask of `symbol`: bit `i % 64` of word `i / 64` is set when pattern symbol
+    /// `i` equals `symbol`.
+    pub fn mask(&self, symbol: &A) -> &[u64] {
+        let s = self
+            .symbols
+            .binary_search(symbol)
+            .unwrap_or(self.symbols.len());
+        &self.masks[s * self.words..][..self.words]
+    }
+
+    /// Return the length of the longest common subsequence of the pattern and `text`.
+    pub fn lcs_length(&self, text: &[A]) -> usize {
+        if self.words == 1 {
+            lcs_length(self.len, text.iter().map(|symbol| self.mask(symbol)[0]))
+        } else {
+            lcs_length_words(self.len, text.iter().map(|symbol| self.mask(symbol)))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A> fmt::Debug for MatchMasks<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MatchMasks")
+            .field("len", &self.len)
+            .field("symbols", &self.symbols.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::reference::random_text;
+    use super::*;
+
+    fn reference_lcs<A: PartialEq>(a: &[A], b: &[A]) -> usize {
+        let mut row = vec![0; b.len() + 1];
+        for x in a {
+            let mut diagonal = 0;
+            for (j, y) in b.iter().enumerate() {
+                let value = if x == y {
+                    diagonal + 1
+                } else {
+                    row[j].max(row[j + 1])
+                };
+                diagonal = row[j + 1];
+                row[j + 1] = value;
+            }
+        }
+        row[b.len()]
+    }
+
+    macro_rules! assert_lcs_matches_reference {
+        ($T:ty) => {
+            let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+            for len in 1..=<$T>::BITS as usize {
+                for &alphabet in &[2, 4, 26] {
+                    let pattern = random_text(&mut state, len, alphabet);
+                    let text = random_text(&mut state, 2 * len, alphabet);
+                    let expected = reference_lcs(&pattern, &text);
+                    assert_eq!(Lcs::<$T>::new(&pattern).length(&text), expected);
+                    assert_eq!(MatchMasks::new(&pattern).lcs_length(&text), expected);
+                }
+            }
+        };
+    }
+
+    #[test]
+    fn test_lcs_matches_reference() {
+        assert_lcs_matches_reference!(u8);
+        assert_lcs_matches_reference!(u16);
+        assert_lcs_matches_reference!(u32);
+        assert_lcs_matches_reference!(u64);
+        assert_lcs_matches_reference!(u128);
+    }
+
+    #[test]
+    fn test_multiword_lcs_matches_reference() {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for &len in &[63, 64, 65, 128, 129, 200, 300] {
+            let pattern: Vec<u32> = random_text(&mut state, len, 5)
+                .iter()
+                .map(|&byte| u32::from(byte) * 1000)
+                .collect();
+            let mut text = pattern.clone();
+            text.retain(|&token| token != 98_000);
+            text.extend(pattern.iter().rev().take(40));
+            let masks = MatchMasks::new(&pattern);
+            assert_eq!(masks.lcs_length(&text), reference_lcs(&pattern, &text));
+            assert_eq!(masks.lcs_length(&pattern), len);
+            assert_eq!(masks.lcs_length(&[]), 0);
+        }
+    }
+
+    #[test]
+    fn test_lcs_edge_cases() {
+        assert_eq!(lcs_length::<u8, _>(0, []), 0);
+        assert_eq!(lcs_length_words(0, []), 0);
+        assert_eq!(Lcs::<u8>::new(b"abc").length(b""), 0);
+        assert_eq!(Lcs::<u8>::new(b"abcdefgh").length(b"abcdefgh"), 8);
+        let masks = MatchMasks::new(&["a", "b", "a"]);
+        assert_eq!(masks.mask(&"a"), &[0b101]);
+        assert_eq!(masks.mask(&"z"), &[0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the mask does not have one bit per pattern symbol")]
+    fn test_lcs_length_words_panics_on_wrong_mask_length() {
+        lcs_length_words(65, [&[0_u64][..]]);
+    }
+}
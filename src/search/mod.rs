@@ -0,0 +1,144 @@
+//! Bit-parallel exact and approximate string matching over byte strings.
+//!
+//! Each searcher packs its pattern into the bits of one [`BitlineWord`], `u8` through `u128`,
+//! and advances over the text a whole word of dynamic-programming state per byte:
+//!
+//! - [`ShiftOr`] finds exact occurrences of the pattern;
+//! - [`Bitap`] (with `alloc`) finds occurrences with at most `k` edits, in the Wu–Manber
+//!   formulation of Shift-And;
+//! - [`Myers`] computes the edit distance between the pattern and a text, and finds the
+//!   substrings of a text within a given edit distance of the pattern;
+//! - [`Lcs`] and [`lcs_length`] compute the length of the longest common subsequence of the
+//!   pattern and a text.
+//!
+//! A single-word searcher takes patterns of up to `T::length()` bytes, 128 with `u128`. With
+//! the `alloc` feature, [`LongShiftOr`], [`LongMyers`] and [`lcs_length_words`] split longer
+//! patterns across as many `u64` words as they need, and [`MatchMasks`] computes longest
+//! common subsequences of token sequences over any ordered alphabet.
+//!
+//! Edits are insertions, deletions and substitutions of single bytes (Levenshtein distance).
+//! Matches are reported by their end: the index just past the last byte of the matching
+//! substring, in increasing order.
+//!
+//! Unlike the rest of the crate, pattern position `i` is bit `i` counted from the least
+//! significant bit: the algorithms advance the state with shifts and additions whose carries
+//! move towards the most significant bit. Only the match masks of [`lcs_length`] and
+//! [`MatchMasks`] expose this order.
+//!
+//! # Example
+//!
+//! ```
+//! use bittersweet::search::{Match, Myers, ShiftOr};
+//! let text = b"GET /index.html 200\nGET /indx.html 404\n";
+//! let exact = ShiftOr::<u64>::new(b"/index.html");
+//! assert_eq!(exact.find_iter(text).collect::<Vec<_>>(), vec![15]);
+//! let fuzzy = Myers::<u64>::new(b"/index.html");
+//! assert_eq!(fuzzy.distance(b"/indx.html"), 1);
+//! let ends: Vec<Match> = fuzzy.find_iter(text, 1).collect();
+//! assert_eq!(ends[0], Match { end: 14, distance: 1 });
+//! ```
+
+#[cfg(feature = "alloc")]
+mod bitap;
+mod lcs;
+mod myers;
+mod shift_or;
+// re-export
+#[cfg(feature = "alloc")]
+pub use bitap::{Bitap, BitapMatches};
+pub use lcs::{lcs_length, Lcs};
+#[cfg(feature = "alloc")]
+pub use lcs::{lcs_length_words, MatchMasks};
+#[cfg(feature = "alloc")]
+pub use myers::{LongMyers, LongMyersMatches};
+pub use myers::{Myers, MyersMatches};
+#[cfg(feature = "alloc")]
+pub use shift_or::{LongShiftOr, LongShiftOrMatches};
+pub use shift_or::{ShiftOr, ShiftOrMatches};
+
+use crate::bitline::BitlineWord;
+
+/// An approximate match: the end of a substring and its edit distance from the pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Match {
+    /// The index just past the last byte of the matching substring.
+    pub end: usize,
+    /// The smallest number of edits that turn some substring ending at `end` into the
+    /// pattern.
+    pub distance: usize,
+}
+
+/// Return the word with bit `i` set, counting from the least significant bit.
+#[inline]
+fn bit<T: BitlineWord>(i: usize) -> T {
+    T::ONE << i as u32
+}
+
+/// Check that a pattern fits in a single word of `T`.
+fn assert_pattern_fits<T: BitlineWord>(pattern: &[u8]) {
+    assert!(!pattern.is_empty(), "the pattern must not be empty");
+    assert!(
+        pattern.len() <= T::length(),
+        "the pattern is longer than the word"
+    );
+}
+
+/// Build the match masks of `pattern`: bit `i` of `masks[c]` is set when `pattern[i] == c`.
+fn match_masks<T: BitlineWord>(pattern: &[u8]) -> [T; 256] {
+    let mut masks = [T::as_empty(); 256];
+    for (i, &byte) in pattern.iter().enumerate() {
+        masks[usize::from(byte)] = masks[usize::from(byte)] | bit(i);
+    }
+    masks
+}
+
+#[cfg(test)]
+pub(crate) mod reference {
+    use crate::test_util::xorshift64;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    /// The Levenshtein distance between `a` and `b`, by dynamic programming.
+    pub fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, &x) in a.iter().enumerate() {
+            let mut diagonal = row[0];
+            row[0] = i + 1;
+            for (j, &y) in b.iter().enumerate() {
+                let value = (diagonal + usize::from(x != y))
+                    .min(row[j] + 1)
+                    .min(row[j + 1] + 1);
+                diagonal = row[j + 1];
+                row[j + 1] = value;
+            }
+        }
+        row[b.len()]
+    }
+
+    /// For every end position `j` of `text`, the smallest edit distance between `pattern`
+    /// and a substring of `text` ending at `j`.
+    pub fn search_distances(pattern: &[u8], text: &[u8]) -> Vec<usize> {
+        let mut column: Vec<usize> = (0..=pattern.len()).collect();
+        let mut ends = vec![pattern.len()];
+        for &y in text {
+            let mut diagonal = column[0];
+            column[0] = 0;
+            for (i, &x) in pattern.iter().enumerate() {
+                let value = (diagonal + usize::from(x != y))
+                    .min(column[i] + 1)
+                    .min(column[i + 1] + 1);
+                diagonal = column[i + 1];
+                column[i + 1] = value;
+            }
+            ends.push(column[pattern.len()]);
+        }
+        ends
+    }
+
+    /// A pseudorandom text over the first `alphabet` lowercase letters.
+    pub fn random_text(state: &mut u64, len: usize, alphabet: u8) -> Vec<u8> {
+        (0..len)
+            .map(|_| b'a' + (xorshift64(state) % u64::from(alphabet)) as u8)
+            .collect()
+    }
+}
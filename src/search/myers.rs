@@ -0,0 +1,395 @@
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::slice;
+
+use super::{assert_pattern_fits, bit, match_masks, Match};
+use crate::bitline::BitlineWord;
+
+/// The vertical deltas of one column of the edit distance table, one bit per pattern byte.
+#[derive(Clone, Copy, Debug)]
+struct Column<T> {
+    /// Bit `i` is set when row `i + 1` is one more than row `i`.
+    positive: T,
+    /// Bit `i` is set when row `i + 1` is one less than row `i`.
+    negative: T,
+}
+
+impl<T: BitlineWord> Column<T> {
+    /// The first column, where row `i` holds `i`.
+    fn new() -> Self {
+        Column {
+            positive: T::as_full(),
+            negative: T::as_empty(),
+        }
+    }
+
+    /// Advance to the next text byte, whose match mask is `mask`, given the horizontal delta
+    /// `carry_in` of the top row, and return the horizontal delta of row `high`.
+    ///
+    /// This is the block step of Myers' algorithm: with `high` the top bit of the word, the
+    /// returned delta is the carry into the next block of a longer pattern.
+    #[inline]
+    fn advance(&mut self, mask: T, carry_in: i8, high: T) -> i8 {
+        let zero = T::as_empty();
+        let vertical = mask | self.negative;
+        let mask = if carry_in < 0 { mask | T::ONE } else { mask };
+        let horizontal =
+            ((mask & self.positive).wrapping_add(self.positive) ^ self.positive) | mask;
+        let mut positive = self.negative | !(horizontal | self.positive);
+        let mut negative = self.positive & horizontal;
+        let carry_out = if positive & high != zero {
+            1
+        } else if negative & high != zero {
+            -1
+        } else {
+            0
+        };
+        positive = positive << 1;
+        negative = negative << 1;
+        if carry_in < 0 {
+            negative = negative | T::ONE;
+        } else if carry_in > 0 {
+            positive = positive | T::ONE;
+        }
+        self.positive = negative | !(vertical | positive);
+        self.negative = positive & vertical;
+        carry_out
+    }
+}
+
+/// An edit distance calculator and approximate matcher for patterns of up to `T::length()`
+/// bytes, using Myers' bit-vector algorithm.
+///
+/// Myers' algorithm encodes a column of the edit distance table as the differences between
+/// adjacent rows, one bit per pattern byte, and computes the next column with a handful of
+/// word operations, including one addition. Both the distance to a whole text and the best
+/// distance to a substring ending at each position take one step per text byte.
+///
+/// # Examples
+/// ```
+/// use bittersweet::search::{Match, Myers};
+/// let myers = Myers::<u16>::new(b"kitten");
+/// assert_eq!(myers.distance(b"sitting"), 3);
+/// let matches: Vec<Match> = myers.find_iter(b"a mitten or a kitty", 2).collect();
+/// assert_eq!(matches[0], Match { end: 7, distance: 2 });
+/// assert_eq!(matches[1], Match { end: 8, distance: 1 });
+/// ```
+#[derive(Clone)]
+pub struct Myers<T> {
+    /// Bit `i` of `masks[c]` is set when `pattern[i] == c`.
+    masks: [T; 256],
+    len: usize,
+}
+
+impl<T: BitlineWord> Myers<T> {
+    /// Return a calculator for `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is empty or longer than `T::length()` bytes.
+    pub fn new(pattern: &[u8]) -> Self {
+        assert_pattern_fits::<T>(pattern);
+        Myers {
+            masks: match_masks(pattern),
+            len: pattern.len(),
+        }
+    }
+
+    /// Return the length of the pattern.
+    pub fn pattern_len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the edit distance between the pattern and `text`.
+    pub fn distance(&self, text: &[u8]) -> usize {
+        let high = bit::<T>(self.len - 1);
+        let mut column = Column::new();
+        let mut distance = self.len;
+        for &byte in text {
+            // The top row counts the text bytes, so it always grows by one.
+            let delta = column.advance(self.masks[usize::from(byte)], 1, high);
+            distance = (distance as isize + isize::from(delta)) as usize;
+        }
+        distance
+    }
+
+    /// Return the first substring of `text` within `max_distance` edits of the pattern.
+    pub fn find(&self, text: &[u8], max_distance: usize) -> Option<Match> {
+        self.find_iter(text, max_distance).next()
+    }
+
+    /// Return an iterator over every end position in `text` where a substring is within
+    /// `max_distance` edits of the pattern, with the smallest such number of edits.
+    pub fn find_iter<'a>(&'a self, text: &'a [u8], max_distance: usize) -> MyersMatches<'a, T> {
+        MyersMatches {
+            myers: self,
+            text: text.iter(),
+            position: 0,
+            max_distance,
+            column: Column::new(),
+            distance: self.len,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Myers<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Myers").field("len", &self.len).finish()
+    }
+}
+
+/// An iterator over approximate matches, returned by [`Myers::find_iter`].
+#[derive(Clone, Debug)]
+pub struct MyersMatches<'a, T> {
+    myers: &'a Myers<T>,
+    text: slice::Iter<'a, u8>,
+    position: usize,
+    max_distance: usize,
+    column: Column<T>,
+    distance: usize,
+}
+
+impl<'a, T: BitlineWord> Iterator for MyersMatches<'a, T> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        let high = bit::<T>(self.myers.len - 1);
+        for &byte in &mut self.text {
+            // A match may start anywhere, so the top row stays zero.
+            let delta = self
+                .column
+                .advance(self.myers.masks[usize::from(byte)], 0, high);
+            self.distance = (self.distance as isize + isize::from(delta)) as usize;
+            self.position += 1;
+            if self.distance <= self.max_distance {
+                return Some(Match {
+                    end: self.position,
+                    distance: self.distance,
+                });
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T: BitlineWord> FusedIterator for MyersMatches<'a, T> {}
+
+/// An edit distance calculator and approximate matcher for patterns of any length, using
+/// Myers' algorithm over as many `u64` blocks as the pattern needs.
+///
+/// Each text byte advances every block in turn, passing the horizontal delta at the top of
+/// one block to the bottom of the next.
+///
+/// # Examples
+/// ```
+/// use bittersweet::search::LongMyers;
+/// let pattern: Vec<u8> = (0..200).map(|i| b'a' + i % 7).collect();
+/// let mut text = pattern.clone();
+/// text[100] = b'z';
+/// text.remove(150);
+/// assert_eq!(LongMyers::new(&pattern).distance(&text), 2);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct LongMyers {
+    /// The masks of byte `c` are the `blocks` words starting at `c * blocks`.
+    masks: Vec<u64>,
+    blocks: usize,
+    len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl LongMyers {
+    /// Return a calculator for `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is empty.
+    pub fn new(pattern: &[u8]) -> Self {
+        assert!(!pattern.is_empty(), "the pattern must not be empty");
+        let blocks = (pattern.len() + 63) / 64;
+        let mut masks = vec![0; 256 * blocks];
+        for (i, &byte) in pattern.iter().enumerate() {
+            masks[usize::from(byte) * blocks + i / 64] |= 1 << (i % 64);
+        }
+        LongMyers {
+            masks,
+            blocks,
+            len: pattern.len(),
+        }
+    }
+
+    /// Return the length of the pattern.
+    pub fn pattern_len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the edit distance between the pattern and `text`.
+    pub fn distance(&self, text: &[u8]) -> usize {
+        let mut columns = vec![Column::new(); self.blocks];
+        let mut distance = self.len;
+        for &byte in text {
+            distance =
+                (distance as isize + isize::from(self.advance(&mut columns, byte, 1))) as usize;
+        }
+        distance
+    }
+
+    /// Return the first substring of `text` within `max_distance` edits of the pattern.
+    pub fn find(&self, text: &[u8], max_distance: usize) -> Option<Match> {
+        self.find_iter(text, max_distance).next()
+    }
+
+    /// Return an iterator over every end position in `text` where a substring is within
+    /// `max_distance` edits of the pattern, with the smallest such number of edits.
+    pub fn find_iter<'a>(&'a self, text: &'a [u8], max_distance: usize) -> LongMyersMatches<'a> {
+        LongMyersMatches {
+            myers: self,
+            text: text.iter(),
+            position: 0,
+            max_distance,
+            columns: vec![Column::new(); self.blocks],
+            distance: self.len,
+        }
+    }
+
+    /// Advance every block over `byte` and return the horizontal delta of the last row.
+    fn advance(&self, columns: &mut [Column<u64>], byte: u8, mut carry: i8) -> i8 {
+        let masks = &self.masks[usize::from(byte) * self.blocks..][..self.blocks];
+        let last = columns.len() - 1;
+        for (block, (column, &mask)) in columns.iter_mut().zip(masks).enumerate() {
+            let high = if block == last {
+                1 << ((self.len - 1) % 64)
+            } else {
+                1 << 63
+            };
+            carry = column.advance(mask, carry, high);
+        }
+        carry
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for LongMyers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LongMyers").field("len", &self.len).finish()
+    }
+}
+
+/// An iterator over approximate matches, returned by [`LongMyers::find_iter`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct LongMyersMatches<'a> {
+    myers: &'a LongMyers,
+    text: slice::Iter<'a, u8>,
+    position: usize,
+    max_distance: usize,
+    columns: Vec<Column<u64>>,
+    distance: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Iterator for LongMyersMatches<'a> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        for &byte in &mut self.text {
+            let delta = self.myers.advance(&mut self.columns, byte, 0);
+            self.distance = (self.distance as isize + isize::from(delta)) as usize;
+            self.position += 1;
+            if self.distance <= self.max_distance {
+                return Some(Match {
+                    end: self.position,
+                    distance: self.distance,
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> FusedIterator for LongMyersMatches<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::reference::{edit_distance, random_text, search_distances};
+    use super::*;
+
+    macro_rules! assert_matches_reference {
+        ($T:ty) => {
+            let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+            for len in 1..=<$T>::BITS as usize {
+                let pattern = random_text(&mut state, len, 4);
+                let extra = (state % 20) as usize;
+                let text = random_text(&mut state, len + extra, 4);
+                let myers = Myers::<$T>::new(&pattern);
+                assert_eq!(myers.distance(&text), edit_distance(&pattern, &text));
+                assert_eq!(myers.distance(b""), len);
+                let distances = search_distances(&pattern, &text);
+                let max_distance = len / 3;
+                let expected: Vec<Match> = (1..=text.len())
+                    .filter(|&end| distances[end] <= max_distance)
+                    .map(|end| Match {
+                        end,
+                        distance: distances[end],
+                    })
+                    .collect();
+                assert_eq!(
+                    myers.find_iter(&text, max_distance).collect::<Vec<_>>(),
+                    expected
+                );
+            }
+        };
+    }
+
+    #[test]
+    fn test_myers_matches_reference() {
+        assert_matches_reference!(u8);
+        assert_matches_reference!(u16);
+        assert_matches_reference!(u32);
+        assert_matches_reference!(u64);
+        assert_matches_reference!(u128);
+    }
+
+    #[test]
+    fn test_long_myers_matches_reference() {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for &len in &[1, 5, 63, 64, 65, 127, 128, 129, 250] {
+            let pattern = random_text(&mut state, len, 3);
+            let mut text = random_text(&mut state, 400, 3);
+            text[100..100 + len].copy_from_slice(&pattern);
+            text[100 + len / 2] = b'z';
+            let myers = LongMyers::new(&pattern);
+            assert_eq!(myers.distance(&text), edit_distance(&pattern, &text));
+            assert_eq!(myers.distance(&pattern), 0);
+            let distances = search_distances(&pattern, &text);
+            let max_distance = len / 4 + 1;
+            let found: Vec<Match> = myers.find_iter(&text, max_distance).collect();
+            assert!(found.contains(&Match {
+                end: 100 + len,
+                distance: 1
+            }));
+            assert!(found
+                .iter()
+                .all(|found| distances[found.end] == found.distance));
+            assert_eq!(
+                found.len(),
+                distances[1..]
+                    .iter()
+                    .filter(|&&distance| distance <= max_distance)
+                    .count()
+            );
+            if len <= 128 {
+                let single: Vec<Match> = Myers::<u128>::new(&pattern)
+                    .find_iter(&text, max_distance)
+                    .collect();
+                assert_eq!(found, single);
+            }
+        }
+    }
+}
@@ -0,0 +1,290 @@
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::slice;
+
+use super::{assert_pattern_fits, bit, match_masks};
+use crate::bitline::BitlineWord;
+
+/// An exact matcher for patterns of up to `T::length()` bytes, using the Shift-Or algorithm.
+///
+/// The state keeps bit `i` clear while the last `i + 1` bytes of text equal the first `i + 1`
+/// bytes of the pattern, so each text byte costs one shift and one OR.
+///
+/// # Examples
+/// ```
+/// use bittersweet::search::ShiftOr;
+/// let matcher = ShiftOr::<u32>::new(b"ana");
+/// assert_eq!(matcher.find_iter(b"bananas").collect::<Vec<_>>(), vec![4, 6]);
+/// assert_eq!(matcher.find(b"cabana"), Some(6));
+/// assert_eq!(matcher.find(b"banner"), None);
+/// ```
+#[derive(Clone)]
+pub struct ShiftOr<T> {
+    /// Bit `i` of `masks[c]` is clear when `pattern[i] == c`.
+    masks: [T; 256],
+    len: usize,
+}
+
+impl<T: BitlineWord> ShiftOr<T> {
+    /// Return a matcher for `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is empty or longer than `T::length()` bytes.
+    pub fn new(pattern: &[u8]) -> Self {
+        assert_pattern_fits::<T>(pattern);
+        let mut masks = match_masks::<T>(pattern);
+        for mask in masks.iter_mut() {
+            *mask = !*mask;
+        }
+        ShiftOr {
+            masks,
+            len: pattern.len(),
+        }
+    }
+
+    /// Return the length of the pattern.
+    pub fn pattern_len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the end of the first occurrence of the pattern in `text`.
+    pub fn find(&self, text: &[u8]) -> Option<usize> {
+        self.find_iter(text).next()
+    }
+
+    /// Return an iterator over the ends of every occurrence of the pattern in `text`,
+    /// including overlapping ones.
+    pub fn find_iter<'a>(&'a self, text: &'a [u8]) -> ShiftOrMatches<'a, T> {
+        ShiftOrMatches {
+            matcher: self,
+            text: text.iter(),
+            position: 0,
+            state: T::as_full(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for ShiftOr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShiftOr").field("len", &self.len).finish()
+    }
+}
+
+/// An iterator over the ends of exact matches, returned by [`ShiftOr::find_iter`].
+#[derive(Clone, Debug)]
+pub struct ShiftOrMatches<'a, T> {
+    matcher: &'a ShiftOr<T>,
+    text: slice::Iter<'a, u8>,
+    position: usize,
+    state: T,
+}
+
+impl<'a, T: BitlineWord> Iterator for ShiftOrMatches<'a, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let last = bit::<T>(self.matcher.len - 1);
+        for &byte in &mut self.text {
+            self.state = (self.state << 1) | self.matcher.masks[usize::from(byte)];
+            self.position += 1;
+            if self.state & last == T::as_empty() {
+                return Some(self.position);
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T: BitlineWord> FusedIterator for ShiftOrMatches<'a, T> {}
+
+/// An exact matcher for patterns of any length, using the Shift-Or algorithm over as many
+/// `u64` words as the pattern needs.
+///
+/// This works like [`ShiftOr`], carrying the shift from each word into the next one.
+///
+/// # Examples
+/// ```
+/// use bittersweet::search::LongShiftOr;
+/// let pattern = [b'a'; 200];
+/// let text = [b'a'; 202];
+/// let matcher = LongShiftOr::new(&pattern);
+/// assert_eq!(matcher.find_iter(&text).collect::<Vec<_>>(), vec![200, 201, 202]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct LongShiftOr {
+    /// The masks of byte `c` are the `words` words starting at `c * words`.
+    masks: Vec<u64>,
+    words: usize,
+    len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl LongShiftOr {
+    /// Return a matcher for `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is empty.
+    pub fn new(pattern: &[u8]) -> Self {
+        assert!(!pattern.is_empty(), "the pattern must not be empty");
+        let words = (pattern.len() + 63) / 64;
+        let mut masks = vec![!0; 256 * words];
+        for (i, &byte) in pattern.iter().enumerate() {
+            masks[usize::from(byte) * words + i / 64] &= !(1 << (i % 64));
+        }
+        LongShiftOr {
+            masks,
+            words,
+            len: pattern.len(),
+        }
+    }
+
+    /// Return the length of the pattern.
+    pub fn pattern_len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the end of the first occurrence of the pattern in `text`.
+    pub fn find(&self, text: &[u8]) -> Option<usize> {
+        self.find_iter(text).next()
+    }
+
+    /// Return an iterator over the ends of every occurrence of the pattern in `text`,
+    /// including overlapping ones.
+    pub fn find_iter<'a>(&'a self, text: &'a [u8]) -> LongShiftOrMatches<'a> {
+        LongShiftOrMatches {
+            matcher: self,
+            text: text.iter(),
+            position: 0,
+            state: vec![!0; self.words],
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for LongShiftOr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LongShiftOr")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// An iterator over the ends of exact matches, returned by [`LongShiftOr::find_iter`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct LongShiftOrMatches<'a> {
+    matcher: &'a LongShiftOr,
+    text: slice::Iter<'a, u8>,
+    position: usize,
+    state: Vec<u64>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Iterator for LongShiftOrMatches<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let words = self.matcher.words;
+        let last = self.matcher.len - 1;
+        for &byte in &mut self.text {
+            let masks = &self.matcher.masks[usize::from(byte) * words..][..words];
+            let mut carry = 0;
+            for (word, &mask) in self.state.iter_mut().zip(masks) {
+                let shifted = (*word << 1) | carry;
+                carry = *word >> 63;
+                *word = shifted | mask;
+            }
+            self.position += 1;
+            if self.state[last / 64] & (1 << (last % 64)) == 0 {
+                return Some(self.position);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> FusedIterator for LongShiftOrMatches<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::reference::random_text;
+    use super::*;
+
+    fn naive_ends(pattern: &[u8], text: &[u8]) -> Vec<usize> {
+        text.windows(pattern.len())
+            .enumerate()
+            .filter(|(_, window)| *window == pattern)
+            .map(|(start, _)| start + pattern.len())
+            .collect()
+    }
+
+    macro_rules! assert_matches_naive {
+        ($T:ty) => {
+            let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+            for len in 1..=<$T>::BITS as usize {
+                let text = random_text(&mut state, 600, 2);
+                let start = (state % 300) as usize;
+                let pattern = &text[start..start + len];
+                let matcher = ShiftOr::<$T>::new(pattern);
+                let ends: Vec<usize> = matcher.find_iter(&text).collect();
+                assert_eq!(ends, naive_ends(pattern, &text));
+                assert_eq!(
+                    LongShiftOr::new(pattern)
+                        .find_iter(&text)
+                        .collect::<Vec<_>>(),
+                    ends
+                );
+                assert_eq!(matcher.find(&text), ends.first().copied());
+            }
+        };
+    }
+
+    #[test]
+    fn test_shift_or_matches_naive() {
+        assert_matches_naive!(u8);
+        assert_matches_naive!(u16);
+        assert_matches_naive!(u32);
+        assert_matches_naive!(u64);
+        assert_matches_naive!(u128);
+    }
+
+    #[test]
+    fn test_long_shift_or_matches_naive() {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for &len in &[63, 64, 65, 127, 128, 129, 200, 300] {
+            let mut text = random_text(&mut state, 1000, 2);
+            let pattern = text[100..100 + len].to_vec();
+            text[700..700 + len].copy_from_slice(&pattern);
+            let ends: Vec<usize> = LongShiftOr::new(&pattern).find_iter(&text).collect();
+            assert!(ends.contains(&(700 + len)));
+            assert_eq!(ends, naive_ends(&pattern, &text));
+        }
+    }
+
+    #[test]
+    fn test_shift_or_edge_cases() {
+        let matcher = ShiftOr::<u8>::new(b"aaaaaaaa");
+        assert_eq!(
+            matcher.find_iter(b"aaaaaaaaa").collect::<Vec<_>>(),
+            vec![8, 9]
+        );
+        assert_eq!(matcher.find(b""), None);
+        assert_eq!(matcher.pattern_len(), 8);
+        let matcher = ShiftOr::<u8>::new(&[0xff]);
+        assert_eq!(matcher.find(&[0, 0xff]), Some(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "the pattern is longer than the word")]
+    fn test_shift_or_panics_on_long_pattern() {
+        ShiftOr::<u8>::new(b"123456789");
+    }
+}
@@ -0,0 +1,413 @@
+//! Optional [`serde`] support, enabled by the `serde` feature.
+//!
+//! Bitlines are plain integers, so they serialize as integers by default. The modules
+//! below are meant for `#[serde(with = "...")]` attributes and store them as bits instead:
+//!
+//! - [`bits`] writes a [`bit_repr`](crate::bitline::Bitline::bit_repr)-style string such as
+//!   `"00101100"` to human-readable formats (JSON, TOML, ...) and big-endian bytes to binary
+//!   formats (bincode, ...).
+//! - [`bit_string`] and [`bit_bytes`] always use the string or the bytes.
+//! - [`bit_rows`] (with `alloc`) stores a bit matrix, an array of bitlines such as the
+//!   `[u64; 64]` of [`crate::matrix`], as a sequence of row strings in human-readable
+//!   formats and as the concatenated big-endian rows in binary formats.
+//!
+//! [`Bitboard8x8`] and [`Bitboard16x16`] implement `Serialize` and `Deserialize` the same
+//! way as [`bit_rows`]. [`CompressedBitmap`](crate::compressed::CompressedBitmap) is a
+//! sequence of its values in human-readable formats and its
+//! [portable Roaring serialization](crate::compressed::CompressedBitmap::serialize_roaring)
+//! in binary formats.
+//!
+//! # Example
+//!
+//! With `serde_json`, a field marked `#[serde(with = "bittersweet::serde::bits")]` of type
+//! `u8` and value `0b00101100` is written as `"00101100"`. The functions can also be called
+//! directly with any deserializer:
+//!
+//! ```
+//! use bittersweet::serde::bits;
+//! use serde::de::value::{BytesDeserializer, Error, StrDeserializer};
+//!
+//! let mask: u8 = bits::deserialize(StrDeserializer::<Error>::new("00101100")).unwrap();
+//! assert_eq!(mask, 0b00101100);
+//! let mask: u16 = bits::deserialize(BytesDeserializer::<Error>::new(&[0x80, 0x01])).unwrap();
+//! assert_eq!(mask, 0x8001);
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use ::serde::de::{self, Deserialize, Deserializer, SeqAccess, Unexpected, Visitor};
+use ::serde::ser::{self, Serialize, SerializeSeq, Serializer};
+
+use crate::bitboard::{Bitboard16x16, Bitboard8x8};
+use crate::bitline::Bitline;
+
+/// The longest bitline, in bits.
+const MAX_BITS: usize = 128;
+
+/// Serialize a bitline as a string of `'0'` and `'1'`, MSB first.
+fn serialize_string<T: Bitline + Copy, S: Serializer>(
+    value: T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut bytes = [0; MAX_BITS / 8];
+    value.to_be_bit_bytes(&mut bytes[..T::bytes_length()]);
+    let mut chars = [b'0'; MAX_BITS];
+    for (i, char) in chars[..T::bytes_length() * 8].iter_mut().enumerate() {
+        if bytes[i / 8] & (0x80 >> (i % 8)) != 0 {
+            *char = b'1';
+        }
+    }
+    // Only ASCII digits were written.
+    let string =
+        core::str::from_utf8(&chars[..T::bytes_length() * 8]).map_err(ser::Error::custom)?;
+    serializer.serialize_str(string)
+}
+
+/// Serialize a bitline as its big-endian bytes.
+fn serialize_bytes<T: Bitline + Copy, S: Serializer>(
+    value: T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut bytes = [0; MAX_BITS / 8];
+    value.to_be_bit_bytes(&mut bytes[..T::bytes_length()]);
+    serializer.serialize_bytes(&bytes[..T::bytes_length()])
+}
+
+/// Accept a bit string, big-endian bytes or a sequence of bytes for a bitline.
+struct BitlineVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Bitline + Copy> Visitor<'de> for BitlineVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a string of {} '0' or '1' characters or {} bytes",
+            T::bytes_length() * 8,
+            T::bytes_length()
+        )
+    }
+
+    fn visit_str<E: de::Error>(self, string: &str) -> Result<T, E> {
+        if string.len() != T::bytes_length() * 8 {
+            return Err(E::invalid_length(string.len(), &self));
+        }
+        let mut bytes = [0; MAX_BITS / 8];
+        for (i, char) in string.bytes().enumerate() {
+            match char {
+                b'0' => {}
+                b'1' => bytes[i / 8] |= 0x80 >> (i % 8),
+                _ => return Err(E::invalid_value(Unexpected::Str(string), &self)),
+            }
+        }
+        Ok(T::from_be_bit_bytes(&bytes[..T::bytes_length()]))
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<T, E> {
+        if bytes.len() != T::bytes_length() {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+        Ok(T::from_be_bit_bytes(bytes))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut bytes = [0; MAX_BITS / 8];
+        for (i, byte) in bytes[..T::bytes_length()].iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(T::bytes_length() + 1, &self));
+        }
+        Ok(T::from_be_bit_bytes(&bytes[..T::bytes_length()]))
+    }
+}
+
+/// Store a bitline as a bit string in human-readable formats and as big-endian bytes in
+/// binary formats.
+///
+/// # Examples
+/// ```
+/// use bittersweet::serde::bits;
+/// use serde::de::value::{Error, StrDeserializer};
+///
+/// let mask: u16 = bits::deserialize(StrDeserializer::<Error>::new("1000000000000001")).unwrap();
+/// assert_eq!(mask, 0x8001);
+/// assert!(bits::deserialize::<u16, _>(StrDeserializer::<Error>::new("10")).is_err());
+/// ```
+pub mod bits {
+    use super::*;
+
+    /// Serialize a bitline with [`bits`](self).
+    pub fn serialize<T: Bitline + Copy, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serialize_string(*value, serializer)
+        } else {
+            serialize_bytes(*value, serializer)
+        }
+    }
+
+    /// Deserialize a bitline written with [`bits`](self).
+    pub fn deserialize<'de, T: Bitline + Copy, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BitlineVisitor(PhantomData))
+        } else {
+            deserializer.deserialize_bytes(BitlineVisitor(PhantomData))
+        }
+    }
+}
+
+/// Store a bitline as a bit string such as `"00101100"`, MSB first, in every format.
+pub mod bit_string {
+    use super::*;
+
+    /// Serialize a bitline with [`bit_string`](self).
+    pub fn serialize<T: Bitline + Copy, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serialize_string(*value, serializer)
+    }
+
+    /// Deserialize a bitline written with [`bit_string`](self).
+    pub fn deserialize<'de, T: Bitline + Copy, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        deserializer.deserialize_str(BitlineVisitor(PhantomData))
+    }
+}
+
+/// Store a bitline as its big-endian bytes in every format.
+pub mod bit_bytes {
+    use super::*;
+
+    /// Serialize a bitline with [`bit_bytes`](self).
+    pub fn serialize<T: Bitline + Copy, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serialize_bytes(*value, serializer)
+    }
+
+    /// Deserialize a bitline written with [`bit_bytes`](self).
+    pub fn deserialize<'de, T: Bitline + Copy, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        deserializer.deserialize_bytes(BitlineVisitor(PhantomData))
+    }
+}
+
+/// Serialize one row of a matrix as a bit string.
+struct RowString<T>(T);
+
+impl<T: Bitline + Copy> Serialize for RowString<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_string(self.0, serializer)
+    }
+}
+
+impl<'de, T: Bitline + Copy> Deserialize<'de> for RowString<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_str(BitlineVisitor(PhantomData))
+            .map(RowString)
+    }
+}
+
+/// Serialize matrix rows as row strings in human-readable formats, or as the concatenated
+/// big-endian rows written to `buffer` in binary formats.
+fn serialize_rows<T: Bitline + Copy, S: Serializer>(
+    rows: &[T],
+    buffer: &mut [u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        let mut seq = serializer.serialize_seq(Some(rows.len()))?;
+        for &row in rows {
+            seq.serialize_element(&RowString(row))?;
+        }
+        seq.end()
+    } else {
+        for (&row, bytes) in rows.iter().zip(buffer.chunks_exact_mut(T::bytes_length())) {
+            row.to_be_bit_bytes(bytes);
+        }
+        serializer.serialize_bytes(buffer)
+    }
+}
+
+/// Accept the row strings or the concatenated big-endian rows of a matrix.
+struct RowsVisitor<'a, T>(&'a mut [T]);
+
+impl<'de, T: Bitline + Copy> Visitor<'de> for RowsVisitor<'_, T> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} row strings or {} bytes",
+            self.0.len(),
+            self.0.len() * T::bytes_length()
+        )
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<(), E> {
+        if bytes.len() != self.0.len() * T::bytes_length() {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+        for (row, bytes) in self.0.iter_mut().zip(bytes.chunks_exact(T::bytes_length())) {
+            *row = T::from_be_bit_bytes(bytes);
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let len = self.0.len();
+        for i in 0..len {
+            match seq.next_element::<RowString<T>>()? {
+                Some(RowString(row)) => self.0[i] = row,
+                None => return Err(de::Error::invalid_length(i, &self)),
+            }
+        }
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(len + 1, &self));
+        }
+        Ok(())
+    }
+}
+
+/// Deserialize matrix rows written by [`serialize_rows`] into `rows`.
+fn deserialize_rows<'de, T: Bitline + Copy, D: Deserializer<'de>>(
+    rows: &mut [T],
+    deserializer: D,
+) -> Result<(), D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_seq(RowsVisitor(rows))
+    } else {
+        deserializer.deserialize_bytes(RowsVisitor(rows))
+    }
+}
+
+/// Store a bit matrix `[T; N]` as a sequence of row strings in human-readable formats and
+/// as the concatenated big-endian rows in binary formats.
+///
+/// # Examples
+/// ```
+/// use bittersweet::serde::bit_rows;
+/// use serde::de::value::{Error, SeqDeserializer};
+///
+/// let rows = ["10000000", "01000000", "00100000", "00010000"];
+/// let deserializer = SeqDeserializer::<_, Error>::new(rows.iter().copied());
+/// let tile: [u8; 4] = bit_rows::deserialize(deserializer).unwrap();
+/// assert_eq!(tile, [0b10000000, 0b01000000, 0b00100000, 0b00010000]);
+/// ```
+#[cfg(feature = "alloc")]
+pub mod bit_rows {
+    use super::*;
+
+    /// Serialize a bit matrix with [`bit_rows`](self).
+    pub fn serialize<T: Bitline + Copy, S: Serializer, const N: usize>(
+        rows: &[T; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut buffer = alloc::vec![0; N * T::bytes_length()];
+        serialize_rows(rows, &mut buffer, serializer)
+    }
+
+    /// Deserialize a bit matrix written with [`bit_rows`](self).
+    pub fn deserialize<'de, T: Bitline + Copy, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[T; N], D::Error> {
+        let mut rows = [T::as_empty(); N];
+        deserialize_rows(&mut rows, deserializer)?;
+        Ok(rows)
+    }
+}
+
+impl Serialize for Bitboard8x8 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_rows(&self.to_rows(), &mut [0; 8], serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bitboard8x8 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut rows = [0; 8];
+        deserialize_rows(&mut rows, deserializer)?;
+        Ok(Bitboard8x8::from_rows(rows))
+    }
+}
+
+impl Serialize for Bitboard16x16 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_rows(&self.to_rows(), &mut [0; 32], serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bitboard16x16 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut rows = [0; 16];
+        deserialize_rows(&mut rows, deserializer)?;
+        Ok(Bitboard16x16::from_rows(rows))
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod compressed {
+    use super::*;
+    use crate::compressed::CompressedBitmap;
+
+    impl Serialize for CompressedBitmap {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                let mut seq = serializer.serialize_seq(usize::try_from(self.len()).ok())?;
+                for value in self.iter() {
+                    seq.serialize_element(&value)?;
+                }
+                seq.end()
+            } else {
+                serializer.serialize_bytes(&self.serialize_roaring())
+            }
+        }
+    }
+
+    /// Accept a sequence of values or the portable Roaring serialization.
+    struct CompressedBitmapVisitor;
+
+    impl<'de> Visitor<'de> for CompressedBitmapVisitor {
+        type Value = CompressedBitmap;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a sequence of u32 values or a serialized Roaring bitmap")
+        }
+
+        fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<CompressedBitmap, E> {
+            CompressedBitmap::deserialize_roaring(bytes).map_err(E::custom)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<CompressedBitmap, A::Error> {
+            let mut bitmap = CompressedBitmap::new();
+            while let Some(value) = seq.next_element()? {
+                bitmap.insert(value);
+            }
+            bitmap.optimize();
+            Ok(bitmap)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for CompressedBitmap {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_seq(CompressedBitmapVisitor)
+            } else {
+                deserializer.deserialize_bytes(CompressedBitmapVisitor)
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// A byte buffer that a [`BitWriter`](super::BitWriter) can write into.
+///
+/// The writer only ever asks for the buffer to cover more bytes; a fixed buffer refuses once
+/// it is full, while a growable one extends itself with zero bytes.
+pub trait BitBuffer {
+    /// Make the buffer at least `len` bytes long, returning whether it now is.
+    fn ensure_len(&mut self, len: usize) -> bool;
+
+    /// Return the length the buffer can reach, or `None` if it grows without limit.
+    fn max_len(&self) -> Option<usize>;
+
+    /// Return the contents of the buffer.
+    fn as_bytes(&self) -> &[u8];
+
+    /// Return the contents of the buffer for modification.
+    fn as_bytes_mut(&mut self) -> &mut [u8];
+}
+
+impl BitBuffer for &mut [u8] {
+    #[inline]
+    fn ensure_len(&mut self, len: usize) -> bool {
+        len <= self.len()
+    }
+
+    #[inline]
+    fn max_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    #[inline]
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl BitBuffer for Vec<u8> {
+    #[inline]
+    fn ensure_len(&mut self, len: usize) -> bool {
+        if self.len() < len {
+            self.resize(len, 0);
+        }
+        true
+    }
+
+    #[inline]
+    fn max_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    #[inline]
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
@@ -0,0 +1,25 @@
+use core::fmt;
+
+/// An error from a [`BitWriter`](super::BitWriter) or [`BitReader`](super::BitReader).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StreamError {
+    /// A read needed more bits than remain in the stream.
+    UnexpectedEnd,
+    /// A write needed more room than the buffer has.
+    BufferFull,
+    /// The bits read do not form a valid code, or encode a value that does not fit.
+    InvalidCode,
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::UnexpectedEnd => f.write_str("unexpected end of bit stream"),
+            StreamError::BufferFull => f.write_str("bit buffer is full"),
+            StreamError::InvalidCode => f.write_str("invalid code in bit stream"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StreamError {}
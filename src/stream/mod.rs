@@ -0,0 +1,43 @@
+//! Bit-level writer and reader streams over byte buffers.
+//!
+//! Values are packed MSB-first: the first bit written is the most significant bit of the
+//! first byte, and an `n`-bit value is stored most significant bit first. A value written
+//! with `n` bits therefore reads back as the same `n`-bit field, and its bits appear in
+//! the buffer in the order [`Bitline::bit_repr`](crate::bitline::Bitline::bit_repr)
+//! shows them.
+//!
+//! [`BitWriter`] writes into any [`BitBuffer`]: a fixed `&mut [u8]`, which reports
+//! [`StreamError::BufferFull`] when it runs out of room, or (with the `alloc` feature) a
+//! growable `Vec<u8>`. [`BitReader`] reads from a `&[u8]` and reports
+//! [`StreamError::UnexpectedEnd`] instead of reading past its end.
+//!
+//! # Example
+//!
+//! ```
+//! use bittersweet::stream::{BitReader, BitWriter};
+//! let mut buffer = [0_u8; 2];
+//! let mut writer = BitWriter::new(&mut buffer[..]);
+//! writer.write_bits(0b101, 3).unwrap();
+//! writer.write_bits(0b1, 1).unwrap();
+//! writer.align_to_byte().unwrap();
+//! writer.write_bits(0xab, 8).unwrap();
+//! assert_eq!(buffer, [0b1011_0000, 0xab]);
+//!
+//! let mut reader = BitReader::new(&buffer);
+//! assert_eq!(reader.read_bits(3), Ok(0b101));
+//! assert_eq!(reader.peek_bits(1), Ok(1));
+//! reader.skip(1).unwrap();
+//! reader.align_to_byte();
+//! assert_eq!(reader.read_bits(8), Ok(0xab));
+//! assert!(reader.read_bit().is_err());
+//! ```
+
+mod buffer;
+mod error;
+mod reader;
+mod writer;
+// re-export
+pub use buffer::BitBuffer;
+pub use error::StreamError;
+pub use reader::BitReader;
+pub use writer::BitWriter;
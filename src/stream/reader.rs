@@ -0,0 +1,169 @@
+use crate::stream::error::StreamError;
+
+/// Reads values of up to 64 bits from a byte slice, MSB-first.
+///
+/// See the [module-level documentation](super) for the bit order.
+///
+/// # Examples
+/// ```
+/// use bittersweet::stream::{BitReader, StreamError};
+/// let mut reader = BitReader::new(&[0b1101_0000]);
+/// assert_eq!(reader.read_bits(3), Ok(0b110));
+/// assert_eq!(reader.read_bit(), Ok(true));
+/// assert_eq!(reader.remaining_bits(), 4);
+/// assert_eq!(reader.read_bits(5), Err(StreamError::UnexpectedEnd));
+/// ```
+#[derive(Clone, Debug)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Create a reader at the start of `bytes`.
+    #[inline]
+    pub fn new(bytes: &'a [u8]) -> Self {
+        BitReader { bytes, position: 0 }
+    }
+
+    /// Return the number of bits read or skipped so far.
+    #[inline]
+    pub fn bit_position(&self) -> usize {
+        self.position
+    }
+
+    /// Return the number of bits left to read.
+    #[inline]
+    pub fn remaining_bits(&self) -> usize {
+        self.bytes.len() * 8 - self.position
+    }
+
+    /// Return whether the next bit starts a new byte.
+    #[inline]
+    pub fn is_byte_aligned(&self) -> bool {
+        self.position % 8 == 0
+    }
+
+    /// Return the next `n` bits as the low bits of a `u64` without consuming them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than 64.
+    pub fn peek_bits(&self, n: u32) -> Result<u64, StreamError> {
+        assert!(n <= 64, "bit width out of range");
+        if n as usize > self.remaining_bits() {
+            return Err(StreamError::UnexpectedEnd);
+        }
+        if n == 0 {
+            return Ok(0);
+        }
+        // Load the (at most 9) bytes covering the field and cut the field out of them.
+        let start = self.position / 8;
+        let end = (self.position + n as usize + 7) / 8;
+        let window = self.bytes[start..end]
+            .iter()
+            .fold(0_u128, |window, byte| window << 8 | *byte as u128);
+        let trailing = (end - start) * 8 - self.position % 8 - n as usize;
+        Ok((window >> trailing) as u64 & (u64::MAX >> (64 - n)))
+    }
+
+    /// Read the next `n` bits as the low bits of a `u64`.
+    ///
+    /// Nothing is consumed if fewer than `n` bits remain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than 64.
+    #[inline]
+    pub fn read_bits(&mut self, n: u32) -> Result<u64, StreamError> {
+        let value = self.peek_bits(n)?;
+        self.position += n as usize;
+        Ok(value)
+    }
+
+    /// Read the next bit.
+    #[inline]
+    pub fn read_bit(&mut self) -> Result<bool, StreamError> {
+        self.read_bits(1).map(|bit| bit == 1)
+    }
+
+    /// Skip the next `n` bits.
+    ///
+    /// Nothing is skipped if fewer than `n` bits remain.
+    #[inline]
+    pub fn skip(&mut self, n: usize) -> Result<(), StreamError> {
+        if n > self.remaining_bits() {
+            return Err(StreamError::UnexpectedEnd);
+        }
+        self.position += n;
+        Ok(())
+    }
+
+    /// Skip to the next byte boundary. Does nothing when already aligned.
+    #[inline]
+    pub fn align_to_byte(&mut self) {
+        self.position = (self.position + 7) / 8 * 8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_across_bytes() {
+        let bytes = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x11, 0x22];
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(reader.read_bits(4), Ok(0x1));
+        assert_eq!(reader.read_bits(64), Ok(0x23456789abcdef01));
+        assert_eq!(reader.read_bits(0), Ok(0));
+        assert_eq!(reader.read_bits(12), Ok(0x122));
+        assert_eq!(reader.remaining_bits(), 0);
+        assert_eq!(reader.read_bit(), Err(StreamError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_peek_does_not_consume() {
+        let mut reader = BitReader::new(&[0b1010_0000]);
+        assert_eq!(reader.peek_bits(3), Ok(0b101));
+        assert_eq!(reader.peek_bits(3), Ok(0b101));
+        assert_eq!(reader.bit_position(), 0);
+        assert_eq!(reader.peek_bits(9), Err(StreamError::UnexpectedEnd));
+        assert_eq!(reader.read_bits(2), Ok(0b10));
+        assert_eq!(reader.peek_bits(6), Ok(0b100000));
+    }
+
+    #[test]
+    fn test_underflow_consumes_nothing() {
+        let mut reader = BitReader::new(&[0xff, 0x00]);
+        reader.skip(10).unwrap();
+        assert_eq!(reader.read_bits(7), Err(StreamError::UnexpectedEnd));
+        assert_eq!(reader.skip(7), Err(StreamError::UnexpectedEnd));
+        assert_eq!(reader.bit_position(), 10);
+        assert_eq!(reader.read_bits(6), Ok(0));
+    }
+
+    #[test]
+    fn test_align_to_byte() {
+        let mut reader = BitReader::new(&[0xff, 0x80]);
+        reader.align_to_byte();
+        assert_eq!(reader.bit_position(), 0);
+        reader.read_bit().unwrap();
+        assert!(!reader.is_byte_aligned());
+        reader.align_to_byte();
+        assert_eq!(reader.read_bit(), Ok(true));
+        reader.align_to_byte();
+        assert_eq!(reader.remaining_bits(), 0);
+        reader.align_to_byte();
+        assert_eq!(reader.bit_position(), 16);
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            StreamError::UnexpectedEnd.to_string(),
+            "unexpected end of bit stream"
+        );
+        assert_eq!(StreamError::BufferFull.to_string(), "bit buffer is full");
+    }
+}
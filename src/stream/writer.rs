@@ -0,0 +1,235 @@
+use crate::stream::buffer::BitBuffer;
+use crate::stream::error::StreamError;
+
+/// Writes values of up to 64 bits into a [`BitBuffer`], MSB-first.
+///
+/// Writing starts at the first bit of the buffer and overwrites whatever it holds. The first
+/// bit written into a byte clears the rest of that byte, so the buffer need not be zeroed. See
+/// the [module-level documentation](super) for the bit order.
+///
+/// # Examples
+/// ```
+/// use bittersweet::stream::BitWriter;
+/// let mut writer = BitWriter::new(Vec::new());
+/// writer.write_bits(0b110, 3).unwrap();
+/// writer.write_bit(true).unwrap();
+/// assert_eq!(writer.bit_position(), 4);
+/// assert_eq!(writer.into_inner(), vec![0b1101_0000]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BitWriter<B> {
+    buffer: B,
+    position: usize,
+}
+
+impl<B: BitBuffer> BitWriter<B> {
+    /// Create a writer at the start of `buffer`.
+    #[inline]
+    pub fn new(buffer: B) -> Self {
+        BitWriter {
+            buffer,
+            position: 0,
+        }
+    }
+
+    /// Return the number of bits written so far.
+    #[inline]
+    pub fn bit_position(&self) -> usize {
+        self.position
+    }
+
+    /// Return the number of bits left before the buffer is full, or `None` if it grows
+    /// without limit.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::stream::BitWriter;
+    /// let mut buffer = [0_u8; 2];
+    /// let mut writer = BitWriter::new(&mut buffer[..]);
+    /// writer.write_bits(0b101, 3).unwrap();
+    /// assert_eq!(writer.remaining_bits(), Some(13));
+    /// assert_eq!(BitWriter::new(Vec::new()).remaining_bits(), None);
+    /// ```
+    #[inline]
+    pub fn remaining_bits(&self) -> Option<usize> {
+        self.buffer.max_len().map(|len| len * 8 - self.position)
+    }
+
+    /// Return whether the next bit starts a new byte.
+    #[inline]
+    pub fn is_byte_aligned(&self) -> bool {
+        self.position % 8 == 0
+    }
+
+    /// Return the bytes written so far; the unwritten bits of the last byte are zero.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer.as_bytes()[..(self.position + 7) / 8]
+    }
+
+    /// Return the underlying buffer.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.buffer
+    }
+
+    /// Write the low `n` bits of `value`, most significant first. Higher bits are ignored.
+    ///
+    /// Nothing is written if the buffer cannot hold all `n` bits.
+    ///
+    /// # Examples
+    /// ```
+    /// use bittersweet::stream::{BitWriter, StreamError};
+    /// let mut buffer = [0_u8; 1];
+    /// let mut writer = BitWriter::new(&mut buffer[..]);
+    /// assert_eq!(writer.write_bits(0xfff5, 4), Ok(()));
+    /// assert_eq!(writer.write_bits(0, 5), Err(StreamError::BufferFull));
+    /// assert_eq!(writer.bit_position(), 4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than 64.
+    pub fn write_bits(&mut self, value: u64, n: u32) -> Result<(), StreamError> {
+        assert!(n <= 64, "bit width out of range");
+        let end = self.position + n as usize;
+        if !self.buffer.ensure_len((end + 7) / 8) {
+            return Err(StreamError::BufferFull);
+        }
+        let bytes = self.buffer.as_bytes_mut();
+        let mut remaining = n;
+        while remaining > 0 {
+            // Fill the free bits of the current byte with the next most significant bits.
+            let offset = (self.position % 8) as u32;
+            let width = core::cmp::min(8 - offset, remaining);
+            let field = ((value >> (remaining - width)) & ((1 << width) - 1)) as u8;
+            let byte = &mut bytes[self.position / 8];
+            // Entering a byte clears it, so its unwritten bits stay zero.
+            if offset == 0 {
+                *byte = 0;
+            }
+            *byte |= field << (8 - offset - width);
+            self.position += width as usize;
+            remaining -= width;
+        }
+        Ok(())
+    }
+
+    /// Write a single bit.
+    #[inline]
+    pub fn write_bit(&mut self, bit: bool) -> Result<(), StreamError> {
+        self.write_bits(bit as u64, 1)
+    }
+
+    /// Pad with zero bits up to the next byte boundary. Does nothing when already aligned.
+    #[inline]
+    pub fn align_to_byte(&mut self) -> Result<(), StreamError> {
+        let padding = (8 - self.position % 8) % 8;
+        self.write_bits(0, padding as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::BitReader;
+    use crate::test_util::xorshift64;
+
+    #[test]
+    fn test_round_trip() {
+        let mut state = 0x9e3779b97f4a7c15;
+        let mut fields = Vec::new();
+        let mut writer = BitWriter::new(Vec::new());
+        for _ in 0..1000 {
+            let n = (xorshift64(&mut state) % 65) as u32;
+            let value = xorshift64(&mut state);
+            writer.write_bits(value, n).unwrap();
+            fields.push((value & u64::MAX.checked_shr(64 - n).unwrap_or(0), n));
+        }
+        let total: usize = fields.iter().map(|(_, n)| *n as usize).sum();
+        assert_eq!(writer.bit_position(), total);
+        let bytes = writer.into_inner();
+        assert_eq!(bytes.len(), (total + 7) / 8);
+
+        let mut reader = BitReader::new(&bytes);
+        for &(value, n) in fields.iter() {
+            assert_eq!(reader.read_bits(n), Ok(value));
+        }
+        assert!(reader.remaining_bits() < 8);
+    }
+
+    #[test]
+    fn test_bit_order_matches_bit_repr() {
+        use crate::bitline::Bitline;
+        let value = 0b1011_0010_0111_u16;
+        let mut writer = BitWriter::new(Vec::new());
+        writer.write_bits(value as u64, 16).unwrap();
+        let written: String = writer
+            .as_bytes()
+            .iter()
+            .map(|byte| byte.bit_repr())
+            .collect();
+        assert_eq!(written, value.bit_repr());
+    }
+
+    #[test]
+    fn test_overwrites_existing_bits() {
+        let mut buffer = [0xff_u8; 3];
+        let mut writer = BitWriter::new(&mut buffer[..]);
+        writer.write_bit(false).unwrap();
+        assert_eq!(writer.as_bytes(), [0]);
+        writer.write_bits(0b101, 3).unwrap();
+        writer.write_bits(0, 6).unwrap();
+        assert_eq!(writer.as_bytes(), [0b0101_0000, 0]);
+        assert_eq!(buffer, [0b0101_0000, 0, 0xff]);
+    }
+
+    #[test]
+    fn test_remaining_bits() {
+        let mut buffer = [0_u8; 3];
+        let mut writer = BitWriter::new(&mut buffer[..]);
+        assert_eq!(writer.remaining_bits(), Some(24));
+        writer.write_bits(0, 20).unwrap();
+        assert_eq!(writer.remaining_bits(), Some(4));
+        assert_eq!(writer.write_bits(0, 5), Err(StreamError::BufferFull));
+        writer.write_bits(0, 4).unwrap();
+        assert_eq!(writer.remaining_bits(), Some(0));
+
+        let mut writer = BitWriter::new(Vec::new());
+        writer.write_bits(0, 64).unwrap();
+        assert_eq!(writer.remaining_bits(), None);
+    }
+
+    #[test]
+    fn test_buffer_full() {
+        let mut buffer = [0_u8; 2];
+        let mut writer = BitWriter::new(&mut buffer[..]);
+        writer.write_bits(0x7f, 7).unwrap();
+        assert_eq!(writer.write_bits(0, 10), Err(StreamError::BufferFull));
+        assert_eq!(writer.bit_position(), 7);
+        writer.write_bits(0x1ff, 9).unwrap();
+        assert_eq!(writer.write_bit(false), Err(StreamError::BufferFull));
+        assert_eq!(writer.align_to_byte(), Ok(()));
+        assert_eq!(buffer, [0xff, 0xff]);
+    }
+
+    #[test]
+    fn test_align_to_byte() {
+        let mut writer = BitWriter::new(Vec::new());
+        writer.align_to_byte().unwrap();
+        assert_eq!(writer.bit_position(), 0);
+        writer.write_bit(true).unwrap();
+        assert!(!writer.is_byte_aligned());
+        writer.align_to_byte().unwrap();
+        assert!(writer.is_byte_aligned());
+        writer.write_bits(0b11, 2).unwrap();
+        assert_eq!(writer.into_inner(), vec![0b1000_0000, 0b1100_0000]);
+    }
+
+    #[test]
+    #[should_panic(expected = "bit width out of range")]
+    fn test_write_bits_too_wide() {
+        let mut writer = BitWriter::new(Vec::new());
+        let _ = writer.write_bits(0, 65);
+    }
+}
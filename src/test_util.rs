@@ -0,0 +1,55 @@
+//! Fixtures shared by the unit tests.
+
+/// Advance `state` by one xorshift64 step and return the new state.
+///
+/// A zero `state` stays zero, so seed it with a nonzero constant.
+pub(crate) fn xorshift64(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// An unsigned integer that holds one row of a pseudo-random grid.
+pub(crate) trait GridRow: Copy + Default {
+    /// Return the low bits of `word`.
+    fn truncate(word: u64) -> Self;
+}
+
+macro_rules! impl_GridRow {
+    ($T:ty) => {
+        impl GridRow for $T {
+            fn truncate(word: u64) -> Self {
+                word as $T
+            }
+        }
+    };
+}
+
+impl_GridRow!(u8);
+impl_GridRow!(u16);
+impl_GridRow!(u32);
+impl_GridRow!(u64);
+
+/// Return `N` pseudo-random rows, each the AND of `steps` xorshift64 steps, so that about one
+/// cell in `2^steps` is set. Complement the rows for dense grids.
+pub(crate) fn pseudo_random_grid<T: GridRow, const N: usize>(
+    state: &mut u64,
+    steps: u32,
+) -> [T; N] {
+    let mut grid = [T::default(); N];
+    for row in grid.iter_mut() {
+        let word = (1..steps).fold(xorshift64(state), |word, _| word & xorshift64(state));
+        *row = T::truncate(word);
+    }
+    grid
+}
+
+/// Return `count` boards built by `from_rows` from pseudo-random grids, with a fixed seed.
+pub(crate) fn pseudo_random_boards<T: GridRow, B, const N: usize>(
+    count: usize,
+    from_rows: fn([T; N]) -> B,
+) -> impl Iterator<Item = B> {
+    let mut state = 0x9e3779b97f4a7c15;
+    (0..count).map(move |_| from_rows(pseudo_random_grid(&mut state, 1)))
+}
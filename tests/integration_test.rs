@@ -101,3 +101,16 @@ fn matrix_transpose8x8_u64_matches_be_byte_rows() {
         );
     }
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn matrix_transpose_blocked_matches_transpose64x64() {
+    let mut rows = [0_u64; 64];
+    for (i, row) in rows.iter_mut().enumerate() {
+        *row = 0x0123456789abcdef_u64.rotate_left(i as u32);
+    }
+    assert_eq!(
+        matrix::transpose_blocked_vec(&rows, 64, 64),
+        matrix::transpose64x64(rows).to_vec()
+    );
+}